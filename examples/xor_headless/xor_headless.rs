@@ -0,0 +1,40 @@
+use std::time::Duration;
+use neatlib::{neat::trainer::{configuration::Configuration, node_conf::NodeConf, neat_trainer::NeatTrainer, stop_criteria::StopCriteria, fitness::fitness_resolver::FitnessResolver}, activation_functions::ActivationFunction, phenome::Phenome};
+pub fn main(){
+    let success_threshold = 3.99;
+
+    let calculate_fitness = | phenotype: &dyn Phenome, fitness_resolver: &mut FitnessResolver | {
+        let xor_results= &[[0.0, 0.0, 0.0], [0.0, 1.0, 1.0], [1.0, 0.0, 1.0], [1.0, 1.0, 0.0]];
+
+        let mut component_id = 0;
+        for test in xor_results {
+            let result = phenotype.activate(&vec![test[0], test[1]])[0];
+            let expected_result = test[2];
+            fitness_resolver.add_objective_fitness_component_with_novelty(component_id, 1.0, expected_result, result, 100);
+            component_id += 1;
+        }
+    };
+
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), success_threshold)
+    .target_species(20)
+    .mutation_node_available_activation_functions(ActivationFunction::TANH)
+    .genome_minimal_genes_to_connect_ratio(1.0)
+    .speciation_drop_species_no_improvement_generations(20)
+    .speciation_preserve_elite(true)
+    .print_summary_interval(None)
+    .population_size(1000);
+
+    let stop_criteria = StopCriteria::new()
+    .max_generations(Some(1000))
+    .max_duration(Some(Duration::from_secs(120)))
+    .stagnant_generations(Some(200));
+
+    let report = NeatTrainer::new(configuration).run(calculate_fitness, stop_criteria);
+
+    println!("stopped: {:?}\tgenerations: {}\telapsed: {:.2?}\tbest fitness: {:.4}",
+        report.stop_reason,
+        report.generations,
+        report.elapsed,
+        report.champion_fitness().unwrap_or_default()
+    );
+}
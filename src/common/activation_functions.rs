@@ -1,7 +1,7 @@
 use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
 
-use super::random::Random;
+use super::seeded_random::SeededRandom;
 
 bitflags! {
     #[derive(Serialize, Deserialize)]
@@ -22,6 +22,23 @@ bitflags! {
         const INVERT                = 0b00000000000000000010000000000000;
     }
 }
+const NAMES: [&str; 14] = [
+    "sigmoid",
+    "relu",
+    "tanh",
+    "binary",
+    "linear_clip",
+    "leaky_relu",
+    "sine",
+    "bipolar_sigmoid",
+    "gaussian",
+    "band",
+    "binary_sin",
+    "binary_gaussian",
+    "linear_clip_gaussian",
+    "invert"
+];
+
 impl ActivationFunction{
     pub fn get_all() -> [ActivationFunction; 14]{
         [
@@ -53,7 +70,7 @@ impl ActivationFunction{
             ActivationFunction::LINEAR_CLIP_GAUSSIAN|
             ActivationFunction::INVERT
       }
-    pub fn get_random(&self) -> Self{
+    pub fn get_random(&self, random: &mut SeededRandom) -> Self{
         let mut available: Vec<ActivationFunction> = vec![];
 
         for activation in ActivationFunction::get_all(){
@@ -61,8 +78,24 @@ impl ActivationFunction{
                 available.push(activation);
             }
         }
-        let random = Random::gen_range_usize(0 ,available.len());
-        available[random]
+        let index = random.gen_range_usize(0 ,available.len());
+        available[index]
+    }
+    /* names of the set flags joined with |, empty when no flag is set */
+    pub fn get_names(&self) -> String{
+        ActivationFunction::get_all().iter().zip(NAMES)
+            .filter(|(activation, _)| self.contains(**activation))
+            .map(|(_, name)| name)
+            .collect::<Vec<&str>>()
+            .join("|")
+    }
+    pub fn from_names(names: &str) -> Option<Self>{
+        let mut result = ActivationFunction::empty();
+        for name in names.split('|').map(|n| n.trim()).filter(|n| !n.is_empty()){
+            let index = NAMES.iter().position(|n| n.eq_ignore_ascii_case(name))?;
+            result |= ActivationFunction::get_all()[index];
+        }
+        Some(result)
     }
 }
 impl Default for ActivationFunction {
@@ -74,9 +107,19 @@ impl Default for ActivationFunction {
 #[test]
 fn get_random_works_one_million_times() {
     let a : ActivationFunction = ActivationFunction::SIGMOID | ActivationFunction::RELU | ActivationFunction::TANH | ActivationFunction::BINARY;
+    let mut random = SeededRandom::new(None);
     for _ in 0..1000_000{
-        let result = a.get_random();
+        let result = a.get_random(&mut random);
         let is_valid = result == ActivationFunction::SIGMOID || result == ActivationFunction::RELU || result == ActivationFunction::TANH || result == ActivationFunction::BINARY;
         assert!(is_valid);
     }
-}
\ No newline at end of file
+}
+#[test]
+fn names_round_trip() {
+    let combined = ActivationFunction::SIGMOID | ActivationFunction::LINEAR_CLIP_GAUSSIAN;
+
+    assert_eq!(combined.get_names(), "sigmoid|linear_clip_gaussian");
+    assert_eq!(ActivationFunction::from_names(&combined.get_names()), Some(combined));
+    assert_eq!(ActivationFunction::from_names(""), Some(ActivationFunction::empty()));
+    assert_eq!(ActivationFunction::from_names("tanh|swish"), None);
+}
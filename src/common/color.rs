@@ -0,0 +1,28 @@
+use rand::Rng;
+use rand_distr::StandardNormal;
+use serde::{Serialize, Deserialize};
+use super::seeded_random::SeededRandom;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Serialize, Deserialize)]
+pub struct Rgb{
+    pub red: f32,
+    pub green: f32,
+    pub blue: f32
+}
+
+impl Rgb{
+    pub const GRAY: Rgb = Rgb{ red: 0.5, green: 0.5, blue: 0.5 };
+
+    pub fn new(red: f32, green: f32, blue: f32) -> Self{
+        Self { red, green, blue }
+    }
+    pub fn random(random: &mut SeededRandom) -> Self{
+        Self::new(random.gen_range(0.0..1.0), random.gen_range(0.0..1.0), random.gen_range(0.0..1.0))
+    }
+    pub fn drift(&mut self, random: &mut SeededRandom, scale: f32){
+        self.red = (self.red + random.sample::<f32, _>(StandardNormal) * scale).clamp(0.0, 1.0);
+        self.green = (self.green + random.sample::<f32, _>(StandardNormal) * scale).clamp(0.0, 1.0);
+        self.blue = (self.blue + random.sample::<f32, _>(StandardNormal) * scale).clamp(0.0, 1.0);
+    }
+}
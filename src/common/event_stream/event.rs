@@ -1,8 +1,15 @@
 use serde::{Serialize, Deserialize};
 use bitflags::bitflags;
-pub type PropertyType = gluesql::core::data::Value;
 
-use crate::{neat::{trainer::run_context::RunContext, genome::neat::NeatGenome, population::speciation::{species_member::SpeciesMember}}, activation_functions::ActivationFunction, common::NeatFloat};
+use crate::{neat::{trainer::run_context::RunContext, genome::genome::Genome, population::speciation::{species_member::SpeciesMember}}, activation_functions::ActivationFunction, common::NeatFloat};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum PropertyType{
+    Uuid(u128),
+    I32(i32),
+    F64(f64),
+    Str(String)
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Event{
@@ -50,7 +57,7 @@ impl EventType {
 }
 
 impl Event{
-    pub fn mutation_node_add(run_context: &RunContext, genome_id: &uuid::Uuid, node_number: i32, activation_function: ActivationFunction ,connection_in: i32, connection_out: i32) -> Self{
+    pub fn mutation_node_add<T: Genome>(run_context: &RunContext<T>, genome_id: &uuid::Uuid, node_number: i32, activation_function: ActivationFunction ,connection_in: i32, connection_out: i32) -> Self{
         
         Event{ 
             event_type: EventType::MUTATION_NODE_ADD, 
@@ -64,7 +71,7 @@ impl Event{
             ] 
         }
     }
-    pub fn mutation_node_delete(run_context: &RunContext, genome_id: &uuid::Uuid, node_number: i32) -> Self{
+    pub fn mutation_node_delete<T: Genome>(run_context: &RunContext<T>, genome_id: &uuid::Uuid, node_number: i32) -> Self{
         Event{ 
             event_type: EventType::MUTATION_NODE_DELETE, 
             generation: run_context.current_generation, 
@@ -73,7 +80,7 @@ impl Event{
                 ("node".to_string(), PropertyType::I32(node_number)  )
             ] }
     }
-    pub fn mutation_connection_add(run_context: &RunContext, genome_id: &uuid::Uuid, connection_in: i32, connection_out: i32) -> Self{
+    pub fn mutation_connection_add<T: Genome>(run_context: &RunContext<T>, genome_id: &uuid::Uuid, connection_in: i32, connection_out: i32) -> Self{
         Event{ 
             event_type: EventType::MUTATION_CONNECTION_ADD, 
             generation: run_context.current_generation, 
@@ -83,7 +90,7 @@ impl Event{
                 ("connect_out".to_string(), PropertyType::I32(connection_out) )
             ] }
     }
-    pub fn mutation_connection_delete(run_context: &RunContext, genome_id: &uuid::Uuid, connection_in: i32, connection_out: i32) -> Self{
+    pub fn mutation_connection_delete<T: Genome>(run_context: &RunContext<T>, genome_id: &uuid::Uuid, connection_in: i32, connection_out: i32) -> Self{
         Event{ 
             event_type: EventType::MUTATION_CONNECTION_DELETE, 
             generation: run_context.current_generation, 
@@ -93,7 +100,7 @@ impl Event{
                 ("connect_out".to_string(), PropertyType::I32(connection_out) )
             ] }
     }
-    pub fn mutation_node_bias_change(run_context: &RunContext, genome_id: &uuid::Uuid, node_number: i32, new_bias: NeatFloat) -> Self{
+    pub fn mutation_node_bias_change<T: Genome>(run_context: &RunContext<T>, genome_id: &uuid::Uuid, node_number: i32, new_bias: NeatFloat) -> Self{
         Event{ 
             event_type: EventType::MUTATION_NODE_CHANGE_BIAS, 
             generation: run_context.current_generation, 
@@ -103,7 +110,7 @@ impl Event{
                 ("new_bias".to_string(), PropertyType::F64(new_bias as f64) )
             ] }
     }
-    pub fn mutation_connection_weight_change(run_context: &RunContext, genome_id: &uuid::Uuid, connection_in: i32, connection_out: i32, new_weight: NeatFloat, old_weight: NeatFloat) -> Self{
+    pub fn mutation_connection_weight_change<T: Genome>(run_context: &RunContext<T>, genome_id: &uuid::Uuid, connection_in: i32, connection_out: i32, new_weight: NeatFloat, old_weight: NeatFloat) -> Self{
         Event{ 
             event_type: EventType::MUTATION_CONNECTION_WEIGHT_CHANGE, 
             generation: run_context.current_generation, 
@@ -115,7 +122,7 @@ impl Event{
                 ("old_weight".to_string(), PropertyType::F64(old_weight as f64) )
             ] }
     }
-    pub fn mutation_connection_weight_replace(run_context: &RunContext, genome_id: &uuid::Uuid, connection_in: i32, connection_out: i32, new_weight: NeatFloat, old_weight: NeatFloat) -> Self{
+    pub fn mutation_connection_weight_replace<T: Genome>(run_context: &RunContext<T>, genome_id: &uuid::Uuid, connection_in: i32, connection_out: i32, new_weight: NeatFloat, old_weight: NeatFloat) -> Self{
         Event{ 
             event_type: EventType::MUTATION_CONNECTION_WEIGHT_REPLACE, 
             generation: run_context.current_generation, 
@@ -127,7 +134,7 @@ impl Event{
                 ("old_weight".to_string(), PropertyType::F64(old_weight as f64) )
             ] }
     }
-    pub fn mutation_connection_disabled(run_context: &RunContext, genome_id: &uuid::Uuid, connection_in: i32, connection_out: i32) -> Self{
+    pub fn mutation_connection_disabled<T: Genome>(run_context: &RunContext<T>, genome_id: &uuid::Uuid, connection_in: i32, connection_out: i32) -> Self{
         Event{ 
             event_type: EventType::MUTATION_CONNECTION_DISABLED, 
             generation: run_context.current_generation, 
@@ -137,28 +144,28 @@ impl Event{
                 ("connect_out".to_string(), PropertyType::I32(connection_out) ),
             ] }
     }
-    pub fn speciation_reproduce(current_generation: u32, best_genome: &NeatGenome, other_genome: &NeatGenome, new_genome: &NeatGenome, species_id: &uuid::Uuid) -> Self{
+    pub fn speciation_reproduce<T: Genome>(current_generation: u32, best_genome: &T, other_genome: &T, new_genome: &T, species_id: &uuid::Uuid) -> Self{
         Event{ 
             event_type: EventType::SPECIATION_REPRODUCE, 
             generation: current_generation, 
             additional_properties: vec![
-                ("best_genome_id".to_string(), PropertyType::Uuid(best_genome.id.as_u128()) ),
-                ("other_genome_id".to_string(), PropertyType::Uuid(other_genome.id.as_u128()) ),
-                ("new_genome".to_string(), PropertyType::Uuid(new_genome.id.as_u128()) ),
+                ("best_genome_id".to_string(), PropertyType::Uuid(best_genome.get_id().as_u128()) ),
+                ("other_genome_id".to_string(), PropertyType::Uuid(other_genome.get_id().as_u128()) ),
+                ("new_genome".to_string(), PropertyType::Uuid(new_genome.get_id().as_u128()) ),
                 ("species_id".to_string(), PropertyType::Uuid(species_id.as_u128()) ),
             ] }
     }  
-    pub fn speciation_reproduce_cross_species(current_generation: u32, best_genome: &NeatGenome, other_genome: &NeatGenome, new_genome: &NeatGenome, species_id: &uuid::Uuid) -> Self{
+    pub fn speciation_reproduce_cross_species<T: Genome>(current_generation: u32, best_genome: &T, other_genome: &T, new_genome: &T, species_id: &uuid::Uuid) -> Self{
         Event{ 
             event_type: EventType::SPECIATION_REPRODUCE_CROSS_SPECIES, 
             generation: current_generation, 
             additional_properties: vec![
-                ("best_genome_id".to_string(), PropertyType::Uuid(best_genome.id.as_u128()) ),
-                ("best_genome_complexity".to_string(), PropertyType::F64(best_genome.genes.get_complexity() as f64) ),
-                ("other_genome_id".to_string(), PropertyType::Uuid(other_genome.id.as_u128()) ),
-                ("other_genome_complexity".to_string(), PropertyType::F64(other_genome.genes.get_complexity() as f64) ),
-                ("new_genome".to_string(), PropertyType::Uuid(new_genome.id.as_u128()) ),
-                ("new_genome_complexity".to_string(), PropertyType::F64(new_genome.genes.get_complexity() as f64) ),
+                ("best_genome_id".to_string(), PropertyType::Uuid(best_genome.get_id().as_u128()) ),
+                ("best_genome_complexity".to_string(), PropertyType::F64(best_genome.get_complexity() as f64) ),
+                ("other_genome_id".to_string(), PropertyType::Uuid(other_genome.get_id().as_u128()) ),
+                ("other_genome_complexity".to_string(), PropertyType::F64(other_genome.get_complexity() as f64) ),
+                ("new_genome".to_string(), PropertyType::Uuid(new_genome.get_id().as_u128()) ),
+                ("new_genome_complexity".to_string(), PropertyType::F64(new_genome.get_complexity() as f64) ),
                 ("species_id".to_string(), PropertyType::Uuid(species_id.as_u128()) ),
             ] }
     } 
@@ -1,5 +1,7 @@
 use crate::neat::trainer::configuration::Configuration;
-use super::{event::{Event, EventType}, listeners::{listeners::Listeners, console_listener::ConsoleEventListener, sql_listener::SqlRepositoryEventListener}};
+use super::{event::{Event, EventType}, listeners::{listeners::Listeners, console_listener::ConsoleEventListener}};
+#[cfg(feature = "sql")]
+use super::listeners::sql_listener::SqlRepositoryEventListener;
 
 pub struct EventRecorder;
 
@@ -10,6 +12,7 @@ impl EventRecorder{
                 if subscription.listeners & Listeners::CONSOLE == Listeners::CONSOLE{
                     ConsoleEventListener::process(event);
                 }
+                #[cfg(feature = "sql")]
                 if subscription.listeners & Listeners::SQL == Listeners::SQL{
                     SqlRepositoryEventListener::process(event);
                 }
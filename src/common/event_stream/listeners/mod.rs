@@ -1,3 +1,4 @@
 pub mod console_listener;
 pub mod listeners;
+#[cfg(feature = "sql")]
 pub mod sql_listener;
\ No newline at end of file
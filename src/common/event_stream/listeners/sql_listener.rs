@@ -2,7 +2,7 @@ use gluesql::prelude::*;
 use std::sync::Mutex;
 use array_tool::vec::Join;
 
-use crate::{common::event_stream::event::{Event}};
+use crate::{common::event_stream::event::{Event, PropertyType}};
 
 lazy_static! {
     pub static ref REPOSITORY: Mutex<SqlRepository> =  Mutex::new(SqlRepository::new());
@@ -25,7 +25,7 @@ impl SqlRepository{
     }
     pub fn insert_event(&mut self, event: &Event){
         let table_name = format!("{:?}", event.event_type);
-        let columns = event.additional_properties.clone();
+        let columns = event.additional_properties.iter().map(|(name, value)| (name.clone(), Value::from(value))).collect::<Vec<(String, Value)>>();
         if !self.created_tables.contains(&table_name){
             let mut column_strings = Vec::new();
             for (name, datatype) in &columns{
@@ -89,6 +89,16 @@ impl SqlRepository{
     }
 }
 
+impl From<&PropertyType> for Value{
+    fn from(property: &PropertyType) -> Self {
+        match property {
+            PropertyType::Uuid(value) => Value::Uuid(*value),
+            PropertyType::I32(value) => Value::I32(*value),
+            PropertyType::F64(value) => Value::F64(*value),
+            PropertyType::Str(value) => Value::Str(value.clone()),
+        }
+    }
+}
 
 pub struct SqlRepositoryEventListener;
 
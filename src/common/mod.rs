@@ -1,10 +1,14 @@
 pub mod activation_functions;
 pub mod node_kind;
 pub mod network_definition;
+pub mod network_file;
+pub mod network_file_error;
 pub mod network_definition_node_layer_resolver;
 pub type NodePosition = (types::NeatFloat,types::NeatFloat,types::NeatFloat);
 pub mod random;
+pub mod seeded_random;
 pub mod types;
 pub use types::NeatFloat as NeatFloat;
 pub mod event_stream;
-pub mod cpu_limiter;
\ No newline at end of file
+pub mod cpu_limiter;
+pub mod color;
\ No newline at end of file
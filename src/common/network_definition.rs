@@ -1,3 +1,4 @@
+use std::{collections::hash_map::DefaultHasher, hash::{Hash, Hasher}};
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 use crate::{node_kind::NodeKind, activation_functions::ActivationFunction, common::NodePosition};
@@ -10,6 +11,25 @@ pub trait NetworkDefinition{
     fn get_all_connections(&self) -> Vec<NetworkDefinitionConnection>;
     fn get_feed_connections_for_node(&self, node_identity: i32) -> Vec<NetworkDefinitionConnection>;
     fn get_output_nodes_count(&self) -> u32;
+    /* hash of everything that affects activation, two networks with the same hash compute the same outputs */
+    fn get_structure_hash(&self) -> u64{
+        let mut hasher = DefaultHasher::new();
+        for node in self.get_all_nodes(){
+            node.identity.hash(&mut hasher);
+            node.kind.hash(&mut hasher);
+            node.activation_function.hash(&mut hasher);
+            node.bias.to_bits().hash(&mut hasher);
+            node.input_multiplier.to_bits().hash(&mut hasher);
+        }
+        for connection in self.get_all_connections(){
+            connection.connection_in.hash(&mut hasher);
+            connection.connection_out.hash(&mut hasher);
+            connection.is_enabled.hash(&mut hasher);
+            connection.is_recurrent.hash(&mut hasher);
+            connection.weight.to_bits().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,247 @@
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::{activation_functions::ActivationFunction, node_kind::NodeKind, cpu_phenome::CpuPhenome, neat::{genome::genome::Genome, population::GenerationMember}};
+use super::{NeatFloat, network_definition::{NetworkDefinition, NetworkDefinitionNode, NetworkDefinitionConnection}, network_file_error::NetworkFileError};
+
+pub const NETWORK_FILE_FORMAT: &str = "neatlib-network";
+pub const NETWORK_FILE_VERSION: u32 = 1;
+
+/*
+A standalone description of a trained network that can be activated without the trainer.
+The file is json:
+
+    {
+        "format": "neatlib-network",
+        "version": 1,
+        "id": "<uuid of the genome>",
+        "sensors": [1, 2],
+        "outputs": [3],
+        "nodes": [
+            { "id": 1, "kind": "Sensor", "activation": "", "bias": 1.0, "input_multiplier": 1.0 },
+            { "id": 3, "kind": "Output", "activation": "tanh", "bias": 0.5, "input_multiplier": 1.0 }
+        ],
+        "connections": [
+            { "from": 1, "to": 3, "weight": 0.8, "recurrent": false }
+        ]
+    }
+
+sensors and outputs give the order of the values passed to and returned from activate, they
+must match the order of the sensor and output nodes in nodes. activation is a | separated list
+of activation function names (see ActivationFunction::get_names). Only enabled connections are
+written.
+*/
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkFile{
+    pub format: String,
+    pub version: u32,
+    pub id: Uuid,
+    pub sensors: Vec<i32>,
+    pub outputs: Vec<i32>,
+    pub nodes: Vec<NetworkFileNode>,
+    pub connections: Vec<NetworkFileConnection>
+}
+
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkFileNode{
+    pub id: i32,
+    pub kind: NodeKind,
+    pub activation: String,
+    pub bias: NeatFloat,
+    pub input_multiplier: NeatFloat
+}
+
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkFileConnection{
+    pub from: i32,
+    pub to: i32,
+    pub weight: NeatFloat,
+    pub recurrent: bool
+}
+
+impl NetworkFile{
+    pub fn from_network<S>(network: &S) -> Self where S: NetworkDefinition{
+        let all_nodes = network.get_all_nodes();
+        let identities_of = |kind: NodeKind| all_nodes.iter().filter(|n| n.kind == kind).map(|n| n.identity).collect::<Vec<i32>>();
+
+        Self{
+            format: NETWORK_FILE_FORMAT.to_string(),
+            version: NETWORK_FILE_VERSION,
+            id: network.get_network_identifier(),
+            sensors: identities_of(NodeKind::Sensor),
+            outputs: identities_of(NodeKind::Output),
+            nodes: all_nodes.iter().map(|n| NetworkFileNode{
+                id: n.identity,
+                kind: n.kind.clone(),
+                activation: n.activation_function.get_names(),
+                bias: n.bias,
+                input_multiplier: n.input_multiplier
+            }).collect(),
+            connections: network.get_all_connections().iter().filter(|c| c.is_enabled).map(|c| NetworkFileConnection{
+                from: c.connection_in,
+                to: c.connection_out,
+                weight: c.weight,
+                recurrent: c.is_recurrent
+            }).collect()
+        }
+    }
+    pub fn from_member<T>(member: &GenerationMember<T>) -> Self where T: Genome + NetworkDefinition{
+        Self::from_network(&member.genome)
+    }
+    pub fn from_json_str(json: &str) -> Result<Self, NetworkFileError>{
+        let network_file: NetworkFile = serde_json::from_str(json).map_err(|e| NetworkFileError::Parse(e.to_string()))?;
+        network_file.validate()?;
+        Ok(network_file)
+    }
+    pub fn to_json_string(&self) -> String{
+        serde_json::to_string_pretty(self).unwrap()
+    }
+    pub fn load<P>(path: P) -> Result<Self, NetworkFileError> where P: AsRef<Path>{
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|e| NetworkFileError::Read { path: path.display().to_string(), message: e.to_string() })?;
+        Self::from_json_str(&json)
+    }
+    pub fn save<P>(&self, path: P) -> Result<(), NetworkFileError> where P: AsRef<Path>{
+        let path = path.as_ref();
+        std::fs::write(path, self.to_json_string()).map_err(|e| NetworkFileError::Write { path: path.display().to_string(), message: e.to_string() })
+    }
+    pub fn validate(&self) -> Result<(), NetworkFileError>{
+        if self.format != NETWORK_FILE_FORMAT{
+            return Err(NetworkFileError::UnsupportedFormat(self.format.clone()));
+        }
+        if self.version > NETWORK_FILE_VERSION{
+            return Err(NetworkFileError::UnsupportedVersion { version: self.version, current_version: NETWORK_FILE_VERSION });
+        }
+        for node in &self.nodes{
+            if ActivationFunction::from_names(&node.activation).is_none(){
+                return Err(NetworkFileError::UnknownActivationFunction(node.activation.clone()));
+            }
+        }
+        for connection in &self.connections{
+            for id in [connection.from, connection.to]{
+                if !self.nodes.iter().any(|n| n.id == id){
+                    return Err(NetworkFileError::UnknownNode(id));
+                }
+            }
+        }
+        let identities_of = |kind: NodeKind| self.nodes.iter().filter(|n| n.kind == kind).map(|n| n.id).collect::<Vec<i32>>();
+        if identities_of(NodeKind::Sensor) != self.sensors || identities_of(NodeKind::Output) != self.outputs{
+            return Err(NetworkFileError::NodeOrderMismatch);
+        }
+        Ok(())
+    }
+    fn to_definition_node(&self, node_position: usize) -> NetworkDefinitionNode{
+        let node = &self.nodes[node_position];
+        NetworkDefinitionNode{
+            node_position,
+            identity: node.id,
+            kind: node.kind.clone(),
+            activation_function: ActivationFunction::from_names(&node.activation).unwrap(),
+            bias: node.bias,
+            input_multiplier: node.input_multiplier,
+            position: None
+        }
+    }
+    fn to_definition_connection(connection: &NetworkFileConnection) -> NetworkDefinitionConnection{
+        NetworkDefinitionConnection{
+            connection_in: connection.from,
+            connection_out: connection.to,
+            is_enabled: true,
+            is_recurrent: connection.recurrent,
+            weight: connection.weight
+        }
+    }
+}
+
+impl NetworkDefinition for NetworkFile{
+    fn get_network_identifier(&self) -> Uuid {
+        self.id
+    }
+
+    fn get_node(&self, node_identity: i32) -> NetworkDefinitionNode {
+        let node_position = self.nodes.iter().position(|n| n.id == node_identity).unwrap();
+        self.to_definition_node(node_position)
+    }
+
+    fn get_all_nodes(&self) -> Vec<NetworkDefinitionNode> {
+        (0..self.nodes.len()).map(|i| self.to_definition_node(i)).collect()
+    }
+
+    fn get_nodes_len(&self) -> u32 {
+        self.nodes.len() as u32
+    }
+
+    fn get_all_connections(&self) -> Vec<NetworkDefinitionConnection> {
+        self.connections.iter().map(Self::to_definition_connection).collect()
+    }
+
+    fn get_feed_connections_for_node(&self, node_identity: i32) -> Vec<NetworkDefinitionConnection> {
+        self.connections.iter().filter(|c| c.to == node_identity).map(Self::to_definition_connection).collect()
+    }
+
+    fn get_output_nodes_count(&self) -> u32 {
+        self.outputs.len() as u32
+    }
+}
+
+impl CpuPhenome{
+    pub fn from_network_file<P>(path: P) -> Result<Self, NetworkFileError> where P: AsRef<Path>{
+        Ok(CpuPhenome::from_network_schema(&NetworkFile::load(path)?))
+    }
+}
+
+#[cfg(test)]
+fn trained_champion() -> GenerationMember<crate::neat::genome::neat::NeatGenome>{
+    use crate::{neat::trainer::{configuration::Configuration, node_conf::NodeConf, neat_trainer::NeatTrainer, stop_criteria::StopCriteria, fitness::fitness_resolver::FitnessResolver}, phenome::Phenome};
+
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(100)
+        .target_species(5)
+        .mutation_node_available_activation_functions(ActivationFunction::TANH | ActivationFunction::SIGMOID)
+        .seed(Some(5));
+    let fitness = |phenotype: &dyn Phenome, fitness_resolver: &mut FitnessResolver| {
+        for (component_id, test) in [[0.0, 0.0, 0.0], [0.0, 1.0, 1.0], [1.0, 0.0, 1.0], [1.0, 1.0, 0.0]].iter().enumerate(){
+            let result = phenotype.activate(&vec![test[0], test[1]])[0];
+            fitness_resolver.add_objective_fitness_component(component_id as u32, 1.0, test[2], result);
+        }
+    };
+    NeatTrainer::new(configuration).run(fitness, StopCriteria::new().max_generations(Some(20))).champion.unwrap()
+}
+
+#[test]
+fn exported_champion_activates_like_the_genome() {
+    use crate::phenome::Phenome;
+
+    let champion = trained_champion();
+    let path = std::env::temp_dir().join(format!("neatlib-champion-{}.json", std::process::id()));
+    NetworkFile::from_member(&champion).save(&path).unwrap();
+
+    let from_genome = CpuPhenome::from_network_schema(&champion.genome);
+    let from_file = CpuPhenome::from_network_file(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(from_file.id, champion.genome.get_id());
+    for inputs in [[0.0, 0.0], [0.0, 1.0], [1.0, 0.0], [1.0, 1.0], [0.3, -0.7]]{
+        assert_eq!(from_file.activate(&inputs.to_vec()), from_genome.activate(&inputs.to_vec()));
+    }
+}
+
+#[test]
+fn invalid_network_files_are_rejected() {
+    let network_file = NetworkFile::from_member(&trained_champion());
+
+    let mut unknown_activation = network_file.clone();
+    unknown_activation.nodes[0].activation = "swish".to_string();
+    let mut newer_version = network_file.clone();
+    newer_version.version = NETWORK_FILE_VERSION + 1;
+    let mut reordered_sensors = network_file.clone();
+    reordered_sensors.sensors.reverse();
+
+    assert_eq!(NetworkFile::from_json_str(&network_file.to_json_string()), Ok(network_file));
+    assert_eq!(NetworkFile::from_json_str(&unknown_activation.to_json_string()), Err(NetworkFileError::UnknownActivationFunction("swish".to_string())));
+    assert!(matches!(NetworkFile::from_json_str(&newer_version.to_json_string()), Err(NetworkFileError::UnsupportedVersion { .. })));
+    assert_eq!(NetworkFile::from_json_str(&reordered_sensors.to_json_string()), Err(NetworkFileError::NodeOrderMismatch));
+}
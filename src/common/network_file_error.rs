@@ -0,0 +1,30 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NetworkFileError{
+    Read{ path: String, message: String },
+    Write{ path: String, message: String },
+    Parse(String),
+    UnsupportedFormat(String),
+    UnsupportedVersion{ version: u32, current_version: u32 },
+    UnknownActivationFunction(String),
+    UnknownNode(i32),
+    NodeOrderMismatch
+}
+
+impl fmt::Display for NetworkFileError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkFileError::Read { path, message } => write!(f, "could not read network file {}: {}", path, message),
+            NetworkFileError::Write { path, message } => write!(f, "could not write network file {}: {}", path, message),
+            NetworkFileError::Parse(message) => write!(f, "could not parse network file: {}", message),
+            NetworkFileError::UnsupportedFormat(format) => write!(f, "unsupported network file format {}", format),
+            NetworkFileError::UnsupportedVersion { version, current_version } => write!(f, "network file has version {} but the newest supported version is {}", version, current_version),
+            NetworkFileError::UnknownActivationFunction(name) => write!(f, "unknown activation function {}", name),
+            NetworkFileError::UnknownNode(id) => write!(f, "connection references unknown node {}", id),
+            NetworkFileError::NodeOrderMismatch => write!(f, "sensors and outputs must list the sensor and output nodes in the order they appear in nodes"),
+        }
+    }
+}
+
+impl std::error::Error for NetworkFileError {}
@@ -0,0 +1,99 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_distr::StandardNormal;
+use rand_xoshiro::Xoshiro256PlusPlus;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+use super::NeatFloat;
+
+/// Run owned generator, kept in the RunContext so a seeded run repeats and a saved run carries on where it stopped.
+#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct SeededRandom{
+    rng: Xoshiro256PlusPlus
+}
+
+impl SeededRandom{
+    pub fn new(seed: Option<u64>) -> Self{
+        let rng = match seed {
+            Some(seed) => Xoshiro256PlusPlus::seed_from_u64(seed),
+            None => Xoshiro256PlusPlus::from_entropy()
+        };
+        Self { rng }
+    }
+    /// Generator for one stream of work (a species, a member) so parallel work does not depend on thread order.
+    pub fn for_stream<T: Hash>(seed: u64, stream: T) -> Self{
+        let mut hasher = DefaultHasher::new();
+        seed.hash(&mut hasher);
+        stream.hash(&mut hasher);
+        Self { rng: Xoshiro256PlusPlus::seed_from_u64(hasher.finish()) }
+    }
+    pub fn for_optional_stream<T: Hash>(seed: Option<u64>, stream: T) -> Self{
+        match seed {
+            Some(seed) => Self::for_stream(seed, stream),
+            None => Self::new(None)
+        }
+    }
+    pub fn gen_range_f32(&mut self, low_inc: NeatFloat, high_exc: NeatFloat) -> NeatFloat{
+        self.rng.gen_range(low_inc..high_exc)
+    }
+    pub fn gen_range_usize(&mut self, low_inc: usize, high_exc: usize) -> usize{
+        self.rng.gen_range(low_inc..high_exc)
+    }
+    pub fn gen_range_i64(&mut self, low_inc: i64, high_exc: i64) -> i64{
+        self.rng.gen_range(low_inc..high_exc)
+    }
+    pub fn gen_bool(&mut self, prob: NeatFloat) -> bool{
+        self.rng.gen_bool(prob as f64)
+    }
+    pub fn gen_u64(&mut self) -> u64{
+        self.rng.gen()
+    }
+    pub fn standard_normal(&mut self) -> NeatFloat{
+        self.rng.sample(StandardNormal)
+    }
+    pub fn new_uuid(&mut self) -> Uuid{
+        uuid::Builder::from_random_bytes(self.rng.gen()).into_uuid()
+    }
+}
+
+impl Default for SeededRandom{
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl RngCore for SeededRandom{
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+#[test]
+fn same_seed_gives_same_sequence() {
+    let mut left = SeededRandom::new(Some(42));
+    let mut right = SeededRandom::new(Some(42));
+    for _ in 0..100{
+        assert_eq!(left.gen_u64(), right.gen_u64());
+    }
+    assert_eq!(left.new_uuid(), right.new_uuid());
+}
+
+#[test]
+fn streams_are_independent_of_each_other() {
+    let mut first = SeededRandom::for_stream(42, 1);
+    let mut second = SeededRandom::for_stream(42, 2);
+    assert_ne!(first.gen_u64(), second.gen_u64());
+    assert_eq!(SeededRandom::for_stream(42, 1).gen_u64(), SeededRandom::for_stream(42, 1).gen_u64());
+}
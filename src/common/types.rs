@@ -1,4 +1,7 @@
+#[cfg(not(feature = "f64"))]
 pub type NeatFloat = f32;
+#[cfg(feature = "f64")]
+pub type NeatFloat = f64;
 pub struct NeatFloatExtensions;
 impl NeatFloatExtensions{
     pub fn abs_diff(a: NeatFloat, b: NeatFloat) -> NeatFloat{
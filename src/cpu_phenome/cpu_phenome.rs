@@ -19,6 +19,10 @@ impl Phenome for CpuPhenome{
     fn get_id(&self) -> Uuid {
         self.id
     }
+
+    fn activate_with_state(&self, sensor_values: &Vec<NeatFloat>, state: &mut Vec<NeatFloat>) -> Vec<NeatFloat>{
+        self.activate_recurrent_external_state(sensor_values, state)
+    }
 }
 impl CpuPhenome {
     pub fn activate_recurrent(&self, inputs: &Vec<Vec<NeatFloat>>) -> Vec<NeatFloat>{
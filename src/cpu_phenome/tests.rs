@@ -1,6 +1,6 @@
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use wasm_stopwatch::Stopwatch;
-use crate::{activation_functions::{ActivationFunction as GeneActivationFunction, self}, neat::{genome::{neat::{node_gene::{NodeGene}, NeatGenome, connect_gene::ConnectGene, mutation_mode::MutationMode}, genome::Genome}, trainer::{run_context::RunContext, configuration::Configuration, node_conf::NodeConf}}, node_kind::NodeKind, common::NeatFloat, phenome::Phenome};
+use crate::{activation_functions::{ActivationFunction as GeneActivationFunction, self}, neat::{genome::{neat::{node_gene::{NodeGene}, NeatGenome, connect_gene::ConnectGene, mutation_mode::MutationMode}, genome::Genome}, trainer::{run_context::RunContext, configuration::Configuration, node_conf::NodeConf}}, node_kind::NodeKind, common::{NeatFloat, seeded_random::SeededRandom}, phenome::Phenome};
 
 use super::CpuPhenome;
 #[test]
@@ -9,7 +9,7 @@ fn maps_all_activations() {
         let mut configuration  = Configuration::neat(
             Box::new(vec![
                 NodeGene::new(1, NodeKind::Sensor),
-                NodeGene::new_hidden(2, activation),
+                NodeGene::new_hidden(2, activation, &mut SeededRandom::new(Some(0))),
                 NodeGene::new(3, NodeKind::Output)
             ]), 0.0);
     
@@ -102,7 +102,7 @@ fn activate_recurrent_connection() {
     let mut configuration  = Configuration::neat(
         Box::new(vec![
             NodeGene::new(1, NodeKind::Sensor),
-            NodeGene::new_hidden(2, activation_functions::ActivationFunction::RELU),
+            NodeGene::new_hidden(2, activation_functions::ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
             NodeGene::new(3, NodeKind::Output)
         ]), 0.0)
         .mutation_node_available_activation_functions(GeneActivationFunction::RELU);
@@ -135,7 +135,7 @@ fn activate_recurrent_connection_output_to_hidden() {
     let mut configuration  = Configuration::neat(
         Box::new(vec![
             NodeGene::new(1, NodeKind::Sensor),
-            NodeGene::new_hidden(2, activation_functions::ActivationFunction::RELU),
+            NodeGene::new_hidden(2, activation_functions::ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
             NodeGene::new(3, NodeKind::Output)
         ]), 0.0)
         .mutation_node_available_activation_functions(GeneActivationFunction::RELU);
@@ -143,7 +143,7 @@ fn activate_recurrent_connection_output_to_hidden() {
     let mut run_context = RunContext::new(3, 0);
     let mut minimal_genome = NeatGenome::minimal(&mut configuration, &mut run_context);
 
-    minimal_genome.genes.connect.add(ConnectGene::new(3, 2, true));
+    minimal_genome.genes.connect.add(ConnectGene::new(3, 2, true, &mut run_context.random));
 
     let phenotype = CpuPhenome::from_network_schema(&minimal_genome);
 
@@ -163,7 +163,7 @@ fn activate_one_inputs_with_one_hidden_one_million_times() {
     let mut configuration  = Configuration::neat(
         Box::new(vec![
             NodeGene::new(1, NodeKind::Sensor),
-            NodeGene::new_hidden(2, GeneActivationFunction::RELU),
+            NodeGene::new_hidden(2, GeneActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
             NodeGene::new(3, NodeKind::Output)
         ]), 0.0);
 
@@ -189,7 +189,7 @@ fn activate_one_inputs_with_one_hidden_connection_skips_layer_one_million_times(
     let mut configuration  = Configuration::neat(
         Box::new(vec![
             NodeGene::new(1, NodeKind::Sensor),
-            NodeGene::new_hidden(2, GeneActivationFunction::RELU),
+            NodeGene::new_hidden(2, GeneActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
             NodeGene::new(3, NodeKind::Output)
         ]), 0.0);
 
@@ -218,7 +218,7 @@ fn phenotype_result_indexing_works_layer_skips() {
             NodeGene::new(1, NodeKind::Sensor),
             NodeGene::new(2, NodeKind::Sensor),
             NodeGene::new(3, NodeKind::Output),
-            NodeGene::new_hidden(4, GeneActivationFunction::RELU)
+            NodeGene::new_hidden(4, GeneActivationFunction::RELU, &mut SeededRandom::new(Some(0)))
         ]), 0.0);
 
     let mut run_context = RunContext::new(4, 0);
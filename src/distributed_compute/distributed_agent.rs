@@ -140,8 +140,8 @@ impl DistributedAgent{
                         println!("Got {} items of work to process.",  work.members.len());
                     }
 
-                    let mut members = work.members.iter().map(|work_item| work_item.member.to_owned()).collect::<Vec<GenerationMember<NeatGenome>>>();
-                    let computation_results = compute_fitnesses_cpu(fitness, current_generation_data.as_ref().unwrap().configuration.as_ref().unwrap(), &mut members, current_generation_data.as_ref().unwrap().novelty_component_store.as_ref().unwrap());
+                    let members = work.members.iter().map(|work_item| work_item.member.to_owned()).collect::<Vec<GenerationMember<NeatGenome>>>();
+                    let computation_results = compute_fitnesses_cpu(fitness, current_generation_data.as_ref().unwrap().configuration.as_ref().unwrap(), &members, current_generation_data.as_ref().unwrap().novelty_component_store.as_ref().unwrap());
 
                     if computation_results.len() > 0 {
                         println!("Submitting {} results.",  computation_results.len());
@@ -1,11 +1,13 @@
-#[macro_use]
+#[cfg_attr(any(feature = "sql", feature = "distributed"), macro_use)]
 extern crate lazy_static;
 
 pub mod neat;
 pub mod cpu_phenome;
 pub mod phenome;
 pub mod common;
+#[cfg(feature = "renderer")]
 pub mod renderer;
 pub use common::node_kind;
 pub use common::activation_functions;
+#[cfg(feature = "distributed")]
 pub mod distributed_compute;
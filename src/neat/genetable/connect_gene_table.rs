@@ -2,6 +2,8 @@ use hashbrown::HashMap;
 use nohash_hasher::{BuildNoHashHasher};
 use serde::{Deserialize, Serialize};
 use crate::neat::genome::neat::connect_gene::ConnectGene;
+#[cfg(test)]
+use crate::common::seeded_random::SeededRandom;
 
 #[derive(Debug, Clone, Default)]
 #[derive(Serialize, Deserialize)]
@@ -41,8 +43,8 @@ impl ConnectGeneTable{
 #[test]
 fn same_connections_does_not_increase_len() {
     let mut table = ConnectGeneTable::new();
-    table.add(ConnectGene::new(1,1, true));
-    let exists = table.exists(&ConnectGene::new(1,1, false));
+    table.add(ConnectGene::new(1, 1, true, &mut SeededRandom::new(Some(0))));
+    let exists = table.exists(&ConnectGene::new(1, 1, false, &mut SeededRandom::new(Some(0))));
     let len = table.len();
     let next_innovation_number =table.next_innovation_number();
 
@@ -54,8 +56,8 @@ fn same_connections_does_not_increase_len() {
 #[test]
 fn different_connections_do_increase_len() {
     let mut table = ConnectGeneTable::new();
-    table.add(ConnectGene::new(1,1,true));
-    table.add(ConnectGene::new(1,2,true));
+    table.add(ConnectGene::new(1, 1, true, &mut SeededRandom::new(Some(0))));
+    table.add(ConnectGene::new(1, 2, true, &mut SeededRandom::new(Some(0))));
     let len = table.len();
     let highest_innovation_number =table.next_innovation_number();
 
@@ -66,8 +68,8 @@ fn different_connections_do_increase_len() {
 #[test]
 fn weight_does_not_increase_len() {
     let mut table = ConnectGeneTable::new();
-    table.add(ConnectGene::new(1,1, true));
-    table.add(ConnectGene::new(1,1, true));
+    table.add(ConnectGene::new(1, 1, true, &mut SeededRandom::new(Some(0))));
+    table.add(ConnectGene::new(1, 1, true, &mut SeededRandom::new(Some(0))));
     let len = table.len();
     let highest_innovation_number =table.next_innovation_number();
 
@@ -1,5 +1,5 @@
 use serde::{Serialize, Deserialize};
-use crate::{neat::genome::neat::{node_gene::{NodeGene}}, activation_functions::ActivationFunction};
+use crate::{neat::genome::neat::{node_gene::{NodeGene}}, activation_functions::ActivationFunction, common::seeded_random::SeededRandom};
 
 #[derive(Debug, Clone, Default)]
 #[derive(Serialize, Deserialize)]
@@ -15,12 +15,9 @@ impl NoneGeneIndex{
             index: initial_count + 1
         }
     }
-    pub fn get_hidden(&mut self, activation_function: ActivationFunction) -> NodeGene {
+    pub fn get_hidden(&mut self, activation_function: ActivationFunction, random: &mut SeededRandom) -> NodeGene {
             self.index += 1;
-            return NodeGene::new_hidden(
-                self.index as i32,
-                activation_function
-            )
+            return NodeGene::new_hidden(self.index as i32, activation_function, random)
     }
     pub fn clear(&mut self){
         self.index = self.initial_count + 1;
@@ -0,0 +1,6 @@
+use crate::{common::seeded_random::SeededRandom, neat::trainer::configuration::Configuration};
+
+pub trait Crossover{
+    fn reproduce(best_performing: &Self, other: &Self, configuration: &Configuration, random: &mut SeededRandom) -> Self;
+    fn reproduce_cross_species(best_performing: &Self, other: &Self, configuration: &Configuration, random: &mut SeededRandom) -> Self;
+}
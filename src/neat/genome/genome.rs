@@ -1,15 +1,21 @@
-use crate::common::NeatFloat;
+use std::fmt::Debug;
+use crate::common::{NeatFloat, color::Rgb};
 
 use super::{super::trainer::{configuration::Configuration, run_context::RunContext}, neat::mutation_mode::MutationMode};
 
-pub trait Genome{
+pub trait Genome: Sized + Clone + Debug + Send + Sync{
     fn get_id(&self) -> uuid::Uuid;
-    fn minimal(configuration: &Configuration, run_context: &mut RunContext) -> Self;
-    fn mutate(&mut self, configuration: &Configuration, run_context: &mut RunContext, mutation_mode: MutationMode);
+    fn set_id(&mut self, id: uuid::Uuid);
+    fn minimal(configuration: &Configuration, run_context: &mut RunContext<Self>) -> Self;
+    fn mutate(&mut self, configuration: &Configuration, run_context: &mut RunContext<Self>, mutation_mode: MutationMode);
     fn dont_allow_mutation(&mut self);
     fn set_objective_fitness(&mut self, value: NeatFloat);
     fn set_novelty(&mut self, value: NeatFloat);
     fn get_fitness(&self) -> Option<NeatFloat>;
+    fn get_novelty(&self) -> NeatFloat;
     fn get_genetic_difference_distance_from(&mut self, other_genome: &Self, stop_when_hit: NeatFloat) -> NeatFloat;
     fn get_complexity(&self) -> NeatFloat;
+    fn get_color(&self) -> Rgb{
+        Rgb::GRAY
+    }
 }
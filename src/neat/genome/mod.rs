@@ -1,4 +1,6 @@
 
 
 pub mod neat;
-pub mod genome;
\ No newline at end of file
+pub mod genome;
+pub mod crossover;
+pub mod mutation_history;
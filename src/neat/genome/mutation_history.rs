@@ -0,0 +1,14 @@
+use crate::common::NeatFloat;
+use super::neat::mutation::Mutation;
+
+// Used by the genetically engineered member maker, genomes that don't keep a history of their mutations can rely on the defaults.
+pub trait MutationHistory{
+    fn get_parents_objective_fitness(&self) -> Option<NeatFloat>{
+        None
+    }
+    fn get_mutations(&self) -> &[Mutation]{
+        &[]
+    }
+    fn apply_mutation_optimization(&mut self, _mutation: Mutation){
+    }
+}
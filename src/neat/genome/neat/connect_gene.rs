@@ -3,7 +3,7 @@ use std::hash::{Hash, Hasher};
 use crate::common::NeatFloat;
 use crate::common::event_stream::event::{EventType, Event};
 use crate::common::event_stream::event_recorder::EventRecorder;
-use crate::common::random::Random;
+use crate::common::seeded_random::SeededRandom;
 #[allow(unused_imports)]
 use crate::neat::genome::neat::node_gene::NodeGene;
 #[allow(unused_imports)]
@@ -27,13 +27,13 @@ pub struct ConnectGene {
 }
 
 impl ConnectGene {
-    pub fn new(connection_in: i32,connection_out: i32,is_enabled: bool)  -> Self {
+    pub fn new(connection_in: i32,connection_out: i32,is_enabled: bool, random: &mut SeededRandom)  -> Self {
         let mut hasher = DefaultHasher::new();
         connection_in.hash(&mut hasher);
         connection_out.hash(&mut hasher);
         let connection_hash = hasher.finish();
 
-        let random = Random::gen_range_f32(-1.0 ,1.0);
+        let random = random.gen_range_f32(-1.0 ,1.0);
 
         ConnectGene{
             connection_in,
@@ -64,12 +64,12 @@ impl ConnectGene {
             connection_hash
         }
    }
-    pub fn mutate(&mut self, genome_id: &uuid::Uuid, configuration: &Configuration, run_context: &RunContext, mutations: &mut Vec<Mutation>) {
+    pub fn mutate(&mut self, genome_id: &uuid::Uuid, configuration: &Configuration, run_context: &mut RunContext, mutations: &mut Vec<Mutation>) {
         
-        let mut random = Random::gen_range_f32(0.0 ,1.0);
+        let mut random = run_context.random.gen_range_f32(0.0 ,1.0);
 
         if random < configuration.mutation_connection_weight_change_probability{
-            let val: NeatFloat = Random::standard_normal() * configuration.mutation_connection_weight_change_scale;
+            let val: NeatFloat = run_context.random.standard_normal() * configuration.mutation_connection_weight_change_scale;
 
             let new_weight = (self.weight + val).clamp(configuration.connection_weight_min_value, configuration.connection_weight_max_value);
             if EventRecorder::has_subscription(configuration, EventType::MUTATION_CONNECTION_WEIGHT_CHANGE){
@@ -81,9 +81,9 @@ impl ConnectGene {
             self.weight = new_weight
         }
 
-        random = Random::gen_range_f32(0.0 ,1.0);
+        random = run_context.random.gen_range_f32(0.0 ,1.0);
         if random < configuration.mutation_connection_weight_replace_probability {
-            let val: NeatFloat = Random::standard_normal();
+            let val: NeatFloat = run_context.random.standard_normal();
 
             let new_weight = val.clamp(configuration.connection_weight_min_value, configuration.connection_weight_max_value);
             if EventRecorder::has_subscription(configuration, EventType::MUTATION_CONNECTION_WEIGHT_REPLACE){
@@ -95,7 +95,7 @@ impl ConnectGene {
             self.weight = new_weight;
         }
 
-        let should_disable = Random::gen_bool(configuration.mutation_connection_disable_probability);
+        let should_disable = run_context.random.gen_bool(configuration.mutation_connection_disable_probability);
         if should_disable{
             if EventRecorder::has_subscription(configuration, EventType::MUTATION_CONNECTION_DISABLED){
                 EventRecorder::record_event(configuration, &Event::mutation_connection_disabled(run_context, genome_id, self.connection_in, self.connection_out));
@@ -122,7 +122,7 @@ fn mutate_changes_weight() {
     .mutation_connection_weight_change_probability(1.0);
 
     let mut connection = ConnectGene::new_with_weight(1, 2, 0.0, true);
-    connection.mutate(&uuid::Uuid::new_v4(), &configuration, &RunContext::new(2, 2), &mut Vec::new());
+    connection.mutate(&uuid::Uuid::new_v4(), &configuration, &mut RunContext::new(2, 2), &mut Vec::new());
     assert_ne!(connection.weight, 0.0);
 }
 
@@ -136,6 +136,6 @@ fn mutate_replaces_weight() {
     .mutation_connection_weight_replace_probability(1.0);
 
     let mut connection = ConnectGene::new_with_weight(1, 2, 0.0, true);
-    connection.mutate(&uuid::Uuid::new_v4(), &configuration, &RunContext::new(2, 2),&mut Vec::new());
+    connection.mutate(&uuid::Uuid::new_v4(), &configuration, &mut RunContext::new(2, 2),&mut Vec::new());
     assert_ne!(connection.weight, 0.0);
 }
\ No newline at end of file
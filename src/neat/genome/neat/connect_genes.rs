@@ -2,7 +2,7 @@ use std::slice::{Iter, IterMut};
 use hashbrown::HashMap;
 use nohash_hasher::BuildNoHashHasher;
 use serde::{Serialize, Deserialize};
-use crate::common::random::Random;
+use crate::common::seeded_random::SeededRandom;
 
 use super::connect_gene::ConnectGene;
 
@@ -111,13 +111,13 @@ impl ConnectGenes{
         self.connect_genes.clear();
         self.map.clear();
     }
-    pub fn get_random_connect_gene(&self) -> Option<&ConnectGene> {
+    pub fn get_random_connect_gene(&self, random: &mut SeededRandom) -> Option<&ConnectGene> {
         
         let len = self.connect_genes.len();
         if len == 0{
             return None;
         }
-        let random_node_index = if len > 1 { Random::gen_range_usize(0, len-1) } else { 0 };
+        let random_node_index = if len > 1 { random.gen_range_usize(0, len-1) } else { 0 };
         Some(&self.connect_genes[random_node_index])
     }
     fn recompute_indexes(&mut self){
@@ -14,15 +14,15 @@ pub enum Mutation {
     //connect_in, connect_out
     DeleteConnection(i32, i32),
     //node_id, bias
-    AddNode(i32, f32),
+    AddNode(i32, NeatFloat),
     //node_id
     DeleteNode(i32),
     //connect_in, connect_out, initial_weight
-    AddConnection(i32, i32, f32),
+    AddConnection(i32, i32, NeatFloat),
     //node_id, new_value
-    NodeBiasChange(i32, f32),
+    NodeBiasChange(i32, NeatFloat),
     //node_id, new_value
-    CppnInputMultiplierChange(i32, f32), 
+    CppnInputMultiplierChange(i32, NeatFloat), 
 }
 
 impl Mutation{
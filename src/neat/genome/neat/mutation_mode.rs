@@ -1,6 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MutationMode{
     Steady,
     Optimistic,
     FoolHardy,
     ShootForTheMoon
-}
\ No newline at end of file
+}
@@ -2,7 +2,6 @@
 use serde::{Serialize, Deserialize};
 use crate::common::event_stream::event::Event;
 use crate::common::{NeatFloat, event_stream::event::EventType};
-use crate::common::random::Random;
 use crate::neat::trainer::configuration::Configuration;
 use crate::neat::trainer::run_context::RunContext;
 use super::connect_genes::ConnectGenes;
@@ -25,13 +24,13 @@ impl NeatGenes{
         Self { nodes: node_genes, connect: connect_genes }
     }
     pub fn mutate(&mut self, genome_id: &uuid::Uuid, configuration: &Configuration, run_context: &mut RunContext, mutations: &mut Vec<Mutation>, mutation_mode: MutationMode){
-        let mut random = Random::gen_range_f32(0.0 ,1.0);
+        let mut random = run_context.random.gen_range_f32(0.0 ,1.0);
 
-        let number_of_structural_mutations = match mutation_mode{
-            MutationMode::Steady => 1,
-            MutationMode::Optimistic => Random::gen_range_usize(1 , 3),
-            MutationMode::FoolHardy =>  Random::gen_range_usize(1 , 6),
-            MutationMode::ShootForTheMoon =>  Random::gen_range_usize(1 , 12),
+        let max_structural_mutations = configuration.mutation_schedule.get_max_structural_mutations(mutation_mode);
+        let number_of_structural_mutations = if max_structural_mutations <= 1 {
+            max_structural_mutations
+        }else{
+            run_context.random.gen_range_usize(1 , max_structural_mutations + 1)
         };
         
         for _ in 0..number_of_structural_mutations{
@@ -39,12 +38,12 @@ impl NeatGenes{
                 self.mutate_add_node(configuration, run_context, genome_id, mutations);
             }
         
-            random = Random::gen_range_f32(0.0 ,1.0);
+            random = run_context.random.gen_range_f32(0.0 ,1.0);
             if random <= configuration.mutation_node_delete_probability {
                 self.mutate_delete_node(configuration, run_context, genome_id, mutations);
             }
 
-            random = Random::gen_range_f32(0.0 ,1.0);
+            random = run_context.random.gen_range_f32(0.0 ,1.0);
             if random <= configuration.mutation_connection_add_probability {
                 let mut success = self.mutate_add_connection(genome_id, configuration, run_context, mutations);
                 let mut c = 1;
@@ -54,7 +53,7 @@ impl NeatGenes{
                 }
             }
 
-            random = Random::gen_range_f32(0.0 ,1.0);
+            random = run_context.random.gen_range_f32(0.0 ,1.0);
             if random <= configuration.mutation_connection_delete_probability {
                 self.mutate_delete_connection(genome_id, configuration, run_context, mutations);
             }    
@@ -71,12 +70,13 @@ impl NeatGenes{
         }
     }
     fn mutate_add_node(&mut self, configuration: &Configuration, run_context: &mut RunContext, genome_id: &uuid::Uuid, mutations: &mut Vec<Mutation>){
-        let connection_to_split_option = self.connect.get_random_connect_gene();
+        let connection_to_split_option = self.connect.get_random_connect_gene(&mut run_context.random);
         if connection_to_split_option.is_none() {
             return;
         }
         let connection_to_split = connection_to_split_option.unwrap();
-        let new_node = run_context.node_index.get_hidden(configuration.mutation_node_available_activation_functions.get_random());
+        let activation_function = configuration.mutation_node_available_activation_functions.get_random(&mut run_context.random);
+        let new_node = run_context.node_index.get_hidden(activation_function, &mut run_context.random);
         
         //new
         let connection_in = run_context.gene_table.add(ConnectGene::new_with_weight(connection_to_split.connection_in, new_node.number,1.0 ,true));
@@ -106,7 +106,7 @@ impl NeatGenes{
     }
     fn mutate_delete_node(&mut self, configuration: &Configuration, run_context: &mut RunContext, genome_id: &uuid::Uuid, mutations: &mut Vec<Mutation>){
         
-        let node_to_delete_number = &self.nodes.get_random_hidden_node_number();
+        let node_to_delete_number = &self.nodes.get_random_hidden_node_number(&mut run_context.random);
         match node_to_delete_number {
             Some(node_number) => {
                 let n = *node_number;
@@ -129,10 +129,10 @@ impl NeatGenes{
     }
     fn mutate_add_connection(&mut self, genome_id: &uuid::Uuid, configuration: &Configuration, run_context: &mut RunContext, mutations: &mut Vec<Mutation>) -> bool{
         
-        let node_in_number_option = self.nodes.get_random_node_number(|n| n.kind != NodeKind::Output );
+        let node_in_number_option = self.nodes.get_random_node_number(|n| n.kind != NodeKind::Output, &mut run_context.random);
         match node_in_number_option {
             Some(node_in_number) => {
-                let node_out_number_option = self.nodes.get_random_node_number(|n| n.number != node_in_number );
+                let node_out_number_option = self.nodes.get_random_node_number(|n| n.number != node_in_number, &mut run_context.random);
                 if node_out_number_option == None{
                     return false;
                 }
@@ -176,7 +176,8 @@ impl NeatGenes{
                     is_recurrent = source_layer >= target_layer
                 }
 
-                let mut connection_to_add = run_context.gene_table.add(ConnectGene::new(node_in_number, node_out_number_option.unwrap(), true));
+                let weight = run_context.random.gen_range_f32(-1.0 ,1.0);
+                let mut connection_to_add = run_context.gene_table.add(ConnectGene::new_with_weight(node_in_number, node_out_number_option.unwrap(), weight, true));
                 connection_to_add.is_recurrent = is_recurrent;
 
                 if Self::does_connection_cause_loop(self, &connection_to_add)
@@ -199,9 +200,9 @@ impl NeatGenes{
             }
         }
     }
-    fn mutate_delete_connection(&mut self, genome_id: &uuid::Uuid, configuration: &Configuration, run_context: &RunContext, mutations: &mut Vec<Mutation>){
+    fn mutate_delete_connection(&mut self, genome_id: &uuid::Uuid, configuration: &Configuration, run_context: &mut RunContext, mutations: &mut Vec<Mutation>){
         
-        let connection_to_delete_option = self.connect.get_random_connect_gene();
+        let connection_to_delete_option = self.connect.get_random_connect_gene(&mut run_context.random);
         if connection_to_delete_option.is_some(){
             let connection_to_delete = connection_to_delete_option.unwrap();
             if EventRecorder::has_subscription(configuration, EventType::MUTATION_CONNECTION_DELETE){
@@ -1,9 +1,9 @@
-use bevy::prelude::Color;
 use hashbrown::HashMap;
 use uuid::Uuid;
 use crate::common::NeatFloat;
+use crate::common::color::Rgb;
 use crate::common::network_definition::{NetworkDefinition, NetworkDefinitionNode, NetworkDefinitionConnection};
-use crate::common::random::Random;
+use crate::common::seeded_random::SeededRandom;
 use crate::neat::trainer::configuration::Configuration;
 use crate::neat::trainer::run_context::RunContext;
 use crate::node_kind::NodeKind;
@@ -11,6 +11,7 @@ use crate::neat::genome::neat::mutation_mode::MutationMode;
 use super::connect_gene::ConnectGene;
 use super::connect_genes::ConnectGenes;
 use super::super::genome::Genome;
+use super::super::mutation_history::MutationHistory;
 use super::mutation::Mutation;
 use super::node_gene::{NodeGene};
 use super::node_genes::NodeGenes;
@@ -28,23 +29,13 @@ pub struct NeatGenome {
     pub novelty: NeatFloat,
     pub allow_mutation: bool,
     pub cached_genetic_distance: HashMap<uuid::Uuid, NeatFloat>,
-    pub color: Color,
+    pub color: Rgb,
     pub mutations: Vec<Mutation>
 }
 
 impl NeatGenome{
-    pub fn mutate_color(&mut self){
-        let change: NeatFloat = Random::standard_normal() * 0.01;
-        let new_val = (self.color.r() + change).clamp(0.0, 1.0);
-        self.color.set_r(new_val);
-        
-        let change: NeatFloat = Random::standard_normal() * 0.01;
-        let new_val = (self.color.g() + change).clamp(0.0, 1.0);
-        self.color.set_g(new_val);
-
-        let change: NeatFloat = Random::standard_normal() * 0.01;
-        let new_val = (self.color.b() + change).clamp(0.0, 1.0);
-        self.color.set_b(new_val);
+    pub fn mutate_color(&mut self, random: &mut SeededRandom){
+        self.color.drift(random, 0.01);
     }
 }
 
@@ -57,23 +48,24 @@ impl Genome for NeatGenome{
 
         for output in outputs{
             for sensor in &sensors{
-                let random = Random::gen_range_f32(0.0 ,1.0);
+                let random = run_context.random.gen_range_f32(0.0 ,1.0);
                 if random <= configuration.genome_minimal_genes_to_connect_ratio{
-                    let gene_to_add = ConnectGene::new(sensor.number, output.number, true);
+                    let weight = run_context.random.gen_range_f32(-1.0 ,1.0);
+                    let gene_to_add = ConnectGene::new_with_weight(sensor.number, output.number, weight, true);
                     let connection= run_context.gene_table.add(gene_to_add);
                     connect_genes.push(connection);
                 }
             }
         }
         NeatGenome{
-            id: Uuid::new_v4(),
+            id: run_context.random.new_uuid(),
             genes: NeatGenes::new(NodeGenes::from_vec(&*configuration.node_genes), ConnectGenes::from_vec(&connect_genes)),
             parents_objective_fitness: None,            
             objective_fitness: None,
             novelty: 0.0,
             allow_mutation: true,
             cached_genetic_distance: HashMap::new(),
-            color: Color::Rgba { red: Random::gen_range_f32(0.0, 1.0), green: Random::gen_range_f32(0.0, 1.0), blue: Random::gen_range_f32(0.0, 1.0), alpha: 1.0 },
+            color: Rgb::random(&mut run_context.random),
             mutations: Vec::new()
         }
     }
@@ -81,7 +73,7 @@ impl Genome for NeatGenome{
         if self.allow_mutation{
             self.genes.mutate(&self.id, configuration, run_context, &mut self.mutations, mutation_mode);
         }
-        NeatGenome::mutate_color(self);
+        NeatGenome::mutate_color(self, &mut run_context.random);
     }
     fn dont_allow_mutation(&mut self){
         self.allow_mutation = false;
@@ -110,11 +102,37 @@ impl Genome for NeatGenome{
         self.id
     }
 
+    fn set_id(&mut self, id: uuid::Uuid) {
+        self.id = id;
+    }
+
+    fn get_novelty(&self) -> NeatFloat {
+        self.novelty
+    }
+
+    fn get_color(&self) -> Rgb {
+        self.color
+    }
+
     fn get_complexity(&self) -> NeatFloat {
         self.genes.get_complexity()
     }
 }
 
+impl MutationHistory for NeatGenome{
+    fn get_parents_objective_fitness(&self) -> Option<NeatFloat> {
+        self.parents_objective_fitness
+    }
+
+    fn get_mutations(&self) -> &[Mutation] {
+        &self.mutations
+    }
+
+    fn apply_mutation_optimization(&mut self, mutation: Mutation) {
+        Mutation::apply_optimization_to_genome(mutation, self);
+    }
+}
+
 impl NetworkDefinition for NeatGenome{
     fn get_network_identifier(&self) -> Uuid {
         
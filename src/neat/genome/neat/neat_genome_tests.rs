@@ -3,7 +3,7 @@
 use crate::neat::genome::genome::Genome;
 #[cfg(test)]
 use super::{node_gene::{NodeGene}, connect_gene::ConnectGene, neat_genome::NeatGenome};
-use crate::{neat::{trainer::{run_context::RunContext, configuration::Configuration}, genome::neat::{mutation_mode::MutationMode, mutation_add_mode::MutationNodeAddMode}}, node_kind::NodeKind, activation_functions::ActivationFunction, common::{network_definition_node_layer_resolver::NetworkDefinitionNodeLayerResolver, NeatFloat}};
+use crate::{neat::{trainer::{run_context::RunContext, configuration::Configuration}, genome::neat::{mutation_mode::MutationMode, mutation_add_mode::MutationNodeAddMode}}, node_kind::NodeKind, activation_functions::ActivationFunction, common::{network_definition_node_layer_resolver::NetworkDefinitionNodeLayerResolver, NeatFloat, seeded_random::SeededRandom}};
 
 #[test]
 fn minimal_add_connect_genes() {
@@ -173,8 +173,8 @@ fn get_nodes_in_layer() {
     let configuration  = Configuration::neat(
     Box::new(vec![
         NodeGene::new(1, NodeKind::Sensor),
-        NodeGene::new_hidden(2, ActivationFunction::RELU),
-        NodeGene::new_hidden(3, ActivationFunction::RELU),
+        NodeGene::new_hidden(2, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
+        NodeGene::new_hidden(3, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
         NodeGene::new(4, NodeKind::Output)
     ]), 0.0);
 
@@ -182,10 +182,10 @@ fn get_nodes_in_layer() {
     let mut minimal_genome = NeatGenome::minimal(&configuration, &mut run_context);
 
     minimal_genome.genes.connect.clear();
-    minimal_genome.genes.connect.add(ConnectGene::new(1, 2, true));
-    minimal_genome.genes.connect.add(ConnectGene::new(1, 3, true));
-    minimal_genome.genes.connect.add(ConnectGene::new(2, 4, true));
-    minimal_genome.genes.connect.add(ConnectGene::new(3, 4, true));
+    minimal_genome.genes.connect.add(ConnectGene::new(1, 2, true, &mut run_context.random));
+    minimal_genome.genes.connect.add(ConnectGene::new(1, 3, true, &mut run_context.random));
+    minimal_genome.genes.connect.add(ConnectGene::new(2, 4, true, &mut run_context.random));
+    minimal_genome.genes.connect.add(ConnectGene::new(3, 4, true, &mut run_context.random));
 
     let layer_zero_nodes = minimal_genome.genes.get_nodes_in_layer(0);
     let layer_one_nodes = minimal_genome.genes.get_nodes_in_layer(1);
@@ -202,7 +202,7 @@ fn get_genetic_difference_distance_zero_for_identical_genomes(){
         let configuration  = Configuration::neat(
         Box::new(vec![
             NodeGene::new(1, NodeKind::Sensor),
-            NodeGene::new_hidden(2, ActivationFunction::RELU),
+            NodeGene::new_hidden(2, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
             NodeGene::new(3, NodeKind::Output)
         ]), 0.0);
 
@@ -225,7 +225,7 @@ fn get_genetic_difference_distance_1_for_disjoint_node(){
     let configuration  = Configuration::neat(
     Box::new(vec![
         NodeGene::new(1, NodeKind::Sensor),
-        NodeGene::new_hidden(2, ActivationFunction::RELU),
+        NodeGene::new_hidden(2, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
         NodeGene::new(3, NodeKind::Output)
     ]), 0.0)
     .mutation_node_available_activation_functions(ActivationFunction::RELU)
@@ -253,7 +253,7 @@ fn get_genetic_difference_distance_correct_for_node_with_different_activation(){
     let configuration  = Configuration::neat(
     Box::new(vec![
         NodeGene::new(1, NodeKind::Sensor),
-        NodeGene::new_hidden(2, ActivationFunction::RELU),
+        NodeGene::new_hidden(2, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
         NodeGene::new(3, NodeKind::Output)
     ]), 0.0);
 
@@ -277,7 +277,7 @@ fn get_genetic_difference_distance_correct_for_node_with_different_bias(){
     let configuration  = Configuration::neat(
     Box::new(vec![
         NodeGene::new(1, NodeKind::Sensor),
-        NodeGene::new_hidden(2, ActivationFunction::RELU),
+        NodeGene::new_hidden(2, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
         NodeGene::new(3, NodeKind::Output)
     ]), 0.0);
 
@@ -301,7 +301,7 @@ fn get_genetic_difference_distance_correct_for_connections_with_different_weight
     let configuration  = Configuration::neat(
     Box::new(vec![
         NodeGene::new(1, NodeKind::Sensor),
-        NodeGene::new_hidden(2, ActivationFunction::RELU),
+        NodeGene::new_hidden(2, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
         NodeGene::new(3, NodeKind::Output)
     ]), 0.0);
 
@@ -325,13 +325,13 @@ fn get_node_layers(){
     let configuration  = Configuration::neat(
     Box::new(vec![
         NodeGene::new(1, NodeKind::Sensor),
-        NodeGene::new_hidden(2, ActivationFunction::RELU),
-        NodeGene::new_hidden(3, ActivationFunction::RELU),
-        NodeGene::new_hidden(4, ActivationFunction::RELU),
+        NodeGene::new_hidden(2, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
+        NodeGene::new_hidden(3, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
+        NodeGene::new_hidden(4, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
 
-        NodeGene::new_hidden(5, ActivationFunction::RELU),
-        NodeGene::new_hidden(6, ActivationFunction::RELU),
-        NodeGene::new_hidden(7, ActivationFunction::RELU),
+        NodeGene::new_hidden(5, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
+        NodeGene::new_hidden(6, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
+        NodeGene::new_hidden(7, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
 
         NodeGene::new(8, NodeKind::Output)
     ]), 0.0);
@@ -362,8 +362,8 @@ fn get_node_layers_recurrent(){
     let configuration  = Configuration::neat(
     Box::new(vec![
         NodeGene::new(1, NodeKind::Sensor),
-        NodeGene::new_hidden(2, ActivationFunction::RELU),
-        NodeGene::new_hidden(3, ActivationFunction::RELU),
+        NodeGene::new_hidden(2, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
+        NodeGene::new_hidden(3, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
         NodeGene::new(4, NodeKind::Output)
     ]), 0.0);
 
@@ -390,7 +390,7 @@ fn get_node_layers_debug(){
     let configuration  = Configuration::neat(
     Box::new(vec![
         NodeGene::new(1, NodeKind::Sensor),
-        NodeGene::new_hidden(2, ActivationFunction::RELU),
+        NodeGene::new_hidden(2, ActivationFunction::RELU, &mut SeededRandom::new(Some(0))),
         NodeGene::new(3, NodeKind::Output)
     ]), 0.0);
 
@@ -406,4 +406,26 @@ fn get_node_layers_debug(){
     assert_eq!(resolved_node_layers.layers[2][0].identity, 3);
     
     println!("{:?}", resolved_node_layers.layers);
-}
\ No newline at end of file
+}
+#[test]
+fn structure_hash_only_changes_with_the_genes(){
+    use crate::common::network_definition::NetworkDefinition;
+    let configuration  = Configuration::neat(
+        Box::new(vec![
+            NodeGene::new(1, NodeKind::Sensor),
+            NodeGene::new(2, NodeKind::Output)
+        ]), 0.0)
+        .mutation_no_mutation()
+        .mutation_connection_weight_replace_probability(1.0);
+
+    let mut run_context =  RunContext::new(2, 0);
+    let genome = NeatGenome::minimal(&configuration, &mut run_context);
+    let mut clone = genome.clone();
+    clone.set_id(uuid::Uuid::new_v4());
+    clone.set_objective_fitness(1.0);
+    let mut mutated = genome.clone();
+    mutated.mutate(&configuration, &mut run_context, MutationMode::Steady);
+
+    assert_eq!(genome.get_structure_hash(), clone.get_structure_hash());
+    assert_ne!(genome.get_structure_hash(), mutated.get_structure_hash());
+}
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use crate::{neat::trainer::{configuration::Configuration, run_context::RunContext}, node_kind::NodeKind, activation_functions::ActivationFunction, common::{random::Random, NeatFloat, event_stream::{event::{EventType, Event}, event_recorder::EventRecorder}}};
+use crate::{neat::trainer::{configuration::Configuration, run_context::RunContext}, node_kind::NodeKind, activation_functions::ActivationFunction, common::{seeded_random::SeededRandom, NeatFloat, event_stream::{event::{EventType, Event}, event_recorder::EventRecorder}}};
 
 use super::mutation::Mutation;
 
@@ -26,21 +26,24 @@ impl NodeGene{
             activation_function: ActivationFunction::empty()
         }
     }
-    pub fn new_hidden(index: i32, activation_function: ActivationFunction) -> NodeGene {
+    pub fn new_hidden(index: i32, activation_function: ActivationFunction, random: &mut SeededRandom) -> NodeGene {
+        Self::new_hidden_with_input_multiplier(index, activation_function, random.gen_range_f32(0.0 ,50.0))
+    }
+    pub fn new_hidden_with_input_multiplier(index: i32, activation_function: ActivationFunction, input_multiplier: NeatFloat) -> NodeGene {
         let bias: NeatFloat = 1.0;
         NodeGene{
             number: index,
             kind: NodeKind::Hidden,
             bias: bias,
-            input_multiplier: Random::gen_range_f32(0.0 ,50.0),
+            input_multiplier,
             activation_function: activation_function
         }
     }
-    pub fn mutate(&mut self, genome_id: &uuid::Uuid, configuration: &Configuration, run_context: &RunContext, mutations: &mut Vec<Mutation>){
-        let random = Random::gen_range_f32(0.0 ,1.0);
+    pub fn mutate(&mut self, genome_id: &uuid::Uuid, configuration: &Configuration, run_context: &mut RunContext, mutations: &mut Vec<Mutation>){
+        let random = run_context.random.gen_range_f32(0.0 ,1.0);
 
         if random < configuration.mutation_node_bias_change_probability{
-            let val: NeatFloat = Random::standard_normal() * configuration.mutation_node_bias_change_scale;
+            let val: NeatFloat = run_context.random.standard_normal() * configuration.mutation_node_bias_change_scale;
             
             if EventRecorder::has_subscription(configuration, EventType::MUTATION_NODE_CHANGE_BIAS){
                 EventRecorder::record_event(configuration, &Event::mutation_node_bias_change(run_context, genome_id, self.number, val));
@@ -54,7 +57,7 @@ impl NodeGene{
         if self.activation_function & ActivationFunction::for_cppn() == self.activation_function {
             
             if random < configuration.mutation_node_cppn_input_multiplier_change_probability{
-                let val: NeatFloat = Random::standard_normal() * configuration.mutation_node_cppn_input_multiplier_change_scale;
+                let val: NeatFloat = run_context.random.standard_normal() * configuration.mutation_node_cppn_input_multiplier_change_scale;
 
                 mutations.push(Mutation::CppnInputMultiplierChange(self.number, self.bias));
 
@@ -62,7 +65,7 @@ impl NodeGene{
             }
 
             if random < configuration.mutation_node_cppn_input_multiplier_replace_probability{
-                let val: NeatFloat = Random::standard_normal() * 20.0;
+                let val: NeatFloat = run_context.random.standard_normal() * 20.0;
                 mutations.push(Mutation::CppnInputMultiplierChange(self.number, self.bias));
 
                 self.input_multiplier = val; 
@@ -87,9 +90,9 @@ fn mutate_changes_bias() {
     .mutation_no_mutation()
     .mutation_node_bias_change_probability(1.0);
 
-    let mut node = NodeGene::new_hidden(1, ActivationFunction::RELU);
+    let mut node = NodeGene::new_hidden(1, ActivationFunction::RELU, &mut SeededRandom::new(Some(0)));
     node.bias = 0.0;
     let id = uuid::Uuid::new_v4();
-    node.mutate(&id, &configuration, &RunContext::new(2, 1), &mut Vec::new());
+    node.mutate(&id, &configuration, &mut RunContext::new(2, 1), &mut Vec::new());
     assert_ne!(node.bias, 0.0);
 }
\ No newline at end of file
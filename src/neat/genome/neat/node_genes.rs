@@ -2,7 +2,7 @@ use std::slice::{Iter, IterMut};
 use hashbrown::HashMap;
 use nohash_hasher::BuildNoHashHasher;
 use serde::{Serialize, Deserialize};
-use crate::{node_kind::NodeKind, common::random::Random};
+use crate::{node_kind::NodeKind, common::seeded_random::SeededRandom};
 
 use super::node_gene::{NodeGene};
 
@@ -80,10 +80,10 @@ impl NodeGenes{
     pub fn len(&self) -> usize{
         self.node_genes.len()
     }
-    pub fn get_random_hidden_node_number(&self) -> Option<i32> {
-        self.get_random_node_number(|n| n.kind == NodeKind::Hidden)
+    pub fn get_random_hidden_node_number(&self, random: &mut SeededRandom) -> Option<i32> {
+        self.get_random_node_number(|n| n.kind == NodeKind::Hidden, random)
     }
-    pub fn get_random_node_number<F>(&self, filter: F) -> Option<i32>  where F: FnMut(&&NodeGene) -> bool {
+    pub fn get_random_node_number<F>(&self, filter: F, random: &mut SeededRandom) -> Option<i32>  where F: FnMut(&&NodeGene) -> bool {
         let nodes = self.node_genes.iter()
         .filter(filter)
         .collect::<Vec<&NodeGene>>();
@@ -93,7 +93,7 @@ impl NodeGenes{
         }
         
         let len = nodes.len();
-        let random_node_index = if len > 1 { Random::gen_range_usize(0, len) } else { 0 };
+        let random_node_index = if len > 1 { random.gen_range_usize(0, len) } else { 0 };
         Some(nodes[random_node_index].number)
     }
     fn recompute_indexes(&mut self){
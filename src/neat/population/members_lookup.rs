@@ -1,19 +1,19 @@
 use hashbrown::HashMap;
 use nohash_hasher::BuildNoHashHasher;
 use uuid::Uuid;
-use crate::neat::genome::neat::NeatGenome;
+use crate::neat::genome::genome::Genome;
 use super::GenerationMember;
 
 pub struct MembersLookup{
     lookup: HashMap<u64, usize, nohash_hasher::BuildNoHashHasher<u64>>
 }
 impl MembersLookup {
-    pub fn new(members: &Vec<GenerationMember<NeatGenome>>) -> Self {
+    pub fn new<T>(members: &Vec<GenerationMember<T>>) -> Self where T: Genome {
         
         let mut lookup:  HashMap<u64, usize, nohash_hasher::BuildNoHashHasher<u64>> = HashMap::with_capacity_and_hasher(members.len(), BuildNoHashHasher::default());
         let mut c = 0 as usize;
         for member in members{
-            lookup.insert(member.genome.id.as_u64_pair().0, c);
+            lookup.insert(member.genome.get_id().as_u64_pair().0, c);
             c+= 1;
         }
         MembersLookup{
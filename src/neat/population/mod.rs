@@ -8,7 +8,7 @@ use crate::common::NeatFloat;
 use crate::phenome::Phenome;
 use super::{ 
     genome::neat::{NeatGenome, node_gene::NodeGene}, 
-    trainer::{configuration::Configuration, node_conf::NodeConf}
+    trainer::{configuration::Configuration, node_conf::NodeConf, fitness::{objective::Objective, component_score::ComponentScore}}
 };
 pub mod reproduction;
 pub mod speciation;
@@ -20,8 +20,14 @@ pub struct GenerationMember <T> where T: Genome{
     pub genome: T,
     pub created_generation: u32,
     pub number_of_generations_since_species_improved: u32,
-    #[serde(skip)]
-    pub species_hint: uuid::Uuid
+    #[serde(default)]
+    pub species_hint: uuid::Uuid,
+    #[serde(default)]
+    pub objective_fitness_variance: NeatFloat,
+    #[serde(default)]
+    pub objectives: Vec<Objective>,
+    #[serde(default)]
+    pub component_scores: Vec<ComponentScore>
 }
 
 impl<T>  GenerationMember<T> where T: Genome{
@@ -30,7 +36,10 @@ impl<T>  GenerationMember<T> where T: Genome{
             genome,
             created_generation,
             number_of_generations_since_species_improved: 0,
-            species_hint: uuid::Uuid::nil()
+            species_hint: uuid::Uuid::nil(),
+            objective_fitness_variance: 0.0,
+            objectives: Vec::new(),
+            component_scores: Vec::new()
         }
     }
 }
@@ -1,15 +1,13 @@
 use hashbrown::HashMap;
-use rand::Rng;
 use rayon::iter::IntoParallelRefIterator;
-use super::super::{genome::neat::{NeatGenome, connect_gene::ConnectGene, node_gene::NodeGene, node_genes::NodeGenes, connect_genes::ConnectGenes, neat_genes::NeatGenes}};
+use super::super::{genome::{crossover::Crossover, neat::{NeatGenome, connect_gene::ConnectGene, node_gene::NodeGene, node_genes::NodeGenes, connect_genes::ConnectGenes, neat_genes::NeatGenes}}, trainer::configuration::Configuration};
 use super::GenerationMember;
-use crate::{phenome::Phenome, common::{random::Random, NeatFloat}};
+use crate::{phenome::Phenome, common::{seeded_random::SeededRandom, NeatFloat}};
 pub struct Reproduction {
 }
 
 impl Reproduction {
-    pub fn reproduce(best_performing: &NeatGenome, other: &NeatGenome, species_hint: uuid::Uuid, reproduction_weights_from_fitter_probability: NeatFloat) -> NeatGenome {
-        let mut rng = rand::thread_rng();
+    pub fn reproduce(best_performing: &NeatGenome, other: &NeatGenome, reproduction_weights_from_fitter_probability: NeatFloat, random: &mut SeededRandom) -> NeatGenome {
         
         let best_connect_genes = &best_performing.genes.connect.to_vec();
         let best_connect_len = best_connect_genes.len(); 
@@ -20,7 +18,7 @@ impl Reproduction {
             let best_gene = &best_connect_genes[i];
             let other_gene = other_connect_genes.get_by_hash(best_gene.connection_hash);
             if other_gene.is_some() {
-                if Random::gen_bool(reproduction_weights_from_fitter_probability)
+                if random.gen_bool(reproduction_weights_from_fitter_probability)
                 {
                     new_connect_genes.push(best_gene.clone())
                 } else {
@@ -36,7 +34,7 @@ impl Reproduction {
         for best_gene in best_node_genes.iter() {
             let other_gene = other_node_genes.get_opt(best_gene.number);
             if other_gene.is_some() {
-                if Random::gen_bool(reproduction_weights_from_fitter_probability)
+                if random.gen_bool(reproduction_weights_from_fitter_probability)
                 {
                     new_node_genes.push(best_gene.clone())
                 } else {
@@ -49,7 +47,7 @@ impl Reproduction {
             objective_fitness: None,
             parents_objective_fitness: best_performing.objective_fitness,
             novelty: 0.0,
-            id: uuid::Uuid::new_v4(),
+            id: random.new_uuid(),
             genes: NeatGenes::new(NodeGenes::from_vec(&new_node_genes), ConnectGenes::from_vec(&new_connect_genes)),
             allow_mutation: true,
             cached_genetic_distance: HashMap::new(),
@@ -58,7 +56,7 @@ impl Reproduction {
         };
         new_genome
     }
-    pub fn reproduce_cross_species(best_performing: &NeatGenome, other: &NeatGenome, species_hint: uuid::Uuid, reproduction_weights_from_fitter_probability: NeatFloat) -> NeatGenome {
+    pub fn reproduce_cross_species(best_performing: &NeatGenome, other: &NeatGenome, reproduction_weights_from_fitter_probability: NeatFloat, random: &mut SeededRandom) -> NeatGenome {
         let best_connect_genes = best_performing.genes.connect.to_vec();
         let other_connect_genes = other.genes.connect.to_vec();
         let max_limit_connection = (best_connect_genes.len() + other_connect_genes.len()) / 2;
@@ -96,7 +94,7 @@ impl Reproduction {
             objective_fitness: None,
             parents_objective_fitness: best_performing.objective_fitness,
             novelty: 0.0,
-            id: uuid::Uuid::new_v4(),
+            id: random.new_uuid(),
             genes: NeatGenes::new(NodeGenes::from_vec(&new_node_genes), ConnectGenes::from_vec(&new_connect_genes)),
             allow_mutation: true,
             cached_genetic_distance: HashMap::new(),
@@ -107,4 +105,13 @@ impl Reproduction {
         new_genome.genes.cleanup_orphan_nodes();
         new_genome
     }
-}
\ No newline at end of file
+}
+
+impl Crossover for NeatGenome{
+    fn reproduce(best_performing: &Self, other: &Self, configuration: &Configuration, random: &mut SeededRandom) -> Self {
+        Reproduction::reproduce(best_performing, other, configuration.reproduction_weights_from_fitter_probability, random)
+    }
+    fn reproduce_cross_species(best_performing: &Self, other: &Self, configuration: &Configuration, random: &mut SeededRandom) -> Self {
+        Reproduction::reproduce_cross_species(best_performing, other, configuration.reproduction_weights_from_fitter_probability, random)
+    }
+}
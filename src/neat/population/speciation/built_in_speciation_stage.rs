@@ -0,0 +1,59 @@
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+use crate::neat::{genome::{genome::Genome, crossover::Crossover, mutation_history::MutationHistory}, population::GenerationMember, trainer::{configuration::Configuration, run_context::RunContext}};
+use super::{speciation::Speciation, speciation_stage::SpeciationStage};
+
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum BuiltInSpeciationStage{
+    UpdateSeedBank,
+    ClearSpeciesMembersAndSetPrimary,
+    AddBestMembersBackIn,
+    AssignSpecies,
+    CalculateSpeciesFitness,
+    RemoveSpeciesThatHaveNotImproved,
+    SetAllowedOffspring,
+    RemoveSpeciesWithNoOffspring,
+    RemoveMembersBelowSurvivalThreshold,
+    CrossSpeciesReproduction,
+    ProduceOffspringWithinSpecies,
+    GeneticallyEngineeredMembers,
+    AddNewSpeciesDuringRun,
+    RemoveOldGenerationMembers
+}
+
+impl BuiltInSpeciationStage{
+    pub fn default_pipeline() -> Vec<String>{
+        BuiltInSpeciationStage::iter().map(|stage| stage.get_stage_name().to_string()).collect()
+    }
+    pub fn from_name(name: &str) -> Option<Self>{
+        BuiltInSpeciationStage::iter().find(|stage| stage.get_stage_name() == name)
+    }
+    pub fn get_stage_name(&self) -> &'static str{
+        match self {
+            BuiltInSpeciationStage::UpdateSeedBank => "update_seed_bank",
+            BuiltInSpeciationStage::ClearSpeciesMembersAndSetPrimary => "clear_species_members_and_set_primary",
+            BuiltInSpeciationStage::AddBestMembersBackIn => "add_best_members_back_in",
+            BuiltInSpeciationStage::AssignSpecies => "assign_species",
+            BuiltInSpeciationStage::CalculateSpeciesFitness => "calculate_species_fitness",
+            BuiltInSpeciationStage::RemoveSpeciesThatHaveNotImproved => "remove_species_that_have_not_improved",
+            BuiltInSpeciationStage::SetAllowedOffspring => "set_allowed_offspring",
+            BuiltInSpeciationStage::RemoveSpeciesWithNoOffspring => "remove_species_with_no_offspring",
+            BuiltInSpeciationStage::RemoveMembersBelowSurvivalThreshold => "remove_members_below_survival_threshold",
+            BuiltInSpeciationStage::CrossSpeciesReproduction => "cross_species_reproduction",
+            BuiltInSpeciationStage::ProduceOffspringWithinSpecies => "produce_offspring_within_species",
+            BuiltInSpeciationStage::GeneticallyEngineeredMembers => "genetically_engineered_members",
+            BuiltInSpeciationStage::AddNewSpeciesDuringRun => "add_new_species_during_run",
+            BuiltInSpeciationStage::RemoveOldGenerationMembers => "remove_old_generation_members"
+        }
+    }
+}
+
+impl<T> SpeciationStage<T> for BuiltInSpeciationStage where T: Genome + Crossover + MutationHistory{
+    fn get_name(&self) -> &str {
+        self.get_stage_name()
+    }
+
+    fn run(&self, members: &mut Vec<GenerationMember<T>>, configuration: &Configuration, run_context: &mut RunContext<T>) {
+        Speciation::run_built_in_stage(*self, members, configuration, run_context)
+    }
+}
@@ -1,6 +1,6 @@
 use uuid::Uuid;
 
-use crate::{neat::{trainer::{configuration::Configuration, run_context::RunContext}, population::{GenerationMember, members_lookup::MembersLookup}, genome::neat::{NeatGenome, mutation::Mutation}}, common::NeatFloat};
+use crate::{neat::{trainer::{configuration::Configuration, run_context::RunContext}, population::{GenerationMember, members_lookup::MembersLookup}, genome::{genome::Genome, mutation_history::MutationHistory, neat::mutation::Mutation}}, common::NeatFloat};
 
 pub struct GeneticallyEngineeredMemberMaker;
 pub struct MutationGroup{
@@ -14,30 +14,34 @@ pub struct MutationWithDelta{
 }
 
 impl GeneticallyEngineeredMemberMaker{
-    pub fn make_genetically_engineered_members_v1(members: &mut Vec<GenerationMember<NeatGenome>>, mut configuration: &Configuration, run_context: &mut RunContext){
+    pub fn make_genetically_engineered_members_v1<T>(members: &mut Vec<GenerationMember<T>>, mut configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + MutationHistory{
         let members_lookup = MembersLookup::new(members);
+        let random = &mut run_context.random;
         
-        for (species_id, species )in run_context.species_index.iter_mut(){
+        /* species share the run's random generator, so they are visited in id order to give the same result after a run is loaded */
+        let mut species_in_order: Vec<_> = run_context.species_index.iter_mut().collect();
+        species_in_order.sort_unstable_by_key(|(species_id, _)| **species_id);
+        for (species_id, species) in species_in_order{
 
-            let mut best_member: Option<&NeatGenome> = None;
+            let mut best_member: Option<&T> = None;
             let mut mutations_for_members_where_fitness_improved: Vec<(NeatFloat, Mutation)> = Vec::new();
             for member in species.members.iter_mut(){
                 let member = &members[members_lookup.get_array_index(member.id)];
-                if member.genome.objective_fitness.is_none() || member.genome.parents_objective_fitness.is_none(){
+                if member.genome.get_fitness().is_none() || member.genome.get_parents_objective_fitness().is_none(){
                     continue;
                 }
 
                 //keep track of the best member
-                if best_member.is_none() || (member.genome.objective_fitness.unwrap() > best_member.unwrap().objective_fitness.unwrap()) {
+                if best_member.is_none() || (member.genome.get_fitness().unwrap() > best_member.unwrap().get_fitness().unwrap()) {
                     best_member = Some(&member.genome);
                 }
                 
                 //add mutations along with fitness delta to list
-                let objective_fitness = member.genome.objective_fitness.unwrap();
-                let parents_objective_fitness = member.genome.parents_objective_fitness.unwrap();
+                let objective_fitness = member.genome.get_fitness().unwrap();
+                let parents_objective_fitness = member.genome.get_parents_objective_fitness().unwrap();
                 if objective_fitness > parents_objective_fitness{
                     let parent_child_fitness_delta = objective_fitness - parents_objective_fitness;
-                    let mutations =  member.genome.mutations.iter().map(|m| (parent_child_fitness_delta, m.clone()))
+                    let mutations =  member.genome.get_mutations().iter().map(|m| (parent_child_fitness_delta, m.clone()))
                     .collect::<Vec<(NeatFloat, Mutation)>>();
                     for m in mutations {
                         mutations_for_members_where_fitness_improved.push(m);
@@ -69,20 +73,23 @@ impl GeneticallyEngineeredMemberMaker{
 
             if best_member.is_some(){
                 let mut mutant_genome = best_member.unwrap().clone();
-                mutant_genome.id = uuid::Uuid::new_v4();
+                mutant_genome.set_id(random.new_uuid());
 
                 let total_grouped_mutations = mutations_grouped.len();
                 for mutation_group in mutations_grouped.iter_mut().take(total_grouped_mutations / 2) {
                     mutation_group.parameter_values.sort_by(|a, b| b.fitness_delta.partial_cmp(&a.fitness_delta).unwrap());
                     let top_mutation= &mutation_group.parameter_values[0];
 
-                    Mutation::apply_optimization_to_genome(top_mutation.mutation, &mut mutant_genome);
+                    mutant_genome.apply_mutation_optimization(top_mutation.mutation);
                 }
                 members.push(GenerationMember{ 
                     genome: mutant_genome, 
                     created_generation: run_context.current_generation,
                     number_of_generations_since_species_improved: 0,
-                    species_hint: species_id.clone()});
+                    species_hint: species_id.clone(),
+                    objective_fitness_variance: 0.0,
+                    objectives: Vec::new(),
+                    component_scores: Vec::new()});
             }
         }
     }
@@ -1,6 +1,10 @@
 pub mod speciation;
+pub mod speciation_stage;
+pub mod built_in_speciation_stage;
 pub mod species;
 pub mod species_member;
 pub mod species_metric;
 pub mod species_member_terminator;
+pub mod selection_strategy;
+pub mod parent_selector;
 mod genetically_engineered_member_maker;
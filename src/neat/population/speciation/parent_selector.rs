@@ -0,0 +1,304 @@
+use std::cmp::Ordering;
+use crate::{common::{NeatFloat, seeded_random::SeededRandom}, neat::{genome::genome::Genome, population::GenerationMember, trainer::{configuration::Configuration, fitness::{objective::Objective, pareto_front::{ParetoFront, ParetoRank}}}}};
+use super::selection_strategy::SelectionStrategy;
+
+/*
+Picks parents from a fixed set of candidates and returns their index in it. Everything a strategy needs is
+worked out up front: for lexicase selection the component scores of the candidates are laid out as a table,
+a candidate that did not report a component gets the worst score for it, roulette and rank selection keep
+cumulative weights and truncation keeps the candidates ordered fittest first.
+With multiple objectives the strategy is not used, parents are picked by the NSGA-II crowded comparison
+binary tournament on the pareto ranks of the candidates.
+*/
+pub struct ParentSelector{
+    selection_strategy: SelectionStrategy,
+    multi_objective_enabled: bool,
+    number_of_candidates: usize,
+    fitnesses: Vec<NeatFloat>,
+    fittest_first: Vec<usize>,
+    cumulative_weights: Vec<NeatFloat>,
+    tournament_size: usize,
+    truncated_len: usize,
+    scores_by_component: Vec<Vec<NeatFloat>>,
+    epsilons: Vec<NeatFloat>,
+    pareto_ranks: Vec<ParetoRank>
+}
+
+impl ParentSelector{
+    pub fn new<T>(candidates: &[&GenerationMember<T>], configuration: &Configuration) -> Self where T: Genome{
+        let selection_strategy = configuration.speciation_selection_strategy;
+        let fitnesses: Vec<NeatFloat> = candidates.iter().map(|c| c.genome.get_fitness().unwrap_or(NeatFloat::MIN)).collect();
+        let mut fittest_first: Vec<usize> = (0..candidates.len()).collect();
+        fittest_first.sort_by(|a, b| fitnesses[*b].total_cmp(&fitnesses[*a]).then(a.cmp(b)));
+
+        let cumulative_weights = match selection_strategy {
+            SelectionStrategy::Roulette => {
+                let least_fit = fitnesses.iter().copied().fold(NeatFloat::MAX, NeatFloat::min);
+                Self::get_cumulative_weights(fitnesses.iter().map(|f| f - least_fit))
+            },
+            SelectionStrategy::Rank => {
+                let mut ranks = vec![0.0; candidates.len()];
+                for (position, candidate) in fittest_first.iter().enumerate(){
+                    ranks[*candidate] = (candidates.len() - position) as NeatFloat;
+                }
+                Self::get_cumulative_weights(ranks.into_iter())
+            },
+            _ => Vec::new()
+        };
+
+        let mut scores_by_component = Vec::new();
+        let mut epsilons = Vec::new();
+
+        if selection_strategy == SelectionStrategy::Lexicase || selection_strategy == SelectionStrategy::EpsilonLexicase{
+            let mut component_ids: Vec<u32> = candidates.iter().flat_map(|c| c.component_scores.iter().map(|s| s.component_id)).collect();
+            component_ids.sort_unstable();
+            component_ids.dedup();
+
+            for component_id in component_ids{
+                let scores: Vec<NeatFloat> = candidates.iter().map(|c| {
+                    c.component_scores.iter().find(|s| s.component_id == component_id).map(|s| s.score).unwrap_or(NeatFloat::MIN)
+                }).collect();
+                epsilons.push(if selection_strategy == SelectionStrategy::EpsilonLexicase { Self::get_median_absolute_deviation(&scores) } else { 0.0 });
+                scores_by_component.push(scores);
+            }
+        }
+
+        let pareto_ranks = if configuration.multi_objective_enabled {
+            let objectives: Vec<&[Objective]> = candidates.iter().map(|c| c.objectives.as_slice()).collect();
+            ParetoFront::rank(&objectives)
+        }else{
+            Vec::new()
+        };
+
+        Self {
+            selection_strategy,
+            multi_objective_enabled: configuration.multi_objective_enabled,
+            number_of_candidates: candidates.len(),
+            fitnesses,
+            fittest_first,
+            cumulative_weights,
+            tournament_size: configuration.speciation_tournament_size,
+            truncated_len: usize::max(1, NeatFloat::ceil(candidates.len() as NeatFloat * configuration.speciation_truncation_ratio) as usize),
+            scores_by_component,
+            epsilons,
+            pareto_ranks
+        }
+    }
+    pub fn select(&self, random: &mut SeededRandom) -> usize{
+        if self.multi_objective_enabled{
+            return self.select_crowded_tournament(random);
+        }
+        match self.selection_strategy {
+            SelectionStrategy::Random => random.gen_range_usize(0, self.number_of_candidates),
+            SelectionStrategy::Lexicase | SelectionStrategy::EpsilonLexicase => self.select_lexicase(random),
+            SelectionStrategy::Tournament => self.select_tournament(random),
+            SelectionStrategy::Roulette | SelectionStrategy::Rank => self.select_weighted(random),
+            SelectionStrategy::Truncation => self.fittest_first[random.gen_range_usize(0, usize::min(self.truncated_len, self.number_of_candidates))]
+        }
+    }
+    /* whether the candidate at first makes a better parent than the candidate at second */
+    pub fn is_better(&self, first: usize, second: usize) -> bool{
+        if self.multi_objective_enabled{
+            ParetoFront::compare(&self.pareto_ranks[first], &self.pareto_ranks[second]) == Ordering::Less
+        }else{
+            self.fitnesses[first] > self.fitnesses[second]
+        }
+    }
+    fn select_crowded_tournament(&self, random: &mut SeededRandom) -> usize{
+        let first = random.gen_range_usize(0, self.number_of_candidates);
+        let second = random.gen_range_usize(0, self.number_of_candidates);
+        if self.is_better(second, first) { second } else { first }
+    }
+    fn select_tournament(&self, random: &mut SeededRandom) -> usize{
+        let mut winner = random.gen_range_usize(0, self.number_of_candidates);
+        for _ in 1..self.tournament_size{
+            let challenger = random.gen_range_usize(0, self.number_of_candidates);
+            if self.fitnesses[challenger] > self.fitnesses[winner] {
+                winner = challenger;
+            }
+        }
+        winner
+    }
+    /* when every weight is zero, as in a roulette of equally fit candidates, any candidate can be picked */
+    fn select_weighted(&self, random: &mut SeededRandom) -> usize{
+        let total = *self.cumulative_weights.last().unwrap();
+        if total <= 0.0 {
+            return random.gen_range_usize(0, self.number_of_candidates);
+        }
+        let spin = random.gen_range_f32(0.0, total);
+        self.cumulative_weights.iter().position(|w| spin < *w).unwrap_or(self.number_of_candidates - 1)
+    }
+    fn get_cumulative_weights<I>(weights: I) -> Vec<NeatFloat> where I: Iterator<Item = NeatFloat>{
+        let mut total = 0.0;
+        weights.map(|w| {
+            total += w;
+            total
+        }).collect()
+    }
+    fn select_lexicase(&self, random: &mut SeededRandom) -> usize{
+        let mut pool: Vec<usize> = (0..self.number_of_candidates).collect();
+        let mut components: Vec<usize> = (0..self.scores_by_component.len()).collect();
+
+        while pool.len() > 1 && !components.is_empty(){
+            let component = components.swap_remove(random.gen_range_usize(0, components.len()));
+            let scores = &self.scores_by_component[component];
+            let best = pool.iter().map(|c| scores[*c]).fold(NeatFloat::MIN, NeatFloat::max);
+            pool.retain(|c| scores[*c] >= best - self.epsilons[component]);
+        }
+
+        pool[random.gen_range_usize(0, pool.len())]
+    }
+    fn get_median_absolute_deviation(scores: &[NeatFloat]) -> NeatFloat{
+        let median = Self::get_median(scores.to_vec());
+        Self::get_median(scores.iter().map(|s| (s - median).abs()).collect())
+    }
+    fn get_median(mut values: Vec<NeatFloat>) -> NeatFloat{
+        values.sort_by(|a, b| a.total_cmp(b));
+        let middle = values.len() / 2;
+        if values.len() % 2 == 0 {
+            (values[middle - 1] + values[middle]) / 2.0
+        }else{
+            values[middle]
+        }
+    }
+}
+
+#[cfg(test)]
+fn member_with_scores(scores: &[NeatFloat]) -> GenerationMember<crate::neat::genome::neat::NeatGenome>{
+    use crate::neat::{trainer::{node_conf::NodeConf, run_context::RunContext, fitness::component_score::ComponentScore}, genome::neat::NeatGenome};
+
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0);
+    let mut run_context = RunContext::new(configuration.node_genes.len()+1, 0);
+    let mut member = GenerationMember::new(NeatGenome::minimal(&configuration, &mut run_context), 0);
+    member.component_scores = scores.iter().enumerate().map(|(component_id, score)| ComponentScore::new(component_id as u32, *score)).collect();
+    member.genome.set_objective_fitness(scores.iter().sum());
+    member
+}
+
+#[cfg(test)]
+fn count_selections(candidates: &[&GenerationMember<crate::neat::genome::neat::NeatGenome>], selection_strategy: SelectionStrategy) -> Vec<usize>{
+    use crate::neat::trainer::node_conf::NodeConf;
+
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0).speciation_selection_strategy(selection_strategy);
+    let selector = ParentSelector::new(candidates, &configuration);
+    let mut random = SeededRandom::new(Some(3));
+    let mut selected = vec![0; candidates.len()];
+    for _ in 0..1000{
+        selected[selector.select(&mut random)] += 1;
+    }
+    selected
+}
+
+#[test]
+fn lexicase_only_selects_members_that_are_best_on_some_ordering() {
+    let specialist_a = member_with_scores(&[1.0, 0.0, 0.5]);
+    let specialist_b = member_with_scores(&[0.0, 1.0, 0.5]);
+    let generalist = member_with_scores(&[0.6, 0.6, 0.6]);
+    let dominated = member_with_scores(&[0.5, 0.5, 0.5]);
+
+    let selected = count_selections(&[&specialist_a, &specialist_b, &generalist, &dominated], SelectionStrategy::Lexicase);
+
+    assert!(selected[0] > 0 && selected[1] > 0 && selected[2] > 0);
+    assert_eq!(selected[3], 0);
+}
+
+#[test]
+fn fitness_based_strategies_prefer_fitter_members() {
+    use strum::IntoEnumIterator;
+
+    let members: Vec<_> = [0.0, 1.0, 2.0, 3.0].iter().map(|f| member_with_scores(&[*f])).collect();
+    let candidates: Vec<_> = members.iter().collect();
+
+    for selection_strategy in [SelectionStrategy::Tournament, SelectionStrategy::Roulette, SelectionStrategy::Rank, SelectionStrategy::Truncation]{
+        let selected = count_selections(&candidates, selection_strategy);
+        assert!(selected[3] > selected[0], "{:?} selected {:?}", selection_strategy, selected);
+    }
+
+    let truncation = count_selections(&candidates, SelectionStrategy::Truncation);
+    assert_eq!(truncation[0] + truncation[1], 0);
+    let roulette = count_selections(&candidates, SelectionStrategy::Roulette);
+    assert_eq!(roulette[0], 0);
+    for selection_strategy in SelectionStrategy::iter(){
+        assert!(count_selections(&candidates, selection_strategy).iter().sum::<usize>() == 1000);
+    }
+}
+
+#[test]
+fn multi_objective_parents_are_picked_by_crowded_comparison() {
+    use crate::neat::trainer::{node_conf::NodeConf, fitness::objective::Objective};
+
+    /* the dominated member has by far the largest summed fitness, which the pareto ranks ignore */
+    let mut members: Vec<_> = [(1.0, 5.0), (5.0, 1.0), (3.0, 3.0), (2.0, 2.0)].iter().map(|(speed, accuracy)| {
+        let mut member = member_with_scores(&[]);
+        member.objectives = vec![Objective::new("speed", *speed), Objective::new("accuracy", *accuracy)];
+        member
+    }).collect();
+    members[3].genome.set_objective_fitness(100.0);
+    let candidates: Vec<_> = members.iter().collect();
+
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .multi_objective_enabled(true)
+        .speciation_selection_strategy(SelectionStrategy::Lexicase);
+    let selector = ParentSelector::new(&candidates, &configuration);
+    assert!(selector.is_better(0, 3) && selector.is_better(2, 3));
+    /* the ends of the front are less crowded than the member between them */
+    assert!(selector.is_better(0, 2) && !selector.is_better(2, 1));
+
+    let mut random = SeededRandom::new(Some(3));
+    let mut selected = vec![0; candidates.len()];
+    for _ in 0..1000{
+        selected[selector.select(&mut random)] += 1;
+    }
+    /* the dominated member only wins a tournament against itself */
+    assert!(selected[3] > 0 && selected[3] < 100, "selected {:?}", selected);
+    assert!(selected[0] > selected[2] && selected[1] > selected[2], "selected {:?}", selected);
+}
+
+#[test]
+fn lexicase_selection_keeps_the_per_case_scores() {
+    use crate::neat::trainer::{neat_trainer::NeatTrainer, test_fixtures::{xor_configuration, xor_fitness}};
+    use crate::neat::population::speciation::selection_strategy::SelectionStrategy;
+
+    for selection_strategy in [SelectionStrategy::Lexicase, SelectionStrategy::EpsilonLexicase]{
+        let configuration = xor_configuration(23).population_size(100)
+            .speciation_selection_strategy(selection_strategy);
+
+        let mut neat_trainer = NeatTrainer::new(configuration);
+        for _ in 0..10{
+            neat_trainer.new_generation();
+            neat_trainer.evaluate_generation(&xor_fitness);
+        }
+
+        for member in &neat_trainer.members{
+            assert_eq!(member.component_scores.len(), 4);
+            let summed = member.component_scores.iter().map(|s| s.score).sum::<NeatFloat>();
+            assert!((summed - member.genome.get_fitness().unwrap()).abs() < 0.001);
+        }
+    }
+}
+
+#[test]
+fn every_selection_strategy_can_run() {
+    use crate::neat::trainer::{neat_trainer::NeatTrainer, test_fixtures::{xor_configuration, run_xor_generations}};
+    use crate::neat::population::speciation::selection_strategy::SelectionStrategy;
+    use strum::IntoEnumIterator;
+
+    for selection_strategy in SelectionStrategy::iter(){
+        let configuration = xor_configuration(29)
+            .speciation_selection_strategy(selection_strategy);
+
+        let mut neat_trainer = NeatTrainer::new(configuration);
+        run_xor_generations(&mut neat_trainer, 5);
+        assert!(neat_trainer.members.iter().all(|m| m.genome.get_fitness().is_some()), "{:?}", selection_strategy);
+    }
+}
+
+#[test]
+fn a_fitness_that_is_not_a_number_does_not_break_ordering_strategies() {
+    let members: Vec<_> = [1.0, NeatFloat::NAN, 2.0].iter().map(|f| member_with_scores(&[*f])).collect();
+    let candidates: Vec<_> = members.iter().collect();
+
+    for selection_strategy in [SelectionStrategy::EpsilonLexicase, SelectionStrategy::Rank, SelectionStrategy::Truncation]{
+        assert_eq!(count_selections(&candidates, selection_strategy).iter().sum::<usize>(), 1000);
+    }
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+/* how parents are picked from a species or from the members used for cross species reproduction */
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, EnumIter)]
+pub enum SelectionStrategy{
+    /* any member, the fitter of the two parents passes on its structure */
+    #[default]
+    Random,
+    /* the member that is best on a random ordering of the objective fitness components */
+    Lexicase,
+    /* lexicase where members within the median absolute deviation of the best score of a component also pass */
+    EpsilonLexicase,
+    /* the fittest of speciation_tournament_size random members */
+    Tournament,
+    /* chance proportional to the fitness above the least fit candidate */
+    Roulette,
+    /* chance proportional to the position when ordered from least to most fit */
+    Rank,
+    /* any member of the fittest speciation_truncation_ratio of the candidates */
+    Truncation
+}
@@ -1,16 +1,20 @@
 use std::cmp::Ordering;
-use bevy::prelude::Color;
 use hashbrown::{HashMap as HashMap, HashSet};
 use uuid::Uuid;
 use rayon::prelude::*;
-use rand::{Rng, seq::SliceRandom};
 use serde::{Serialize, Deserialize};
-use crate::{phenome::Phenome, common::{random::Random, NeatFloat, event_stream::{event::{EventType, Event}, event_recorder::EventRecorder}, types::NeatFloatExtensions}, neat::{population::{members_lookup::MembersLookup, GenerationMember, reproduction::Reproduction, speciation::{species_member::SpeciesMember, species_metric::SpeciesMetric, species_member_terminator::SpeciesMemberTerminator, genetically_engineered_member_maker::GeneticallyEngineeredMemberMaker}}, genome::{neat::{NeatGenome, mutation_mode::MutationMode}, genome::Genome}, trainer::{configuration::{Configuration, OffSpringMode}, run_context::RunContext}}};
+use crate::{phenome::Phenome, common::{seeded_random::SeededRandom, NeatFloat, event_stream::{event::{EventType, Event}, event_recorder::EventRecorder}, types::NeatFloatExtensions}, neat::{population::{members_lookup::MembersLookup, GenerationMember, reproduction::Reproduction, speciation::{species_member::SpeciesMember, species_metric::SpeciesMetric, species_member_terminator::SpeciesMemberTerminator, parent_selector::ParentSelector, speciation_stage::SpeciationStage, built_in_speciation_stage::BuiltInSpeciationStage, genetically_engineered_member_maker::GeneticallyEngineeredMemberMaker}}, genome::{neat::mutation_mode::MutationMode, genome::Genome, crossover::Crossover, mutation_history::MutationHistory}, trainer::{configuration::{Configuration, OffSpringMode}, config_error::ConfigError, run_context::RunContext, fitness::{fitness_shaping::FitnessShaping, objective::Objective, pareto_front::ParetoFront}}}};
 use super::species::Species;
 pub struct Speciation;
 
 impl Speciation{
-    pub fn speciate(mut members: &mut Vec<GenerationMember<NeatGenome>>, configuration: &Configuration, run_context: &mut RunContext) {
+    pub fn speciate<T>(members: &mut Vec<GenerationMember<T>>, configuration: &Configuration, run_context: &mut RunContext<T>) -> Result<(), ConfigError> where T: Genome + Crossover + MutationHistory {
+        Self::speciate_with_stages(members, configuration, run_context, &[])
+    }
+    pub fn speciate_with_stages<T>(members: &mut Vec<GenerationMember<T>>, configuration: &Configuration, run_context: &mut RunContext<T>, custom_stages: &[Box<dyn SpeciationStage<T>>]) -> Result<(), ConfigError> where T: Genome + Crossover + MutationHistory {
+        let custom_stage_names: Vec<&str> = custom_stages.iter().map(|s| s.get_name()).collect();
+        configuration.validate_speciation_pipeline(&custom_stage_names)?;
+
         if members.len() == 0 || run_context.current_generation == 0 {
             run_context.reset();
             members.clear();
@@ -18,11 +22,10 @@ impl Speciation{
 
             let population_size = configuration.population_size;
             for _ in 0..population_size{
-                let mut new_genome = NeatGenome::minimal(configuration, run_context);
+                let mut new_genome = T::minimal(configuration, run_context);
     
                 new_genome.mutate(configuration, run_context, MutationMode::Optimistic);
 
-                new_genome.objective_fitness = None;
                 let member = GenerationMember::new(new_genome, run_context.current_generation);
                 members.push(member)
             }
@@ -31,35 +34,48 @@ impl Speciation{
                 Self::create_initial_species(members, configuration, run_context);
             }
 
-            return;
+            return Ok(());
         }
         run_context.new_species_created_on_last_generation = 0;
         Self::check_objective_fitness_set(members);
-        Self::update_seed_bank(members, configuration, run_context);
-        Self::clear_species_members_and_set_primary(members, configuration, run_context);
-        Self::add_best_members_back_in(members, configuration, run_context);
-        Self::put_members_in_existing_species_or_create_new(members, configuration, run_context);
-        Self::calculate_species_fitness(&mut members, configuration, run_context);
-        Self::remove_species_that_have_not_improved(configuration, run_context);
-        Self::set_allowed_offspring(&mut members, configuration, run_context);
-        Self::remove_species_with_no_offspring( configuration, run_context);
-        Self::remove_members_of_species_below_threshold(configuration, run_context);
-        Self::cross_species_reproduction(&mut members, configuration, run_context); 
-        Self::produce_offspring_within_species(&mut members, configuration, run_context); 
-        GeneticallyEngineeredMemberMaker::make_genetically_engineered_members_v1(members, configuration, run_context);
-        Self::add_new_species_during_run_if_required(members, configuration, run_context);
-        Self::remove_old_generation_members(&mut members, configuration, run_context);     
+
+        for stage_name in &configuration.speciation_pipeline{
+            match custom_stages.iter().find(|s| s.get_name() == stage_name) {
+                Some(custom_stage) => custom_stage.run(members, configuration, run_context),
+                None => if let Some(stage) = BuiltInSpeciationStage::from_name(stage_name) {
+                    Self::run_built_in_stage(stage, members, configuration, run_context);
+                }
+            }
+        }
+        Ok(())
+    }
+    pub fn run_built_in_stage<T>(stage: BuiltInSpeciationStage, members: &mut Vec<GenerationMember<T>>, configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + Crossover + MutationHistory {
+        match stage {
+            BuiltInSpeciationStage::UpdateSeedBank => Self::update_seed_bank(members, configuration, run_context),
+            BuiltInSpeciationStage::ClearSpeciesMembersAndSetPrimary => Self::clear_species_members_and_set_primary(members, configuration, run_context),
+            BuiltInSpeciationStage::AddBestMembersBackIn => Self::add_best_members_back_in(members, configuration, run_context),
+            BuiltInSpeciationStage::AssignSpecies => Self::put_members_in_existing_species_or_create_new(members, configuration, run_context),
+            BuiltInSpeciationStage::CalculateSpeciesFitness => Self::calculate_species_fitness(members, configuration, run_context),
+            BuiltInSpeciationStage::RemoveSpeciesThatHaveNotImproved => Self::remove_species_that_have_not_improved(configuration, run_context),
+            BuiltInSpeciationStage::SetAllowedOffspring => Self::set_allowed_offspring(members, configuration, run_context),
+            BuiltInSpeciationStage::RemoveSpeciesWithNoOffspring => Self::remove_species_with_no_offspring(configuration, run_context),
+            BuiltInSpeciationStage::RemoveMembersBelowSurvivalThreshold => Self::remove_members_of_species_below_threshold(configuration, run_context),
+            BuiltInSpeciationStage::CrossSpeciesReproduction => Self::cross_species_reproduction(members, configuration, run_context),
+            BuiltInSpeciationStage::ProduceOffspringWithinSpecies => Self::produce_offspring_within_species(members, configuration, run_context),
+            BuiltInSpeciationStage::GeneticallyEngineeredMembers => GeneticallyEngineeredMemberMaker::make_genetically_engineered_members_v1(members, configuration, run_context),
+            BuiltInSpeciationStage::AddNewSpeciesDuringRun => Self::add_new_species_during_run_if_required(members, configuration, run_context),
+            BuiltInSpeciationStage::RemoveOldGenerationMembers => Self::remove_old_generation_members(members, configuration, run_context)
+        }
     }
-    fn update_seed_bank(mut members: &mut Vec<GenerationMember<NeatGenome>>, configuration: &Configuration, run_context: &mut RunContext) {
+    fn update_seed_bank<T>(mut members: &mut Vec<GenerationMember<T>>, configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + Crossover + MutationHistory {
         if configuration.speciation_use_best_seed_bank.is_none(){
             return;
         }
         run_context.seed_bank.update_seed_bank(members);
     }
-    fn clear_species_members_and_set_primary(mut members: &mut Vec<GenerationMember<NeatGenome>>, configuration: &Configuration, run_context: &mut RunContext) {
+    fn clear_species_members_and_set_primary<T>(mut members: &mut Vec<GenerationMember<T>>, configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + Crossover + MutationHistory {
         
-        let members_map:HashMap<uuid::Uuid, &GenerationMember<NeatGenome>> = HashMap::from_iter(members.iter().map(|m| (m.genome.id, m)));
-        let mut rng = rand::thread_rng();
+        let members_map:HashMap<uuid::Uuid, &GenerationMember<T>> = HashMap::from_iter(members.iter().map(|m| (m.genome.get_id(), m)));
         for (_ ,species) in run_context.species_index.iter_mut(){
             if species.members.len() == 0 {
                 continue;
@@ -74,30 +90,30 @@ impl Speciation{
             species.primary = owned_primary_generation_member;
         }
     }
-    fn add_new_species_during_run_if_required(members: &mut Vec<GenerationMember<NeatGenome>>, mut configuration: &Configuration, run_context: &mut RunContext){
+    fn add_new_species_during_run_if_required<T>(members: &mut Vec<GenerationMember<T>>, mut configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + Crossover + MutationHistory {
         if !configuration.speciation_add_new_species_during_run{
             return;
         }
       
         if run_context.species_index.len() < configuration.target_species as usize{
             for i in 1..configuration.population_size{
-                let mut new_genome = NeatGenome::minimal(configuration, run_context);
+                let mut new_genome = T::minimal(configuration, run_context);
                 new_genome.mutate(configuration, run_context, MutationMode::FoolHardy);
-                new_genome.objective_fitness = None;
                 members.push(GenerationMember::new(new_genome, run_context.current_generation))
             }
         }
     }
-    fn create_initial_species(members: &mut Vec<GenerationMember<NeatGenome>>, mut configuration: &Configuration, run_context: &mut RunContext){
+    fn create_initial_species<T>(members: &mut Vec<GenerationMember<T>>, mut configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + Crossover + MutationHistory {
         
         let threshold = configuration.speciation_genetic_distance_threshold;
         let mut total_speciation_distance_approx = 0.0;
      
         let mr_one = &members[0];
+        let first_species_id = run_context.random.new_uuid();
         let mut species = &mut run_context.species_index;
-        let members = members.iter().map(|m| SpeciesMember::new(m.genome.id, 0.0, 0.0, false)).collect::<Vec<SpeciesMember>>();
+        let members = members.iter().map(|m| SpeciesMember::new(m.genome.get_id(), 0.0, 0.0, false)).collect::<Vec<SpeciesMember>>();
         let first_species = Species::new(
-            uuid::Uuid::new_v4(),
+            first_species_id,
             mr_one.clone(),
             members,
             run_context.current_generation,
@@ -105,9 +121,12 @@ impl Speciation{
         );
         species.insert(first_species.id, first_species);
     }
-    fn put_members_in_existing_species_or_create_new(members: &mut Vec<GenerationMember<NeatGenome>>, mut configuration: &Configuration, run_context: &mut RunContext){
+    fn put_members_in_existing_species_or_create_new<T>(members: &mut Vec<GenerationMember<T>>, mut configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + Crossover + MutationHistory {
         
         let threshold = configuration.speciation_genetic_distance_threshold;
+        /* the first close enough species wins, so species are visited in id order to give the same result after a run is loaded */
+        let mut species_ids: Vec<Uuid> = run_context.species_index.keys().copied().collect();
+        species_ids.sort_unstable();
         
         for outer in members.iter_mut() {
             let mut species_number = 0;
@@ -122,19 +141,20 @@ impl Speciation{
                 }
                 
                 if genetic_distance < threshold {
-                        hint_species_unwrapped.members.push(SpeciesMember::new(outer.genome.id, outer.genome.objective_fitness.unwrap(), outer.genome.novelty, false ));
+                        hint_species_unwrapped.members.push(SpeciesMember::from_generation_member(outer));
                         species_found = true;
                         continue;
                 }
             }
             
-            for (s, existing_species) in  run_context.species_index.iter_mut(){
+            for s in species_ids.iter(){
+                let existing_species = run_context.species_index.get_mut(s).unwrap();
                 let genetic_distance = outer.genome.get_genetic_difference_distance_from(&existing_species.primary.genome, threshold);
                 if(genetic_distance == NeatFloat::INFINITY || genetic_distance.is_nan() ||genetic_distance < 0.0){
                     panic!("genetic distance inf or nan");
                 }
                 if genetic_distance < threshold {
-                        existing_species.members.push(SpeciesMember::new(outer.genome.id, outer.genome.objective_fitness.unwrap(), outer.genome.novelty, false));
+                        existing_species.members.push(SpeciesMember::from_generation_member(outer));
                         species_found = true;
                         outer.species_hint = s.clone();
                         break;
@@ -143,14 +163,15 @@ impl Speciation{
             }
 
             if !species_found{
-                let key = uuid::Uuid::new_v4();
+                let key = run_context.random.new_uuid();
                 run_context.species_index.insert(key, 
                 Species::new(key, (*outer).clone(),
-                    vec![SpeciesMember::new(outer.genome.id, outer.genome.objective_fitness.unwrap(), outer.genome.novelty, false)],
+                    vec![SpeciesMember::from_generation_member(outer)],
                     run_context.current_generation,
                     run_context.current_generation + configuration.speciation_new_species_protected_for_generations
                 ));
                 run_context.new_species_created_on_last_generation += 1;
+                species_ids.push(key);
                 outer.species_hint = key;
                 if EventRecorder::has_subscription(configuration, EventType::SPECIATION_SPECIES_NEW){
                     EventRecorder::record_event(configuration, &Event::species_species_new(run_context.current_generation, &key));
@@ -158,14 +179,15 @@ impl Speciation{
             }
         }
     }
-    fn calculate_species_fitness(members: &mut Vec<GenerationMember<NeatGenome>>, configuration: &Configuration, run_context: &mut RunContext){
+    fn calculate_species_fitness<T>(members: &mut Vec<GenerationMember<T>>, configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + Crossover + MutationHistory {
+        let shaped_range = Self::shape_objective_fitness(configuration, run_context);
         let mut all_members_max_objective_fitness: Option<NeatFloat> = None;
         let mut all_members_min_objective_fitness: Option<NeatFloat> = None;
         let mut all_members_max_outcome_novelty: NeatFloat = 0.0;
         let mut all_members_min_outcome_novelty: NeatFloat = 0.0;
         for m in members.iter().map(|m| &m.genome){
-            let objective_fitness = m.objective_fitness.unwrap();
-            let outcome_novelty = m.novelty;
+            let objective_fitness = m.get_fitness().unwrap();
+            let outcome_novelty = m.get_novelty();
             if  all_members_max_objective_fitness.is_none() || objective_fitness > all_members_max_objective_fitness.unwrap() {
                 all_members_max_objective_fitness = Some(objective_fitness);
             }
@@ -180,8 +202,13 @@ impl Speciation{
             }
         }
      
-        let all_members_min_fitness = all_members_min_objective_fitness.unwrap();
-        let worst_ever = run_context.worst_objective_fitness_so_far.unwrap_or(all_members_min_fitness);
+        let raw_all_members_min_fitness = all_members_min_objective_fitness.unwrap();
+        let raw_worst_ever = run_context.worst_objective_fitness_so_far.unwrap_or(raw_all_members_min_fitness);
+        /* shaped fitness is only compared with other shaped fitness, the least fit member is the comparison point */
+        let (all_members_min_fitness, all_members_max_fitness, worst_ever) = match shaped_range {
+            Some((min, max)) => (min, max, min),
+            None => (raw_all_members_min_fitness, all_members_max_objective_fitness.unwrap(), raw_worst_ever)
+        };
 
         let mut min_species_avg_objective_fitness: NeatFloat = 0.0;
         let mut min_species_avg_novelty: NeatFloat = 0.0;
@@ -191,10 +218,14 @@ impl Speciation{
             if members.len() == 0{
                 species.objective_fitness = SpeciesMetric::new();
                 species.outcome_novelty = SpeciesMetric::new();
+                species.objective_fitness_variance = SpeciesMetric::new();
+                species.raw_objective_fitness = SpeciesMetric::new();
                 continue;
             }
             species.objective_fitness.new_generation(worst_ever, &species.members, |s| s.objective_fitness, run_context.current_generation);
+            species.raw_objective_fitness.new_generation(raw_worst_ever, &species.members, |s| s.raw_objective_fitness, run_context.current_generation);
             species.outcome_novelty.new_generation(0.0, &species.members, |s| s.outcome_novelty, run_context.current_generation);
+            species.objective_fitness_variance.new_generation(0.0, &species.members, |s| s.objective_fitness_variance, run_context.current_generation);
             
             if species.objective_fitness.average < min_species_avg_objective_fitness{
                 min_species_avg_objective_fitness = species.objective_fitness.average;
@@ -212,17 +243,17 @@ impl Speciation{
 
             species.stagnant_generation_counter += 1;
             if species.stagnant_generation_counter >= configuration.speciation_remove_stagnant_species_generations{
-                if species.objective_fitness.average < species.stagnant_objective_fitness && species.outcome_novelty.average < species.stagnant_novelty{
+                if species.raw_objective_fitness.average < species.stagnant_objective_fitness && species.outcome_novelty.average < species.stagnant_novelty{
                     species.is_stagnant = true;
                 }else{
-                    species.stagnant_objective_fitness = species.objective_fitness.average;
+                    species.stagnant_objective_fitness = species.raw_objective_fitness.average;
                     species.stagnant_novelty = species.outcome_novelty.average;
                     species.stagnant_generation_counter = 0;
                 }
             }
         }
 
-        let objective_fitness_member_range = NeatFloat::max(NeatFloatExtensions::abs_diff(all_members_max_objective_fitness.unwrap(),all_members_min_fitness),1.0);
+        let objective_fitness_member_range = NeatFloat::max(NeatFloatExtensions::abs_diff(all_members_max_fitness,all_members_min_fitness),1.0);
         let outcome_novelty_member_range = NeatFloat::max(NeatFloatExtensions::abs_diff(all_members_max_outcome_novelty,all_members_min_outcome_novelty),1.0);
         let objective_fitness_species_range = NeatFloat::max(NeatFloatExtensions::abs_diff(max_species_avg_objective_fitness,min_species_avg_objective_fitness),1.0);
         let outcome_novelty_species_range = NeatFloat::max(NeatFloatExtensions::abs_diff(max_species_avg_novelty,min_species_avg_novelty),1.0);
@@ -234,11 +265,37 @@ impl Speciation{
             species.adjusted_average_outcome_novelty_based_on_species_range = (species.outcome_novelty.average - min_species_avg_novelty) / outcome_novelty_species_range;
         }
     }
-    fn remove_species_that_have_not_improved(configuration: &Configuration, run_context: &mut RunContext) {
+    /*
+    Replaces the objective fitness of every species member with its shaped value, keeping the raw value in
+    raw_objective_fitness so improvement is still judged on the true objective. Members are shaped together
+    across species in species id order. Returns the range of the shaped values when shaping is on.
+    */
+    fn shape_objective_fitness<T>(configuration: &Configuration, run_context: &mut RunContext<T>) -> Option<(NeatFloat, NeatFloat)> where T: Genome {
+        if configuration.fitness_shaping == FitnessShaping::None{
+            return None;
+        }
+        let mut species_ids: Vec<Uuid> = run_context.species_index.keys().copied().collect();
+        species_ids.sort_unstable();
+
+        let raw_fitnesses: Vec<NeatFloat> = species_ids.iter().flat_map(|id| run_context.species_index[id].members.iter().map(|m| m.raw_objective_fitness)).collect();
+        if raw_fitnesses.is_empty(){
+            return None;
+        }
+        let mut shaped_fitnesses = configuration.fitness_shaping.shape(&raw_fitnesses).into_iter();
+        for id in &species_ids{
+            for member in run_context.species_index.get_mut(id).unwrap().members.iter_mut(){
+                member.objective_fitness = shaped_fitnesses.next().unwrap();
+            }
+        }
+
+        let shaped_members = species_ids.iter().flat_map(|id| run_context.species_index[id].members.iter().map(|m| m.objective_fitness));
+        Some(shaped_members.fold((NeatFloat::MAX, NeatFloat::MIN), |(min, max), f| (NeatFloat::min(min, f), NeatFloat::max(max, f))))
+    }
+    fn remove_species_that_have_not_improved<T>(configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + Crossover + MutationHistory {
         
         run_context.species_index.retain(|k,species| { 
             let is_still_protected = species.species_protected_until_generation > run_context.current_generation;
-            let has_objective_fitness_improved_in_last_x_generations = (run_context.current_generation - species.objective_fitness.last_generation_improved) < configuration.speciation_drop_species_no_improvement_generations;
+            let has_objective_fitness_improved_in_last_x_generations = (run_context.current_generation - species.raw_objective_fitness.last_generation_improved) < configuration.speciation_drop_species_no_improvement_generations;
             let has_outcome_novelty_improved_in_last_x_generations = (run_context.current_generation - species.outcome_novelty.last_generation_improved) < configuration.speciation_drop_species_no_improvement_generations;
             let species_is_not_stagnant = !species.is_stagnant;
             let has_members = species.members.len() > 0;
@@ -253,13 +310,25 @@ impl Speciation{
             allowed_to_live
         });
     }
-    fn remove_species_with_no_offspring(configuration: &Configuration, run_context: &mut RunContext) {
+    fn remove_species_with_no_offspring<T>(configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + Crossover + MutationHistory {
+        /* fitness sharing hands out whole offspring that add up to the population, protected species get theirs from the min offspring guarantee */
+        if configuration.speciation_offspring_mode == OffSpringMode::FitnessSharing{
+            run_context.species_index.retain(|k, species| {
+                let is_retained = species.allowed_number_of_offspring_based_on_objective_fitness > 0.0;
+                if !is_retained && EventRecorder::has_subscription(configuration, EventType::SPECIATION_SPECIES_REMOVE){
+                    EventRecorder::record_event(configuration, &Event::species_species_remove_no_offspring(run_context.current_generation, k));
+                }
+                is_retained
+            });
+            return;
+        }
         
         run_context.species_index.retain(|k,species| { 
             let has_offspring = species.allowed_number_of_offspring_based_on_objective_fitness > 2.0
                                         || species.allowed_number_of_offspring_based_on_outcome_novelty > 2.0;
             let is_protected = species.species_protected_until_generation < run_context.current_generation;
-            let is_retained = has_offspring || is_protected;
+            let has_guaranteed_offspring = configuration.speciation_new_species_min_offspring > 0 && species.species_protected_until_generation > run_context.current_generation;
+            let is_retained = has_offspring || is_protected || has_guaranteed_offspring;
             
             if !is_retained{
                 if EventRecorder::has_subscription(configuration, EventType::SPECIATION_SPECIES_REMOVE){
@@ -288,7 +357,7 @@ impl Speciation{
         }
 
     }
-    fn remove_members_of_species_below_threshold(configuration: &Configuration, run_context: &mut RunContext){
+    fn remove_members_of_species_below_threshold<T>(configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + Crossover + MutationHistory {
         
         for (_, species) in run_context.species_index.iter_mut(){
             if species.members.len() < configuration.speciation_species_min_number_of_members{
@@ -297,26 +366,29 @@ impl Speciation{
             SpeciesMemberTerminator::terminate_rejects(species, configuration, run_context.current_generation)
         }
     }
-    fn check_objective_fitness_set(members: &mut Vec<GenerationMember<NeatGenome>>){
+    fn check_objective_fitness_set<T>(members: &mut Vec<GenerationMember<T>>) where T: Genome {
         
-        let all_members_fitness_set = members.par_iter().all(|m| m.genome.objective_fitness != None);
+        let all_members_fitness_set = members.par_iter().all(|m| m.genome.get_fitness() != None);
         if !all_members_fitness_set{
             panic!("all members fitness must be set");
         }
     }
-    fn remove_old_generation_members(members: &mut Vec<GenerationMember<NeatGenome>>, configuration: &Configuration, run_context: &mut RunContext) {
+    fn remove_old_generation_members<T>(members: &mut Vec<GenerationMember<T>>, configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + Crossover + MutationHistory {
         members.retain(|m| m.created_generation == run_context.current_generation);
     }
-    fn set_allowed_offspring(members: &mut Vec<GenerationMember<NeatGenome>>, configuration: &Configuration, run_context: &mut RunContext){
-        
-        let mut total_of_species_fitness: NeatFloat = 0.0;
-        
+    fn set_allowed_offspring<T>(members: &mut Vec<GenerationMember<T>>, configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + Crossover + MutationHistory {
         match configuration.speciation_offspring_mode {
-            OffSpringMode::Average => total_of_species_fitness = run_context.species_index.iter().map(|(_, s)| s.objective_fitness.positively_adjusted_average).sum::<NeatFloat>(),
-            OffSpringMode::AdjustedMemberRange =>  total_of_species_fitness = run_context.species_index.iter().map(|(_, s)| s.adjusted_average_objective_fitness_based_on_member_range).sum::<NeatFloat>(),
-            OffSpringMode::AdjustedSpeciesRange =>  total_of_species_fitness = run_context.species_index.iter().map(|(_, s)| s.adjusted_average_objective_fitness_based_on_species_range).sum::<NeatFloat>(),
-
+            OffSpringMode::Average => Self::set_allowed_offspring_by_species_average(configuration, run_context, |s| (s.objective_fitness.positively_adjusted_average, s.outcome_novelty.average)),
+            OffSpringMode::AdjustedMemberRange => Self::set_allowed_offspring_by_species_average(configuration, run_context, |s| (s.adjusted_average_objective_fitness_based_on_member_range, s.adjusted_average_outcome_novelty_based_on_member_range)),
+            OffSpringMode::AdjustedSpeciesRange => Self::set_allowed_offspring_by_species_average(configuration, run_context, |s| (s.adjusted_average_objective_fitness_based_on_species_range, s.adjusted_average_outcome_novelty_based_on_species_range)),
+            OffSpringMode::FitnessSharing => Self::set_allowed_offspring_by_fitness_sharing(configuration, run_context)
         }
+        Self::guarantee_offspring_for_protected_species(configuration, run_context);
+    }
+    /* species_averages gives the average objective fitness and outcome novelty of a species that the offspring mode uses */
+    fn set_allowed_offspring_by_species_average<T, F>(configuration: &Configuration, run_context: &mut RunContext<T>, species_averages: F) where T: Genome, F: Fn(&Species<T>) -> (NeatFloat, NeatFloat) {
+        
+        let total_of_species_fitness = run_context.species_index.iter().map(|(_, s)| species_averages(s).0).sum::<NeatFloat>();
 
         let mut total_of_outcome_novelty =  run_context.species_index.iter().map(|(_, s)| s.outcome_novelty.average).sum::<NeatFloat>();
 
@@ -325,20 +397,9 @@ impl Speciation{
         }
 
         for (s, species) in run_context.species_index.iter_mut(){
-            match configuration.speciation_offspring_mode {
-                OffSpringMode::Average =>  {
-                    species.allowed_number_of_offspring_based_on_objective_fitness = ((species.objective_fitness.positively_adjusted_average / total_of_species_fitness) * (configuration.population_size as NeatFloat));
-                    species.allowed_number_of_offspring_based_on_outcome_novelty= ((species.outcome_novelty.average / total_of_outcome_novelty) * (configuration.population_size as NeatFloat));
-                },
-                OffSpringMode::AdjustedMemberRange => {
-                    species.allowed_number_of_offspring_based_on_objective_fitness = ((species.adjusted_average_objective_fitness_based_on_member_range / total_of_species_fitness) * (configuration.population_size as NeatFloat));
-                    species.allowed_number_of_offspring_based_on_outcome_novelty= ((species.adjusted_average_outcome_novelty_based_on_member_range / total_of_outcome_novelty) * (configuration.population_size as NeatFloat));
-                },
-                OffSpringMode::AdjustedSpeciesRange => {
-                    species.allowed_number_of_offspring_based_on_objective_fitness = ((species.adjusted_average_objective_fitness_based_on_species_range / total_of_species_fitness) * (configuration.population_size as NeatFloat));
-                    species.allowed_number_of_offspring_based_on_outcome_novelty= ((species.adjusted_average_outcome_novelty_based_on_species_range / total_of_outcome_novelty) * (configuration.population_size as NeatFloat));
-                }
-            }
+            let (average_objective_fitness, average_outcome_novelty) = species_averages(species);
+            species.allowed_number_of_offspring_based_on_objective_fitness = ((average_objective_fitness / total_of_species_fitness) * (configuration.population_size as NeatFloat));
+            species.allowed_number_of_offspring_based_on_outcome_novelty= ((average_outcome_novelty / total_of_outcome_novelty) * (configuration.population_size as NeatFloat));
             let too_many_members_based_on_fitness = (species.allowed_number_of_offspring_based_on_objective_fitness > configuration.population_size as NeatFloat);
             let too_many_members_based_on_novelty = (species.allowed_number_of_offspring_based_on_outcome_novelty > configuration.population_size as NeatFloat);
 
@@ -352,15 +413,101 @@ impl Speciation{
             }
         }
     }
-    fn cross_species_reproduction (members: &mut Vec<GenerationMember<NeatGenome>>, mut configuration: &Configuration, run_context: &mut RunContext) {
-        let mut number_of_cross_species_to_create = ((members.len() as f32 * configuration.speciation_cross_species_reproduction_scale)) as usize;
+    /*
+    Textbook NEAT explicit fitness sharing. Each member's fitness, shifted so the least fit member has zero, is
+    divided by the size of its species and the species gets offspring in proportion to the sum of its shared
+    fitness. Offspring are whole members, the slots left after rounding down go to the species with the largest
+    remainders so the population size is kept exactly. Novelty does not take part.
+    */
+    fn set_allowed_offspring_by_fitness_sharing<T>(configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome {
+        let least_fit = run_context.species_index.values().flat_map(|s| s.members.iter().map(|m| m.objective_fitness)).fold(NeatFloat::MAX, NeatFloat::min);
+        let mut species_ids: Vec<Uuid> = run_context.species_index.keys().copied().collect();
+        species_ids.sort_unstable();
+
+        for species in run_context.species_index.values_mut(){
+            let species_size = species.members.len() as NeatFloat;
+            species.shared_fitness = species.members.iter().map(|m| (m.objective_fitness - least_fit) / species_size).sum::<NeatFloat>();
+        }
+
+        let total_shared_fitness = run_context.species_index.values().map(|s| s.shared_fitness).sum::<NeatFloat>();
+        let population_size = configuration.population_size as NeatFloat;
+        let mut remainders = Vec::with_capacity(species_ids.len());
+        let mut allocated = 0;
+        for id in &species_ids{
+            let species = run_context.species_index.get_mut(id).unwrap();
+            let quota = if total_shared_fitness > 0.0 {
+                species.shared_fitness / total_shared_fitness * population_size
+            }else{
+                population_size / species_ids.len() as NeatFloat
+            };
+            let offspring = NeatFloat::floor(quota);
+            allocated += offspring as u32;
+            species.allowed_number_of_offspring_based_on_objective_fitness = offspring;
+            remainders.push((*id, quota - offspring));
+        }
+
+        remainders.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        for (id, _) in remainders.iter().take(configuration.population_size.saturating_sub(allocated) as usize){
+            run_context.species_index.get_mut(id).unwrap().allowed_number_of_offspring_based_on_objective_fitness += 1.0;
+        }
+        for species in run_context.species_index.values_mut(){
+            species.allowed_number_of_offspring_based_on_outcome_novelty = species.allowed_number_of_offspring_based_on_objective_fitness;
+        }
+    }
+    /* protected species are raised to the minimum, taking the difference from the unprotected species with the most offspring */
+    fn guarantee_offspring_for_protected_species<T>(configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome {
+        let min_offspring = configuration.speciation_new_species_min_offspring as NeatFloat;
+        if min_offspring == 0.0 {
+            return;
+        }
+        let current_generation = run_context.current_generation;
+        let mut species_ids: Vec<Uuid> = run_context.species_index.keys().copied().collect();
+        species_ids.sort_unstable();
+        let is_protected = |species: &Species<T>| species.species_protected_until_generation > current_generation;
+
+        let offspring_fields: [fn(&mut Species<T>) -> &mut NeatFloat; 2] = [
+            |s| &mut s.allowed_number_of_offspring_based_on_objective_fitness,
+            |s| &mut s.allowed_number_of_offspring_based_on_outcome_novelty
+        ];
+        for offspring_field in offspring_fields{
+            for id in &species_ids{
+                let species = run_context.species_index.get_mut(id).unwrap();
+                if !is_protected(species) || *offspring_field(species) >= min_offspring {
+                    continue;
+                }
+                let deficit = min_offspring - *offspring_field(species);
+                *offspring_field(species) = min_offspring;
+
+                let mut donor: Option<(Uuid, NeatFloat)> = None;
+                for donor_id in &species_ids{
+                    let candidate = run_context.species_index.get_mut(donor_id).unwrap();
+                    let offspring = *offspring_field(candidate);
+                    if !is_protected(candidate) && (donor.is_none() || offspring > donor.unwrap().1){
+                        donor = Some((*donor_id, offspring));
+                    }
+                }
+                if let Some((donor, _)) = donor{
+                    let donor_offspring = offspring_field(run_context.species_index.get_mut(&donor).unwrap());
+                    *donor_offspring = NeatFloat::max(*donor_offspring - deficit, 0.0);
+                }
+            }
+        }
+    }
+    fn cross_species_reproduction<T>(members: &mut Vec<GenerationMember<T>>, mut configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + Crossover + MutationHistory {
+        let mut number_of_cross_species_to_create = ((members.len() as NeatFloat * configuration.speciation_cross_species_reproduction_scale)) as usize;
 
         if number_of_cross_species_to_create == 0{
             return;
         }
-        let mut rng = rand::thread_rng();
-        
-        members.sort_by(|a, b| {b.genome.objective_fitness.unwrap().partial_cmp(&a.genome.objective_fitness.unwrap()).unwrap()});
+        if configuration.multi_objective_enabled{
+            Self::sort_by_pareto_rank(members);
+        }else{
+            members.sort_by(|a, b| {b.genome.get_fitness().unwrap().partial_cmp(&a.genome.get_fitness().unwrap()).unwrap()});
+        }
+
+        /* parents come from the fittest members, new members are pushed after them so the indexes stay valid */
+        let candidates: Vec<&GenerationMember<T>> = members.iter().take(number_of_cross_species_to_create).collect();
+        let parent_selector = ParentSelector::new(&candidates, configuration);
 
         let mut try_count = 0;
         for i in 0..number_of_cross_species_to_create{
@@ -370,11 +517,11 @@ impl Speciation{
                 break;
             }
 
-            let first_member_index = Random::gen_range_usize(0, number_of_cross_species_to_create);
+            let first_member_index = parent_selector.select(&mut run_context.random);
             let first_member = &members[first_member_index];
             let mut first_member_genome = first_member.genome.clone();
             
-            let second_member_index = Random::gen_range_usize(0, number_of_cross_species_to_create);
+            let second_member_index = parent_selector.select(&mut run_context.random);
             let second_member = &members[second_member_index];
             let second_member_genome = &second_member.genome;
 
@@ -383,10 +530,10 @@ impl Speciation{
                 continue;
             }
 
-            let best_performing: &NeatGenome;
-            let other: &NeatGenome;
+            let best_performing: &T;
+            let other: &T;
 
-            if first_member_genome.objective_fitness > second_member_genome.objective_fitness {
+            if parent_selector.is_better(first_member_index, second_member_index) {
                 best_performing = &first_member_genome;
                 other =  second_member_genome;
             }else{
@@ -394,7 +541,7 @@ impl Speciation{
                 other = &first_member_genome;
             }
 
-            let new_genome = Reproduction::reproduce_cross_species(best_performing, other, first_member.species_hint, configuration.reproduction_weights_from_fitter_probability);
+            let new_genome = T::reproduce_cross_species(best_performing, other, configuration, &mut run_context.random);
 
             if EventRecorder::has_subscription(configuration, EventType::SPECIATION_REPRODUCE_CROSS_SPECIES){
                 EventRecorder::record_event(configuration, &Event::speciation_reproduce_cross_species(run_context.current_generation, best_performing, other, &new_genome, &first_member.species_hint));
@@ -402,7 +549,7 @@ impl Speciation{
 
             let mut species_iter_mut = run_context.species_index.iter_mut();
             let first_species = species_iter_mut.find(|s| s.0 == &first_member.species_hint);
-            let mut species_opt: Option<&mut Species> = None;
+            let mut species_opt: Option<&mut Species<T>> = None;
             if first_species.is_some(){
                 species_opt = Some(first_species.unwrap().1)
             }else{
@@ -420,7 +567,7 @@ impl Speciation{
             let mut species_hint = Uuid::nil();
             if species_opt.is_some(){
                 let sp = species_opt.unwrap();
-                sp.members.push(SpeciesMember::new(new_genome.id, 0.0, 0.0, true));
+                sp.members.push(SpeciesMember::new(new_genome.get_id(), 0.0, 0.0, true));
                 species_hint = sp.id;
             }
             
@@ -428,31 +575,47 @@ impl Speciation{
                 genome: new_genome, 
                 created_generation: run_context.current_generation,
                 number_of_generations_since_species_improved: 0,
-                species_hint: species_hint
+                species_hint: species_hint,
+                objective_fitness_variance: 0.0,
+                objectives: Vec::new(),
+                component_scores: Vec::new()
             });
         }
 
     }
-    fn produce_offspring_within_species(members: &mut Vec<GenerationMember<NeatGenome>>, mut configuration: &Configuration, run_context: &mut RunContext) {
+    fn sort_by_pareto_rank<T>(members: &mut Vec<GenerationMember<T>>) where T: Genome {
+        let objectives: Vec<&[Objective]> = members.iter().map(|m| m.objectives.as_slice()).collect();
+        let order = ParetoFront::get_order(&objectives);
+        let mut unsorted: Vec<Option<GenerationMember<T>>> = members.drain(..).map(Some).collect();
+        members.extend(order.into_iter().map(|i| unsorted[i].take().unwrap()));
+    }
+    fn produce_offspring_within_species<T>(members: &mut Vec<GenerationMember<T>>, mut configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + Crossover + MutationHistory {
         let members_lookup = MembersLookup::new(members);
+        let offspring_seed = run_context.random.gen_u64();
 
-        let members_to_add = run_context.species_index.iter_mut().par_bridge().into_par_iter().map(|(s, species)| {
+        let mut members_to_add_by_species = run_context.species_index.iter_mut().par_bridge().into_par_iter().map(|(s, species)| {
             optick::register_thread("Produce_Offspring par");
-            let mut rng = rand::thread_rng(); 
+            let mut random = SeededRandom::for_stream(offspring_seed, s);
             let mut old_members = species.members.clone();
-            old_members.sort_unstable();
+            if configuration.multi_objective_enabled{
+                old_members = SpeciesMemberTerminator::sort_by_pareto_rank(&old_members);
+            }else{
+                old_members.sort_unstable();
+            }
             species.members.retain(|m| m.is_cross_species );
+            let candidates: Vec<&GenerationMember<T>> = old_members.iter().map(|m| &members[members_lookup.get_array_index(m.id)]).collect();
+            let parent_selector = ParentSelector::new(&candidates, configuration);
 
             if species.allowed_number_of_offspring_based_on_objective_fitness == 0.0 && species.species_protected_until_generation < run_context.current_generation {
-                return vec![];
+                return (*s, vec![]);
             }
 
             let range_between_objective_fitness_and_outcome_novelty = species.allowed_number_of_offspring_based_on_outcome_novelty - species.allowed_number_of_offspring_based_on_objective_fitness;
             let balanced = species.allowed_number_of_offspring_based_on_objective_fitness + (range_between_objective_fitness_and_outcome_novelty * configuration.speciation_offspring_outcome_novelty_weight);
       
             let mut number_of_offspring = u32::max(NeatFloat::floor(balanced) as u32, configuration.speciation_species_min_number_of_members as u32);
-            let mut members_to_add: Vec<(NeatGenome, uuid::Uuid, u32)> = Vec::with_capacity(number_of_offspring as usize);
-            let number_of_generations_since_species_improved = run_context.current_generation - u32::min( species.outcome_novelty.last_generation_improved,species.objective_fitness.last_generation_improved);
+            let mut members_to_add: Vec<(T, uuid::Uuid, u32)> = Vec::with_capacity(number_of_offspring as usize);
+            let number_of_generations_since_species_improved = run_context.current_generation - u32::min( species.outcome_novelty.last_generation_improved,species.raw_objective_fitness.last_generation_improved);
 
             //elite members
             if configuration.speciation_preserve_elite && number_of_offspring > 1{
@@ -460,30 +623,25 @@ impl Speciation{
 
                 let elite_species_member = &old_members[0];
                 let elite_member = &members[members_lookup.get_array_index(elite_species_member.id)];
-                let id = Uuid::new_v4();
                 let mut genome = elite_member.genome.clone();
-                genome.id = id;
+                genome.set_id(random.new_uuid());
                 genome.dont_allow_mutation();
-                species.members.push(SpeciesMember::new(genome.id, 0.0, 0.0, false));
+                species.members.push(SpeciesMember::new(genome.get_id(), 0.0, 0.0, false));
                 members_to_add.push((genome, *s, number_of_generations_since_species_improved));
             }
 
             //reproduction within species
             for i in 0..number_of_offspring {
-                let first_member_index = Random::gen_range_usize(0, old_members.len());
-                let first_member_id = old_members[first_member_index].id;
-                let first_member = &members[members_lookup.get_array_index(first_member_id)];
-                let first_member_genome = &first_member.genome;
+                let first_member_index = parent_selector.select(&mut random);
+                let first_member_genome = &candidates[first_member_index].genome;
                 
-                let second_member_index = Random::gen_range_usize(0, old_members.len());
-                let second_member_id = old_members[second_member_index].id;
-                let second_member = &members[members_lookup.get_array_index(second_member_id)];
-                let second_member_genome = &second_member.genome;
+                let second_member_index = parent_selector.select(&mut random);
+                let second_member_genome = &candidates[second_member_index].genome;
 
-                let best_performing: &NeatGenome;
-                let other: &NeatGenome;
+                let best_performing: &T;
+                let other: &T;
 
-                if first_member_genome.objective_fitness > second_member_genome.objective_fitness {
+                if parent_selector.is_better(first_member_index, second_member_index) {
                     best_performing = first_member_genome;
                     other =  second_member_genome;
                 }else{
@@ -491,52 +649,138 @@ impl Speciation{
                     other = first_member_genome;
                 }
 
-                let new_genome = Reproduction::reproduce(best_performing, other, first_member.species_hint, configuration.reproduction_weights_from_fitter_probability);
+                let new_genome = T::reproduce(best_performing, other, configuration, &mut random);
                 
                 if EventRecorder::has_subscription(configuration, EventType::SPECIATION_REPRODUCE){
                     EventRecorder::record_event(configuration, &Event::speciation_reproduce(run_context.current_generation, best_performing, other, &new_genome, &species.id));
                 }
                 
-                species.members.push(SpeciesMember::new(new_genome.id, 0.0, 0.0, false));
+                species.members.push(SpeciesMember::new(new_genome.get_id(), 0.0, 0.0, false));
                 members_to_add.push((new_genome, *s, number_of_generations_since_species_improved));
             }
-            members_to_add
-        }).flatten_iter().collect::<Vec<(NeatGenome, Uuid, u32)>>();
+            (*s, members_to_add)
+        }).collect::<Vec<(Uuid, Vec<(T, Uuid, u32)>)>>();
+
+        // species finish in any order, keep the members in a stable order so seeded runs repeat
+        members_to_add_by_species.sort_unstable_by_key(|(s, _)| *s);
+        let members_to_add = members_to_add_by_species.into_iter().flat_map(|(_, m)| m);
 
         let mut generation_members_to_add = members_to_add.into_iter().map(|(genome,species_hint, number_of_generations_since_species_improved)|{
             GenerationMember{
                 genome: genome, 
                 created_generation: run_context.current_generation,
                 number_of_generations_since_species_improved,
-                species_hint
+                species_hint,
+                objective_fitness_variance: 0.0,
+                objectives: Vec::new(),
+                component_scores: Vec::new()
             }
         })
-        .collect::<Vec<GenerationMember<NeatGenome>>>();
+        .collect::<Vec<GenerationMember<T>>>();
 
 
         
         members.append(&mut generation_members_to_add);
         
     }
-    fn add_best_members_back_in(members: &mut Vec<GenerationMember<NeatGenome>>, mut configuration: &Configuration, run_context: &mut RunContext){
+    fn add_best_members_back_in<T>(members: &mut Vec<GenerationMember<T>>, mut configuration: &Configuration, run_context: &mut RunContext<T>) where T: Genome + Crossover + MutationHistory {
         if configuration.speciation_add_best_member_back_in{
             if run_context.best_member_so_far.is_some(){
                 let best = run_context.best_member_so_far.as_mut().unwrap();
-                best.genome.allow_mutation = false;
+                best.genome.dont_allow_mutation();
                 members.push(GenerationMember::new(best.genome.clone(), run_context.current_generation));
             }
             if run_context.get_best_member_in_this_gen.is_some(){
                 let best = run_context.get_best_member_in_this_gen.as_mut().unwrap();
-                best.genome.allow_mutation = false;
+                best.genome.dont_allow_mutation();
                 members.push(GenerationMember::new(best.genome.clone(), run_context.current_generation));
             }
         }
         if configuration.speciation_use_best_seed_bank.is_some(){
             for seed in &run_context.seed_bank.seeds{
                 let mut seed = seed.clone();
-                seed.genome.allow_mutation = false;
+                seed.genome.dont_allow_mutation();
                 members.push(GenerationMember::new(seed.genome, run_context.current_generation));
             }
         }
     }
 }
+
+#[test]
+fn fitness_sharing_divides_the_population_by_shared_fitness() {
+    use crate::neat::trainer::{configuration::Configuration, node_conf::NodeConf, neat_trainer::NeatTrainer, test_fixtures::run_xor_generations};
+    use crate::neat::population::speciation::{speciation::Speciation, species::Species, species_member::SpeciesMember, built_in_speciation_stage::BuiltInSpeciationStage};
+    use crate::neat::genome::neat::NeatGenome;
+
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(10)
+        .speciation_offspring_mode(OffSpringMode::FitnessSharing);
+    let mut run_context = RunContext::new(configuration.node_genes.len()+1, 0);
+    run_context.current_generation = 10;
+    let mut species_ids = Vec::new();
+    /* shared fitnesses of 1, 2 and 0 give quotas of 3.33, 6.67 and 0, the slot left after rounding down goes to the second */
+    for (fitnesses, protected_until_generation) in [(vec![1.0, 1.0], 0), (vec![2.0], 0), (vec![0.0, 0.0, 0.0], 20)]{
+        let id = run_context.random.new_uuid();
+        let primary = GenerationMember::new(NeatGenome::minimal(&configuration, &mut run_context), 0);
+        let members = fitnesses.iter().map(|f| SpeciesMember::new(uuid::Uuid::new_v4(), *f, 0.0, false)).collect();
+        run_context.species_index.insert(id, Species::new(id, primary, members, 0, protected_until_generation));
+        species_ids.push(id);
+    }
+    let offspring = |run_context: &RunContext| species_ids.iter().map(|id| run_context.species_index[id].allowed_number_of_offspring_based_on_objective_fitness).collect::<Vec<NeatFloat>>();
+
+    Speciation::run_built_in_stage(BuiltInSpeciationStage::SetAllowedOffspring, &mut Vec::new(), &configuration, &mut run_context);
+    assert_eq!(offspring(&run_context), vec![3.0, 7.0, 0.0]);
+    assert_eq!(run_context.species_index[&species_ids[0]].shared_fitness, 1.0);
+    assert_eq!(run_context.species_index[&species_ids[1]].shared_fitness, 2.0);
+
+    /* the protected species is raised to the minimum by the unprotected species with the most offspring */
+    Speciation::run_built_in_stage(BuiltInSpeciationStage::SetAllowedOffspring, &mut Vec::new(), &configuration.clone().speciation_new_species_min_offspring(3), &mut run_context);
+    assert_eq!(offspring(&run_context), vec![3.0, 4.0, 3.0]);
+
+    let mut neat_trainer = NeatTrainer::new(configuration.population_size(100).target_species(5).seed(Some(31)).speciation_new_species_min_offspring(3));
+    run_xor_generations(&mut neat_trainer, 10);
+    assert!(neat_trainer.members.iter().all(|m| m.genome.get_fitness().is_some()));
+}
+
+#[test]
+fn fitness_sharing_split_is_kept_by_the_default_pipeline() {
+    use crate::neat::trainer::{configuration::Configuration, node_conf::NodeConf};
+    use crate::neat::population::speciation::{speciation::Speciation, species::Species, species_member::SpeciesMember};
+    use crate::neat::genome::neat::NeatGenome;
+
+    /* only the stages that decide and produce offspring add members */
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(10)
+        .speciation_offspring_mode(OffSpringMode::FitnessSharing)
+        .speciation_add_new_species_during_run(false)
+        .speciation_add_best_member_back_in(false)
+        .speciation_use_best_seed_bank(None)
+        .speciation_cross_species_reproduction_scale(0.0);
+    let mut run_context = RunContext::new(configuration.node_genes.len()+1, 0);
+    run_context.current_generation = 10;
+    let mut members = Vec::new();
+    let mut species_ids = Vec::new();
+    /* shared fitnesses of 1, 2 and 0, the species past protection with no shared fitness is removed instead of being given offspring */
+    for fitnesses in [vec![2.0, 2.0], vec![3.0], vec![1.0, 1.0, 1.0]]{
+        let id = run_context.random.new_uuid();
+        let mut species_members = Vec::new();
+        for fitness in fitnesses{
+            let mut genome = NeatGenome::minimal(&configuration, &mut run_context);
+            genome.set_objective_fitness(fitness);
+            let mut member = GenerationMember::new(genome, 9);
+            member.species_hint = id;
+            species_members.push(SpeciesMember::from_generation_member(&member));
+            members.push(member);
+        }
+        let primary = members.last().unwrap().clone();
+        run_context.species_index.insert(id, Species::new(id, primary, species_members, 0, 5));
+        species_ids.push(id);
+    }
+
+    Speciation::speciate_with_stages(&mut members, &configuration, &mut run_context, &[]).unwrap();
+
+    let offspring: Vec<usize> = species_ids.iter().map(|id| members.iter().filter(|m| m.species_hint == *id).count()).collect();
+    assert_eq!(offspring, vec![3, 7, 0]);
+    assert_eq!(members.len(), 10);
+    assert!(!run_context.species_index.contains_key(&species_ids[2]));
+}
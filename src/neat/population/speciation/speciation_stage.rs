@@ -0,0 +1,10 @@
+use crate::neat::{genome::genome::Genome, population::GenerationMember, trainer::{configuration::Configuration, run_context::RunContext}};
+
+/*
+One step of Speciation::speciate. The steps run in the order of configuration.speciation_pipeline,
+a stage registered on the trainer with the same name as a built in stage replaces it.
+*/
+pub trait SpeciationStage<T>: Send + Sync where T: Genome{
+    fn get_name(&self) -> &str;
+    fn run(&self, members: &mut Vec<GenerationMember<T>>, configuration: &Configuration, run_context: &mut RunContext<T>);
+}
@@ -1,20 +1,24 @@
 use std::cmp::Ordering;
 use serde::{Serialize, Deserialize};
-use crate::{neat::{population::GenerationMember, genome::neat::NeatGenome}, common::NeatFloat};
+use crate::{neat::{population::GenerationMember, genome::{genome::Genome, neat::NeatGenome}}, common::NeatFloat};
 
 use super::{species_member::SpeciesMember, species_metric::SpeciesMetric};
 
 #[derive(Debug, Clone)]
 #[derive(Serialize, Deserialize)]
-pub struct Species{
+pub struct Species<T = NeatGenome> where T: Genome{
     pub id: uuid::Uuid,
-    pub primary: GenerationMember<NeatGenome>,
+    pub primary: GenerationMember<T>,
     pub members: Vec<SpeciesMember>,
     pub created_generation: u32,
     pub allowed_number_of_offspring_based_on_objective_fitness: NeatFloat,
     pub allowed_number_of_offspring_based_on_outcome_novelty: NeatFloat,
     pub objective_fitness: SpeciesMetric,
     pub outcome_novelty: SpeciesMetric,
+    #[serde(default = "SpeciesMetric::new")]
+    pub objective_fitness_variance: SpeciesMetric,
+    #[serde(default = "SpeciesMetric::new")]
+    pub raw_objective_fitness: SpeciesMetric,
     pub adjusted_average_objective_fitness_based_on_member_range: NeatFloat,
     pub adjusted_average_outcome_novelty_based_on_member_range: NeatFloat,
     pub adjusted_average_objective_fitness_based_on_species_range: NeatFloat,
@@ -23,12 +27,14 @@ pub struct Species{
     pub stagnant_objective_fitness: NeatFloat,
     pub stagnant_novelty: NeatFloat,
     pub is_stagnant: bool,
-    pub species_protected_until_generation: u32
+    pub species_protected_until_generation: u32,
+    #[serde(default)]
+    pub shared_fitness: NeatFloat
 }
-impl Species{
+impl<T> Species<T> where T: Genome{
     pub fn new(
         id: uuid::Uuid, 
-        primary: GenerationMember<NeatGenome>, 
+        primary: GenerationMember<T>, 
         members: Vec<SpeciesMember>, 
         created_generation: u32, 
         species_protected_until_generation: u32) -> Self{
@@ -41,6 +47,8 @@ impl Species{
             allowed_number_of_offspring_based_on_outcome_novelty: 0.0,
             objective_fitness: SpeciesMetric::new(),
             outcome_novelty: SpeciesMetric::new(),
+            objective_fitness_variance: SpeciesMetric::new(),
+            raw_objective_fitness: SpeciesMetric::new(),
             adjusted_average_objective_fitness_based_on_member_range: 0.0,
             adjusted_average_outcome_novelty_based_on_member_range: 0.0,
             adjusted_average_objective_fitness_based_on_species_range: 0.0,
@@ -49,12 +57,13 @@ impl Species{
             stagnant_objective_fitness: 0.0,
             stagnant_novelty: 0.0,
             is_stagnant: false,
-            species_protected_until_generation: species_protected_until_generation
+            species_protected_until_generation: species_protected_until_generation,
+            shared_fitness: 0.0
         }
     }
 }
 
-impl Ord for Species{
+impl<T> Ord for Species<T> where T: Genome{
     fn cmp(&self, other: &Self) -> Ordering {
         let a = self.adjusted_average_objective_fitness_based_on_member_range;
         let b = other.adjusted_average_objective_fitness_based_on_member_range;
@@ -63,14 +72,14 @@ impl Ord for Species{
         x
     }
 }
-impl PartialOrd for Species {
+impl<T> PartialOrd for Species<T> where T: Genome {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         other.adjusted_average_objective_fitness_based_on_member_range.partial_cmp(&self.adjusted_average_objective_fitness_based_on_member_range)
     }
 }
-impl PartialEq for Species {
+impl<T> PartialEq for Species<T> where T: Genome {
     fn eq(&self, other: &Self) -> bool {
         (self.id) == (other.id)
     }
 }
-impl Eq for Species { }
+impl<T> Eq for Species<T> where T: Genome { }
@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
 use serde::{Serialize, Deserialize};
-use crate::common::NeatFloat;
+use crate::{common::NeatFloat, neat::{genome::genome::Genome, population::GenerationMember, trainer::fitness::objective::Objective}};
 
 #[derive(Debug, Clone)]
 #[derive(Serialize, Deserialize)]
@@ -9,7 +9,14 @@ pub struct SpeciesMember{
     pub objective_fitness: NeatFloat,
     pub outcome_novelty: NeatFloat,
     pub is_elite: bool,
-    pub is_cross_species: bool
+    pub is_cross_species: bool,
+    #[serde(default)]
+    pub objective_fitness_variance: NeatFloat,
+    #[serde(default)]
+    pub objectives: Vec<Objective>,
+    /* objective_fitness before fitness shaping, the same as objective_fitness when shaping is off */
+    #[serde(default)]
+    pub raw_objective_fitness: NeatFloat
 }
 impl SpeciesMember{
     pub fn new( id: uuid::Uuid, fitness: NeatFloat, novelty: NeatFloat, is_cross_species: bool) -> Self {
@@ -18,7 +25,17 @@ impl SpeciesMember{
             objective_fitness: fitness,
             outcome_novelty: novelty,
             is_elite:false,
-            is_cross_species: is_cross_species
+            is_cross_species: is_cross_species,
+            objective_fitness_variance: 0.0,
+            objectives: Vec::new(),
+            raw_objective_fitness: fitness
+        }
+    }
+    pub fn from_generation_member<T>(member: &GenerationMember<T>) -> Self where T: Genome{
+        SpeciesMember{
+            objective_fitness_variance: member.objective_fitness_variance,
+            objectives: member.objectives.clone(),
+            ..Self::new(member.genome.get_id(), member.genome.get_fitness().unwrap(), member.genome.get_novelty(), false)
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::{neat::trainer::configuration::Configuration, common::{event_stream::{event::{EventType, Event}, event_recorder::EventRecorder}, NeatFloat}};
+use crate::{neat::{trainer::{configuration::Configuration, fitness::{objective::Objective, pareto_front::ParetoFront}}, genome::genome::Genome}, common::{event_stream::{event::{EventType, Event}, event_recorder::EventRecorder}, NeatFloat}};
 use rayon::prelude::*;
 use super::{species::Species, species_member::SpeciesMember};
 use itertools::Itertools;
@@ -6,12 +6,34 @@ use itertools::Itertools;
 pub struct SpeciesMemberTerminator;
 
 impl SpeciesMemberTerminator{
-    pub fn terminate_rejects(species: &mut Species, configuration: &Configuration, current_generation: u32){
-         
-        let mut sorted_by_fitness = species.members.clone();
+    pub fn terminate_rejects<T>(species: &mut Species<T>, configuration: &Configuration, current_generation: u32) where T: Genome{
+        let cut_off_member_index = usize::max(1, f64::ceil((species.members.len() as f64) * (configuration.survival_threshold as f64)) as usize);
+        if configuration.multi_objective_enabled{
+            species.members = Self::sort_by_pareto_rank(&species.members).into_iter().take(cut_off_member_index).collect();
+        }else{
+            species.members = Self::sort_by_fitness_and_novelty(&species.members, configuration).into_iter().take(cut_off_member_index).collect();
+        }
+
+        for survivor in &species.members{
+            if EventRecorder::has_subscription(configuration, EventType::SPECIATION_SURVIVOR){
+                EventRecorder::record_event(configuration, &Event::species_survivor(current_generation, &species.id, &survivor));
+            }
+        }
+
+        if species.members.len() ==0 {
+            panic!("no members after terminating rejects")
+        }
+    }
+    /* best first, by pareto front and then crowding distance */
+    pub fn sort_by_pareto_rank(members: &[SpeciesMember]) -> Vec<SpeciesMember>{
+        let objectives: Vec<&[Objective]> = members.iter().map(|m| m.objectives.as_slice()).collect();
+        ParetoFront::get_order(&objectives).into_iter().map(|i| members[i].clone()).collect()
+    }
+    fn sort_by_fitness_and_novelty(members: &[SpeciesMember], configuration: &Configuration) -> Vec<SpeciesMember>{
+        let mut sorted_by_fitness = members.to_vec();
          sorted_by_fitness.sort_by(|a, b| a.objective_fitness.partial_cmp(&b.objective_fitness).unwrap());
 
-         let mut sorted_by_novelty = species.members.clone();
+         let mut sorted_by_novelty = members.to_vec();
          sorted_by_novelty.sort_by(|a, b| a.outcome_novelty.partial_cmp(&b.outcome_novelty).unwrap());
 
          let mut combined_by_both: Vec<(usize, usize, &SpeciesMember)> = Vec::new();
@@ -37,18 +59,6 @@ impl SpeciesMemberTerminator{
             b_combined.partial_cmp(&a_combined).unwrap()
         });
 
-        let cut_off_member_index = usize::max(1, f64::ceil((species.members.len() as f64) * (configuration.survival_threshold as f64)) as usize);
-
-        species.members = combined_by_both.iter().take(cut_off_member_index).map(|(_,_,m)| (*m).clone()).collect::<Vec<SpeciesMember>>();
-
-        for survivor in &species.members{
-            if EventRecorder::has_subscription(configuration, EventType::SPECIATION_SURVIVOR){
-                EventRecorder::record_event(configuration, &Event::species_survivor(current_generation, &species.id, &survivor));
-            }
-        }
-
-        if species.members.len() ==0 {
-            panic!("no members after terminating rejects")
-        }
+        combined_by_both.iter().map(|(_,_,m)| (*m).clone()).collect::<Vec<SpeciesMember>>()
     }
 }
\ No newline at end of file
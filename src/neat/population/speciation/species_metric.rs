@@ -54,12 +54,12 @@ impl SpeciesMetric{
 
         self.lowest_comparison_point = lowest_comparison_point;
         self.average = sum / members.len() as NeatFloat;
-        self.positively_adjusted_average = f32::abs(lowest_comparison_point - self.average);
+        self.positively_adjusted_average = NeatFloat::abs(lowest_comparison_point - self.average);
         
         if self.max > self.last_generation_max{
             self.last_generation_improved = current_generation;
         }
 
-        self.max_positively_adjusted =f32::abs(lowest_comparison_point - self.max);
+        self.max_positively_adjusted =NeatFloat::abs(lowest_comparison_point - self.max);
     }
 }
\ No newline at end of file
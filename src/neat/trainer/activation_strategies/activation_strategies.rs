@@ -1,14 +1,20 @@
-use crate::neat::trainer::neat_trainer::NeatTrainer;
+use crate::{neat::{trainer::neat_trainer::NeatTrainer, genome::genome::Genome}, common::network_definition::NetworkDefinition};
 
-use super::{ cpu_parallel::CpuParallel, cpu_distributed::CpuDistibuted};
+use super::{cpu_parallel::CpuParallel, cpu_batch::CpuBatch};
+#[cfg(feature = "distributed")]
+use super::cpu_distributed::CpuDistibuted;
 
 #[derive(Default)]
 pub struct ActivationStrategies;
 
 impl ActivationStrategies{
-    pub fn get_cpu_parallel(neat_trainer: &mut NeatTrainer) -> CpuParallel{
+    pub fn get_cpu_parallel<T>(neat_trainer: &mut NeatTrainer<T>) -> CpuParallel<T> where T: Genome + NetworkDefinition {
         CpuParallel::new(neat_trainer)
     }
+    pub fn get_cpu_batch<T>(neat_trainer: &mut NeatTrainer<T>) -> CpuBatch<'_, T> where T: Genome + NetworkDefinition {
+        CpuBatch::new(neat_trainer)
+    }
+    #[cfg(feature = "distributed")]
     pub fn get_cpu_distibuted(neat_trainer: &mut NeatTrainer) -> CpuDistibuted{
         CpuDistibuted::new(neat_trainer)
     }
@@ -0,0 +1,128 @@
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Instant;
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+use crate::{neat::{trainer::{fitness::{fitness_setter::FitnessSetter, Fitness, fitness_failure::FitnessFailure, fitness_resolver::FitnessResolver, novelty_component_store::NoveltyComponentStore}, neat_trainer::NeatTrainer, configuration::Configuration}, population::GenerationMember, genome::{neat::NeatGenome, genome::Genome}}, phenome::Phenome, common::network_definition::NetworkDefinition, cpu_phenome::CpuPhenome};
+use super::cpu_parallel::{CpuParallel, new_fitness_resolver};
+
+/*
+Hands the phenomes of every member that needs evaluating to the fitness function in one call, with a fitness
+resolver per member at the same index, so vectorized environments can step all agents together and expensive
+setup is shared. With the fitness cache enabled only one member of each structure that is not cached yet is
+handed over, not the whole population. The fitness function runs on the calling thread, once per fitness
+evaluation of the genomes.
+There is no telling which member used up the time of a batch, so the time budget does not apply in batch mode
+and the batch time is split evenly over the members for the evaluation times. The step budget still applies per member.
+*/
+pub struct CpuBatch<'a, T = NeatGenome> where T: Genome{
+    neat_trainer: &'a mut NeatTrainer<T>
+}
+impl<'a, T> CpuBatch<'a, T> where T: Genome + NetworkDefinition{
+    pub fn new(neat_trainer: &'a mut NeatTrainer<T>) -> Self{
+        Self{
+            neat_trainer
+        }
+    }
+    pub fn compute<F>(&mut self, mut set_batch_fitness: F, fitness_setter: &mut FitnessSetter) where F: FnMut(&[&dyn Phenome], &mut [FitnessResolver]){
+        CpuParallel::new(self.neat_trainer).compute_with(|configuration, members, novelty_component_store, round| {
+            reevaluate_fitnesses_in_batch(&mut set_batch_fitness, configuration, members, novelty_component_store, round)
+        }, fitness_setter);
+    }
+}
+
+pub fn reevaluate_fitnesses_in_batch<F, T>(set_batch_fitness: &mut F, configuration: &Configuration, members: &[&GenerationMember<T>], novelty_component_store: &NoveltyComponentStore, round: u32) -> Vec<(uuid::Uuid, Fitness)> where F: FnMut(&[&dyn Phenome], &mut [FitnessResolver]), T: Genome + NetworkDefinition {
+    if members.is_empty(){
+        return Vec::new();
+    }
+    let phenomes: Vec<CpuPhenome> = members.par_iter().map(|m| CpuPhenome::from_network_schema(&m.genome)).collect();
+    let phenome_references: Vec<&dyn Phenome> = phenomes.iter().map(|p| p as &dyn Phenome).collect();
+
+    let mut evaluations: Vec<Vec<Fitness>> = members.iter().map(|_| Vec::new()).collect();
+    for evaluation in 0..configuration.fitness_evaluations_per_genome{
+        let mut fitness_resolvers: Vec<FitnessResolver> = members.iter().zip(phenomes.iter())
+            .map(|(m, phenome)| new_fitness_resolver(configuration, m, phenome, novelty_component_store, round, evaluation).with_budget(None, configuration.fitness_step_budget))
+            .collect();
+
+        let started = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| set_batch_fitness(&phenome_references, &mut fitness_resolvers)));
+        let member_duration = started.elapsed() / members.len() as u32;
+        /* a panic leaves no way of telling which member caused it, so the whole batch fails */
+        match result {
+            Ok(()) => {
+                for (member_evaluations, fitness_resolver) in evaluations.iter_mut().zip(fitness_resolvers.iter_mut()){
+                    let mut fitness = fitness_resolver.compute();
+                    fitness.evaluation_duration = member_duration;
+                    member_evaluations.push(fitness);
+                }
+            },
+            Err(payload) => {
+                let failure = FitnessFailure::from_panic(payload);
+                for member_evaluations in evaluations.iter_mut(){
+                    let mut fitness = Fitness::failed(failure.clone());
+                    fitness.evaluation_duration = member_duration;
+                    member_evaluations.push(fitness);
+                }
+            }
+        }
+    }
+
+    members.iter().zip(evaluations).map(|(m, member_evaluations)| (m.genome.get_id(), Fitness::aggregate(member_evaluations, configuration.fitness_aggregation))).collect()
+}
+
+#[test]
+fn batch_evaluation_matches_per_phenome_evaluation() {
+    use crate::common::NeatFloat;
+    use crate::neat::trainer::test_fixtures::{xor_configuration, xor_fitness};
+
+    let configuration = xor_configuration(53);
+    let mut batch_calls = 0;
+    let mut batch_xor_fitness = |phenomes: &[&dyn Phenome], fitness_resolvers: &mut [FitnessResolver]| {
+        batch_calls += 1;
+        for (phenome, fitness_resolver) in phenomes.iter().zip(fitness_resolvers.iter_mut()){
+            xor_fitness(*phenome, fitness_resolver);
+        }
+    };
+
+    let mut neat_trainer = NeatTrainer::new(configuration.clone());
+    let mut batch_neat_trainer = NeatTrainer::new(configuration);
+    for _ in 0..5{
+        neat_trainer.new_generation();
+        neat_trainer.evaluate_generation(&xor_fitness);
+        batch_neat_trainer.new_generation();
+        batch_neat_trainer.evaluate_generation_batch(&mut batch_xor_fitness);
+
+        let fitnesses: Vec<Option<NeatFloat>> = neat_trainer.members.iter().map(|m| m.genome.get_fitness()).collect();
+        let batch_fitnesses: Vec<Option<NeatFloat>> = batch_neat_trainer.members.iter().map(|m| m.genome.get_fitness()).collect();
+        assert_eq!(fitnesses, batch_fitnesses);
+    }
+    assert_eq!(batch_calls, 5);
+}
+
+#[test]
+fn batch_time_is_split_over_the_members_and_not_held_to_the_time_budget() {
+    use std::time::Duration;
+    use crate::neat::genome::genome::Genome;
+    use crate::neat::trainer::test_fixtures::xor_configuration;
+
+    let configuration = xor_configuration(54)
+        .fitness_cache_enabled(false)
+        .fitness_time_budget_milliseconds(Some(20))
+        .fitness_over_budget_penalty(-2.0);
+    let mut slow_batch_fitness = |_: &[&dyn Phenome], fitness_resolvers: &mut [FitnessResolver]| {
+        std::thread::sleep(Duration::from_millis(100));
+        for fitness_resolver in fitness_resolvers.iter_mut(){
+            if !fitness_resolver.should_stop(){
+                fitness_resolver.add_reward(0, 1.0);
+            }
+        }
+    };
+
+    let mut neat_trainer = NeatTrainer::new(configuration);
+    neat_trainer.new_generation();
+    neat_trainer.evaluate_generation_batch(&mut slow_batch_fitness);
+    let evaluation_times = &neat_trainer.run_context.evaluation_times;
+    assert!(neat_trainer.members.iter().all(|m| m.genome.get_fitness() == Some(1.0)));
+    assert_eq!(evaluation_times.over_budget, 0);
+    assert_eq!(evaluation_times.count, neat_trainer.members.len());
+    assert!(evaluation_times.max < Duration::from_millis(100));
+    assert!(evaluation_times.mean * evaluation_times.count as u32 >= Duration::from_millis(99));
+}
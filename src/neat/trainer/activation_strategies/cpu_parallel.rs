@@ -1,6 +1,9 @@
 use std::marker::PhantomData;
-use rayon::prelude::{IntoParallelRefMutIterator, ParallelIterator};
-use crate::{neat::{trainer::{fitness::{fitness_setter::{FitnessSetter}, Fitness, fitness_resolver::FitnessResolver, novelty_component_store::NoveltyComponentStore}, neat_trainer::NeatTrainer, configuration::Configuration}, population::GenerationMember, genome::neat::NeatGenome}, phenome::Phenome, common::NeatFloat, cpu_phenome::CpuPhenome};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+use hashbrown::{HashMap, HashSet};
+use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+use crate::{neat::{trainer::{fitness::{fitness_setter::{FitnessSetter}, Fitness, fitness_failure::FitnessFailure, evaluation_times::EvaluationTimes, fitness_resolver::FitnessResolver, novelty_component_store::NoveltyComponentStore}, neat_trainer::NeatTrainer, configuration::Configuration}, population::GenerationMember, genome::{neat::NeatGenome, genome::Genome, crossover::Crossover, mutation_history::MutationHistory}}, phenome::Phenome, common::{NeatFloat, seeded_random::SeededRandom, network_definition::NetworkDefinition}, cpu_phenome::CpuPhenome};
 
 /*
 The responsibilities of the activation strategies:
@@ -10,44 +13,226 @@ The responsibilities of the activation strategies:
 
 */
 
-pub struct CpuParallel<'a>{
-    neat_trainer: &'a mut NeatTrainer,
+pub struct CpuParallel<'a, T = NeatGenome> where T: Genome{
+    neat_trainer: &'a mut NeatTrainer<T>,
     use_new: PhantomData<u8>
 }
-impl<'a> CpuParallel<'a>{
-    pub fn new(neat_trainer: &'a mut NeatTrainer) -> Self{
+impl<'a, T> CpuParallel<'a, T> where T: Genome + NetworkDefinition{
+    pub fn new(neat_trainer: &'a mut NeatTrainer<T>) -> Self{
         Self{
             neat_trainer,
             use_new: PhantomData
         }
     }
     pub fn compute<F>(&mut self, set_individual_fitness: F, fitness_setter: &mut FitnessSetter) where F:Fn(&dyn Phenome, &mut FitnessResolver) + std::marker::Sync{
+        self.compute_with(|configuration, members, novelty_component_store, round| {
+            reevaluate_fitnesses_for_members(&set_individual_fitness, configuration, members, novelty_component_store, round)
+        }, fitness_setter);
+    }
+    /* wraps the fitness cache, elite re-evaluation and evaluation times around any way of evaluating a list of members */
+    pub fn compute_with<E>(&mut self, mut evaluate: E, fitness_setter: &mut FitnessSetter) where E: FnMut(&Configuration, &[&GenerationMember<T>], &NoveltyComponentStore, u32) -> Vec<(uuid::Uuid, Fitness)>{
+        let generation = self.neat_trainer.run_context.current_generation;
+        let reevaluate_elites_every_generations = self.neat_trainer.configuration.fitness_reevaluate_elites_every_generations;
+        if reevaluate_elites_every_generations.is_some() && generation > 0 && generation % reevaluate_elites_every_generations.unwrap() == 0{
+            self.reevaluate_elites(&mut evaluate, fitness_setter);
+        }
+
         let members = &mut self.neat_trainer.members;
         let novelty_component_store = &self.neat_trainer.run_context.novelty_component_store;
 
-        let results = compute_fitnesses_cpu(set_individual_fitness, &self.neat_trainer.configuration, members, novelty_component_store);
-    
-        for (id, fitness) in results{
-            fitness_setter.set_fitness(id, fitness)
+        if !uses_fitness_cache(&self.neat_trainer.configuration){
+            let members: Vec<&GenerationMember<T>> = members.iter().collect();
+            let results = evaluate(&self.neat_trainer.configuration, &members, novelty_component_store, 0);
+            self.neat_trainer.run_context.evaluation_times = get_evaluation_times(&results);
+            for (id, fitness) in results{
+                fitness_setter.set_fitness(id, fitness)
+            }
+            return;
+        }
+
+        /* only the first member with an uncached structure is evaluated, every other member takes its fitness from the cache */
+        let fitness_cache = &mut self.neat_trainer.run_context.fitness_cache;
+        let structure_hashes: Vec<u64> = members.iter().map(|m| m.genome.get_structure_hash()).collect();
+        let mut members_to_evaluate = Vec::new();
+        let mut hashes_to_evaluate = Vec::new();
+        let mut scheduled_hashes = HashSet::new();
+        for (member, structure_hash) in members.iter().zip(structure_hashes.iter()){
+            if !fitness_cache.contains(*structure_hash) && scheduled_hashes.insert(*structure_hash){
+                members_to_evaluate.push(member);
+                hashes_to_evaluate.push(*structure_hash);
+            }
+        }
+
+        let results = evaluate(&self.neat_trainer.configuration, &members_to_evaluate, novelty_component_store, 0);
+        self.neat_trainer.run_context.evaluation_times = get_evaluation_times(&results);
+        /* a failure can come from the randomness of one evaluation, so it is shared by this generation but never cached */
+        let mut failed_fitnesses = HashMap::new();
+        for ((_, fitness), structure_hash) in results.into_iter().zip(hashes_to_evaluate){
+            if fitness.failure.is_some(){
+                failed_fitnesses.insert(structure_hash, fitness);
+            }else{
+                fitness_cache.insert(structure_hash, fitness);
+            }
+        }
+
+        fitness_cache.misses += members_to_evaluate.len() as u64;
+        fitness_cache.hits += (members.len() - members_to_evaluate.len()) as u64;
+        /* the novelty store has moved on since a cached fitness was evaluated so its outcome novelty is recomputed */
+        for (member, structure_hash) in members.iter().zip(structure_hashes.iter()){
+            let mut fitness = fitness_cache.get(*structure_hash).or_else(|| failed_fitnesses.get(structure_hash)).unwrap().clone();
+            if let Some(quantized_values) = &fitness.outcome_novelty_quantized_values{
+                fitness.outcome_novelty = novelty_component_store.get_outcome_novelty(quantized_values);
+            }
+            fitness_setter.set_fitness(member.genome.get_id(), fitness);
         }
+        fitness_cache.retain(&structure_hashes);
     }
-    pub fn new_generation(&mut self){
+    /* the champion and the seed bank keep the fitness they were lucky enough to get, so they are tested again with fresh randomness */
+    fn reevaluate_elites<E>(&mut self, evaluate: &mut E, fitness_setter: &mut FitnessSetter) where E: FnMut(&Configuration, &[&GenerationMember<T>], &NoveltyComponentStore, u32) -> Vec<(uuid::Uuid, Fitness)>{
+        let run_context = &mut self.neat_trainer.run_context;
+        let configuration = &self.neat_trainer.configuration;
+        let round = run_context.current_generation;
+
+        let mut elites: Vec<&GenerationMember<T>> = run_context.seed_bank.seeds.iter().collect();
+        elites.extend(run_context.best_member_so_far.iter());
+        let results = evaluate(configuration, &elites, &run_context.novelty_component_store, round);
+
+        let seed_bank_len = run_context.seed_bank.seeds.len();
+        let elites = run_context.seed_bank.seeds.iter_mut().chain(run_context.best_member_so_far.iter_mut());
+        for (elite, (id, mut fitness)) in elites.zip(results){
+            /* members with the structure of an elite get the re-tested fitness from the cache instead of the lucky one */
+            if uses_fitness_cache(configuration) && fitness.failure.is_none(){
+                run_context.fitness_cache.insert(elite.genome.get_structure_hash(), fitness.clone());
+            }
+            /* elites are not members of the population so a failed one keeps its place with the penalty fitness */
+            if let Some(failure) = fitness.failure.take(){
+                let penalty = if failure == FitnessFailure::OverBudget { configuration.fitness_over_budget_penalty } else { configuration.fitness_invalid_penalty };
+                fitness_setter.report_failure(id, failure);
+                fitness = Fitness::objective(penalty);
+            }
+            elite.genome.set_objective_fitness(fitness.objective_fitness);
+            elite.objective_fitness_variance = fitness.objective_fitness_variance;
+            elite.objectives = fitness.objectives;
+            elite.component_scores = fitness.component_scores;
+        }
+        if seed_bank_len > 0{
+            run_context.seed_bank.refresh_lowest_fitness();
+        }
+    }
+    pub fn new_generation(&mut self) where T: Crossover + MutationHistory{
         self.neat_trainer.new_generation()
     }
 }
 
-pub fn compute_fitnesses_cpu<F>(set_individual_fitness: F, _configuration: &Configuration, members: &mut Vec<GenerationMember<NeatGenome>>, novelty_component_store: &NoveltyComponentStore ) -> Vec<(uuid::Uuid, Fitness)> where F:Fn(&dyn Phenome, &mut FitnessResolver) + std::marker::Sync {
-    let fitnesses = members.par_iter_mut().map(|m| {
-        let mut fitness_resolver = FitnessResolver::new(&novelty_component_store);
+pub fn compute_fitnesses_cpu<F, T>(set_individual_fitness: F, configuration: &Configuration, members: &[GenerationMember<T>], novelty_component_store: &NoveltyComponentStore ) -> Vec<(uuid::Uuid, Fitness)> where F:Fn(&dyn Phenome, &mut FitnessResolver) + std::marker::Sync, T: Genome + NetworkDefinition {
+    let members: Vec<&GenerationMember<T>> = members.iter().collect();
+    compute_fitnesses_for_members(set_individual_fitness, configuration, &members, novelty_component_store)
+}
+
+pub fn compute_fitnesses_for_members<F, T>(set_individual_fitness: F, configuration: &Configuration, members: &[&GenerationMember<T>], novelty_component_store: &NoveltyComponentStore ) -> Vec<(uuid::Uuid, Fitness)> where F:Fn(&dyn Phenome, &mut FitnessResolver) + std::marker::Sync, T: Genome + NetworkDefinition {
+    reevaluate_fitnesses_for_members(set_individual_fitness, configuration, members, novelty_component_store, 0)
+}
+
+/* round 0 is the first evaluation of a member, later rounds re-test it with fresh randomness */
+pub fn reevaluate_fitnesses_for_members<F, T>(set_individual_fitness: F, configuration: &Configuration, members: &[&GenerationMember<T>], novelty_component_store: &NoveltyComponentStore, round: u32) -> Vec<(uuid::Uuid, Fitness)> where F:Fn(&dyn Phenome, &mut FitnessResolver) + std::marker::Sync, T: Genome + NetworkDefinition {
+    let fitnesses = members.par_iter().map(|m| {
         let phenome = CpuPhenome::from_network_schema(&m.genome);
-        
-        //add some novelty for structure
-        fitness_resolver.add_novelty_component(1001, m.genome.genes.get_complexity(), 1);
-        fitness_resolver.add_novelty_component(1002, m.genome.genes.nodes.iter().count() as NeatFloat, 1);
-        fitness_resolver.add_novelty_component(1003, phenome.layers.len() as NeatFloat, 1);
-
-        let _ = &set_individual_fitness(&phenome, &mut fitness_resolver);
-        (m.genome.id , fitness_resolver.compute())
+        let evaluations = (0..configuration.fitness_evaluations_per_genome).map(|evaluation| {
+            let mut fitness_resolver = new_fitness_resolver(configuration, m, &phenome, novelty_component_store, round, evaluation);
+
+            /* a panicking fitness function fails this member only instead of taking down the whole pool */
+            match panic::catch_unwind(AssertUnwindSafe(|| set_individual_fitness(&phenome, &mut fitness_resolver))) {
+                Ok(()) => fitness_resolver.compute(),
+                Err(payload) => {
+                    let mut fitness = Fitness::failed(FitnessFailure::from_panic(payload));
+                    fitness.evaluation_duration = fitness_resolver.elapsed();
+                    fitness
+                }
+            }
+        }).collect::<Vec<Fitness>>();
+        (m.genome.get_id() , Fitness::aggregate(evaluations, configuration.fitness_aggregation))
     }).collect::<Vec<(uuid::Uuid, Fitness)>>();
     fitnesses
-}
\ No newline at end of file
+}
+
+/* every round draws a different random stream, round 0 is the first evaluation of a member */
+pub fn new_fitness_resolver<'b, T>(configuration: &Configuration, member: &GenerationMember<T>, phenome: &CpuPhenome, novelty_component_store: &'b NoveltyComponentStore, round: u32, evaluation: u32) -> FitnessResolver<'b> where T: Genome + NetworkDefinition{
+    let random = if round == 0 && evaluation == 0 {
+        SeededRandom::for_optional_stream(configuration.seed, member.genome.get_id())
+    }else{
+        SeededRandom::for_optional_stream(configuration.seed, (member.genome.get_id(), round, evaluation))
+    };
+    let mut fitness_resolver = FitnessResolver::new(novelty_component_store)
+        .with_random(random)
+        .with_budget(configuration.fitness_time_budget_milliseconds.map(Duration::from_millis), configuration.fitness_step_budget);
+
+    //add some novelty for structure
+    fitness_resolver.add_novelty_component(1001, member.genome.get_complexity(), 1);
+    fitness_resolver.add_novelty_component(1002, member.genome.get_nodes_len() as NeatFloat, 1);
+    fitness_resolver.add_novelty_component(1003, phenome.layers.len() as NeatFloat, 1);
+    fitness_resolver
+}
+
+/* repeated evaluations are only configured for a noisy fitness, where a cached fitness would keep the luck of its first evaluations */
+fn uses_fitness_cache(configuration: &Configuration) -> bool{
+    configuration.fitness_cache_enabled && configuration.fitness_evaluations_per_genome == 1
+}
+
+fn get_evaluation_times(results: &[(uuid::Uuid, Fitness)]) -> EvaluationTimes{
+    let durations: Vec<Duration> = results.iter().map(|(_, f)| f.evaluation_duration).collect();
+    let over_budget = results.iter().filter(|(_, f)| f.failure == Some(FitnessFailure::OverBudget)).count();
+    EvaluationTimes::new(&durations, over_budget)
+}
+
+#[test]
+fn noisy_fitness_is_evaluated_repeatedly_and_elites_are_retested() {
+    use crate::phenome::Phenome;
+    use crate::neat::genome::genome::Genome;
+    use crate::neat::trainer::{neat_trainer::NeatTrainer, fitness::fitness_resolver::FitnessResolver, test_fixtures::{xor_configuration, xor_fitness}};
+
+    let configuration = xor_configuration(17)
+        .fitness_evaluations_per_genome(4)
+        .fitness_reevaluate_elites_every_generations(Some(2));
+    let noisy_fitness = |phenotype: &dyn Phenome, fitness_resolver: &mut FitnessResolver| {
+        xor_fitness(phenotype, fitness_resolver);
+        let noise = fitness_resolver.random().gen_range_f32(0.0, 1.0);
+        fitness_resolver.add_reward(0, noise);
+    };
+
+    let mut neat_trainer = NeatTrainer::new(configuration);
+    neat_trainer.new_generation();
+    neat_trainer.evaluate_generation(&noisy_fitness);
+    assert!(neat_trainer.members.iter().all(|m| m.objective_fitness_variance > 0.0));
+    assert!(neat_trainer.run_context.fitness_cache.is_empty());
+    assert!(neat_trainer.run_context.best_member_so_far.as_ref().unwrap().genome.get_fitness().unwrap() > 0.0);
+
+    /* a generation that is due re-evaluates the champion before the new members are compared to it */
+    neat_trainer.new_generation();
+    neat_trainer.evaluate_generation(&|_: &dyn Phenome, fitness_resolver: &mut FitnessResolver| fitness_resolver.add_punishment(0, 1.0));
+    assert_eq!(neat_trainer.get_current_generation(), 2);
+    let champion = neat_trainer.run_context.best_member_so_far.as_ref().unwrap();
+    assert_eq!(champion.genome.get_fitness(), Some(-1.0));
+    assert_eq!(champion.objective_fitness_variance, 0.0);
+}
+
+#[test]
+fn retested_elites_update_the_fitness_cache() {
+    use crate::phenome::Phenome;
+    use crate::common::network_definition::NetworkDefinition;
+    use crate::neat::{genome::genome::Genome, population::GenerationMember};
+    use crate::neat::trainer::{neat_trainer::NeatTrainer, fitness::fitness_resolver::FitnessResolver, test_fixtures::{xor_configuration, xor_fitness}};
+
+    let configuration = xor_configuration(15)
+        .fitness_reevaluate_elites_every_generations(Some(1));
+    let mut neat_trainer = NeatTrainer::new(configuration);
+    neat_trainer.new_generation();
+    neat_trainer.evaluate_generation(&xor_fitness);
+    let champion_structure = neat_trainer.run_context.best_member_so_far.as_ref().unwrap().genome.get_structure_hash();
+
+    /* the champion is re-tested first, so a member with its structure gets the new fitness from the cache */
+    neat_trainer.evaluate_generation(&|_: &dyn Phenome, fitness_resolver: &mut FitnessResolver| fitness_resolver.add_punishment(0, 1.0));
+    assert_eq!(neat_trainer.run_context.fitness_cache.get(champion_structure).unwrap().objective_fitness, -1.0);
+    let clones: Vec<&GenerationMember<_>> = neat_trainer.members.iter().filter(|m| m.genome.get_structure_hash() == champion_structure).collect();
+    assert!(!clones.is_empty());
+    assert!(clones.iter().all(|m| m.genome.get_fitness() == Some(-1.0)));
+}
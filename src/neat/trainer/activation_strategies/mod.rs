@@ -1,3 +1,5 @@
 pub mod activation_strategies;
 pub mod cpu_parallel;
+pub mod cpu_batch;
+#[cfg(feature = "distributed")]
 pub mod cpu_distributed;
\ No newline at end of file
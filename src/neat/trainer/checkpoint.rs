@@ -0,0 +1,301 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde::{Serialize, de::DeserializeOwned};
+use crate::neat::genome::genome::Genome;
+use super::{configuration::SaveFormat, neat_trainer::NeatTrainer, load_error::LoadError, save_migrations::SaveMigrations};
+
+const FILE_EXTENSION: &str = "neatrun";
+const CHECKPOINT_MARKER: &str = "-checkpoint-";
+const HEADER_MAGIC: &[u8] = b"NEATRUN";
+const HEADER_LENGTH: usize = 12;
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/*
+Checkpoints are written to a temp file next to the target and renamed into place, so a crash
+mid write never leaves a truncated checkpoint behind.
+
+Every file starts with a header: NEATRUN, the save version as a little endian u32 and one format
+byte. Files without a header are read as version 0 json, or as version 1 compressed bincode when
+they start with the gzip magic. Json saves from older versions are migrated when read.
+*/
+pub struct Checkpoint;
+
+impl Checkpoint{
+    pub fn write<T>(trainer: &NeatTrainer<T>, path: &Path, format: SaveFormat) -> io::Result<()> where T: Genome + Serialize{
+        if let Some(parent) = path.parent(){
+            if !parent.as_os_str().is_empty(){
+                fs::create_dir_all(parent)?;
+            }
+        }
+        let temp_path = path.with_extension(format!("{}.tmp", FILE_EXTENSION));
+        let result = Self::write_to(trainer, &temp_path, format).and_then(|_| fs::rename(&temp_path, path));
+        if result.is_err(){
+            let _ = fs::remove_file(&temp_path);
+        }
+        result
+    }
+    pub fn read<T>(path: &Path) -> Result<NeatTrainer<T>, LoadError> where T: Genome + DeserializeOwned{
+        let path_name = path.display().to_string();
+        let bytes = fs::read(path).map_err(|e| LoadError::Read { path: path_name.clone(), message: e.to_string() })?;
+        let (version, format, payload) = Self::read_header(&bytes)?;
+        let deserialize_error = |message: String| LoadError::Deserialize { path: path_name.clone(), message };
+
+        match format {
+            SaveFormat::Json => {
+                let mut value = serde_json::from_slice(payload).map_err(|e| deserialize_error(e.to_string()))?;
+                SaveMigrations::migrate(&mut value, version)?;
+                serde_json::from_value(value).map_err(|e| deserialize_error(e.to_string()))
+            },
+            SaveFormat::CompressedBincode => {
+                if version != SaveMigrations::current_version(){
+                    return Err(LoadError::BinaryMigrationUnsupported { version });
+                }
+                bincode::deserialize_from(GzDecoder::new(payload)).map_err(|e| deserialize_error(e.to_string()))
+            }
+        }
+    }
+    pub fn file_name(run_name: &str, generation: u32) -> String{
+        format!("{}{}{}.{}", run_name, CHECKPOINT_MARKER, generation, FILE_EXTENSION)
+    }
+    pub fn directory(run_save_directory: &str) -> &Path{
+        if run_save_directory.is_empty() { Path::new(".") } else { Path::new(run_save_directory) }
+    }
+    /* oldest first, optionally only the checkpoints of one run */
+    pub fn list(directory: &Path, run_name: Option<&str>) -> io::Result<Vec<PathBuf>>{
+        let mut checkpoints = Vec::new();
+        for entry in fs::read_dir(directory)?{
+            let entry = entry?;
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some((name, generation)) = Self::parse_file_name(&file_name) else {
+                continue;
+            };
+            if run_name.is_some() && run_name.unwrap() != name{
+                continue;
+            }
+            let modified = entry.metadata().and_then(|m| m.modified()).ok();
+            checkpoints.push((modified, generation, path));
+        }
+        checkpoints.sort();
+        Ok(checkpoints.into_iter().map(|(_, _, path)| path).collect())
+    }
+    pub fn rotate(directory: &Path, run_name: &str, keep_last: usize) -> io::Result<()>{
+        let checkpoints = Self::list(directory, Some(run_name))?;
+        let number_to_remove = checkpoints.len().saturating_sub(keep_last);
+        for path in checkpoints.iter().take(number_to_remove){
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+    fn parse_file_name(file_name: &str) -> Option<(&str, u32)>{
+        let stem = file_name.strip_suffix(FILE_EXTENSION)?.strip_suffix('.')?;
+        let (run_name, generation) = stem.rsplit_once(CHECKPOINT_MARKER)?;
+        Some((run_name, generation.parse().ok()?))
+    }
+    fn read_header(bytes: &[u8]) -> Result<(u32, SaveFormat, &[u8]), LoadError>{
+        if bytes.starts_with(HEADER_MAGIC) && bytes.len() >= HEADER_LENGTH{
+            let version = u32::from_le_bytes(bytes[HEADER_MAGIC.len()..HEADER_LENGTH - 1].try_into().unwrap());
+            let format = match bytes[HEADER_LENGTH - 1] {
+                0 => SaveFormat::Json,
+                1 => SaveFormat::CompressedBincode,
+                unknown => return Err(LoadError::UnknownFormat(unknown))
+            };
+            if version > SaveMigrations::current_version(){
+                return Err(LoadError::UnsupportedVersion { version, current_version: SaveMigrations::current_version() });
+            }
+            return Ok((version, format, &bytes[HEADER_LENGTH..]));
+        }
+        if bytes.starts_with(&GZIP_MAGIC){
+            return Ok((1, SaveFormat::CompressedBincode, bytes));
+        }
+        Ok((0, SaveFormat::Json, bytes))
+    }
+    fn write_header<W>(writer: &mut W, format: SaveFormat) -> io::Result<()> where W: Write{
+        let format_byte: u8 = match format {
+            SaveFormat::Json => 0,
+            SaveFormat::CompressedBincode => 1
+        };
+        writer.write_all(HEADER_MAGIC)?;
+        writer.write_all(&SaveMigrations::current_version().to_le_bytes())?;
+        writer.write_all(&[format_byte])
+    }
+    fn write_to<T>(trainer: &NeatTrainer<T>, path: &Path, format: SaveFormat) -> io::Result<()> where T: Genome + Serialize{
+        let mut writer = BufWriter::new(File::create(path)?);
+        Self::write_header(&mut writer, format)?;
+        match format {
+            SaveFormat::Json => serde_json::to_writer(&mut writer, trainer).map_err(Self::invalid_data)?,
+            SaveFormat::CompressedBincode => {
+                let mut encoder = GzEncoder::new(&mut writer, Compression::default());
+                bincode::serialize_into(&mut encoder, trainer).map_err(Self::invalid_data)?;
+                encoder.finish()?;
+            }
+        }
+        writer.flush()?;
+        writer.get_ref().sync_all()
+    }
+    fn invalid_data<E>(error: E) -> io::Error where E: std::error::Error + Send + Sync + 'static{
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+
+#[cfg(test)]
+fn run_with_checkpoints(directory: &Path, format: SaveFormat) -> NeatTrainer{
+    use crate::{neat::trainer::{configuration::Configuration, node_conf::NodeConf, stop_criteria::StopCriteria, fitness::fitness_resolver::FitnessResolver}, phenome::Phenome};
+
+    let _ = fs::remove_dir_all(directory);
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(50)
+        .target_species(3)
+        .seed(Some(11))
+        .run_name("checkpoint_test".to_string())
+        .run_save_directory(format!("{}/", directory.display()))
+        .run_save_format(format)
+        .checkpoint_every_generations(Some(2))
+        .checkpoint_keep_last(Some(2));
+    let mut trainer = NeatTrainer::new(configuration);
+    let fitness = |phenotype: &dyn Phenome, fitness_resolver: &mut FitnessResolver| {
+        let result = phenotype.activate(&vec![1.0, 0.0])[0];
+        fitness_resolver.add_objective_fitness_component(0, 1.0, 1.0, result);
+    };
+    trainer.run(fitness, StopCriteria::new().max_generations(Some(7)));
+    trainer
+}
+
+#[test]
+fn checkpoints_rotate_and_resume_from_latest() {
+    let directory = std::env::temp_dir().join(format!("neatlib-checkpoints-{}", std::process::id()));
+    run_with_checkpoints(&directory, SaveFormat::CompressedBincode);
+
+    let checkpoints = Checkpoint::list(&directory, Some("checkpoint_test")).unwrap();
+    let names = checkpoints.iter().map(|p| p.file_name().unwrap().to_string_lossy().to_string()).collect::<Vec<String>>();
+    assert_eq!(names, vec![Checkpoint::file_name("checkpoint_test", 4), Checkpoint::file_name("checkpoint_test", 6)]);
+
+    fs::write(directory.join(Checkpoint::file_name("checkpoint_test", 8)), b"not a checkpoint").unwrap();
+    let resumed_run = NeatTrainer::resume_latest(&directory).unwrap();
+    let resumed: NeatTrainer = resumed_run.trainer.unwrap();
+
+    assert_eq!(resumed.get_current_generation(), 6);
+    assert!(!resumed.members.is_empty());
+    assert_eq!(resumed_run.skipped_checkpoints.len(), 1);
+    assert_eq!(resumed_run.skipped_checkpoints[0].0, directory.join(Checkpoint::file_name("checkpoint_test", 8)));
+    let _ = fs::remove_dir_all(&directory);
+}
+
+#[test]
+fn resuming_without_a_custom_stage_returns_the_configuration_error() {
+    use super::{config_error::ConfigError, load_error::LoadError};
+
+    let directory = std::env::temp_dir().join(format!("neatlib-checkpoints-custom-stage-{}", std::process::id()));
+    let _ = fs::create_dir_all(&directory);
+    let mut trainer: NeatTrainer = NeatTrainer::new(super::configuration::Configuration::neat(super::node_conf::NodeConf::simple(2, 1), 4.0));
+    trainer.configuration.speciation_pipeline.insert(0, "custom_culling".to_string());
+    Checkpoint::write(&trainer, &directory.join(Checkpoint::file_name("checkpoint_test", 2)), SaveFormat::CompressedBincode).unwrap();
+    fs::write(directory.join(Checkpoint::file_name("checkpoint_test", 4)), b"not a checkpoint").unwrap();
+
+    let resumed = NeatTrainer::<crate::neat::genome::neat::NeatGenome>::resume_latest(&directory);
+
+    assert_eq!(resumed.err(), Some(LoadError::Configuration(ConfigError::UnknownSpeciationStage("custom_culling".to_string()))));
+    let _ = fs::remove_dir_all(&directory);
+}
+
+#[test]
+fn compressed_checkpoint_is_smaller_than_json() {
+    let directory = std::env::temp_dir().join(format!("neatlib-checkpoint-formats-{}", std::process::id()));
+    let trainer = run_with_checkpoints(&directory, SaveFormat::Json);
+    let json_path = directory.join("trainer.json.neatrun");
+    let compressed_path = directory.join("trainer.bin.neatrun");
+
+    Checkpoint::write(&trainer, &json_path, SaveFormat::Json).unwrap();
+    Checkpoint::write(&trainer, &compressed_path, SaveFormat::CompressedBincode).unwrap();
+    let from_json: NeatTrainer = Checkpoint::read(&json_path).unwrap();
+    let from_compressed: NeatTrainer = Checkpoint::read(&compressed_path).unwrap();
+
+    assert!(fs::metadata(&compressed_path).unwrap().len() < fs::metadata(&json_path).unwrap().len());
+    assert_eq!(serde_json::to_value(&from_json.members).unwrap(), serde_json::to_value(&from_compressed.members).unwrap());
+    let _ = fs::remove_dir_all(&directory);
+}
+
+#[test]
+fn default_format_checkpoint_from_an_older_version_is_migrated() {
+    use super::configuration_defaults::ConfigurationDefaults;
+
+    let directory = std::env::temp_dir().join(format!("neatlib-checkpoint-default-format-{}", std::process::id()));
+    run_with_checkpoints(&directory, ConfigurationDefaults::get().run_save_format);
+    let path = Checkpoint::list(&directory, Some("checkpoint_test")).unwrap().pop().unwrap();
+    let mut bytes = fs::read(&path).unwrap();
+    let older_version = SaveMigrations::current_version() - 1;
+    bytes[HEADER_MAGIC.len()..HEADER_MAGIC.len() + 4].copy_from_slice(&older_version.to_le_bytes());
+    fs::write(&path, bytes).unwrap();
+
+    let loaded: NeatTrainer = Checkpoint::read(&path).unwrap();
+
+    assert_eq!(loaded.get_current_generation(), 6);
+    let _ = fs::remove_dir_all(&directory);
+}
+
+/*
+save_fixtures has a json save written by every released version of the library, so a new migration needs a fixture of
+the version it migrates from. Fields that were added without a migration rely on their serde defaults.
+*/
+#[test]
+fn saves_from_before_versioning_are_migrated() {
+    use serde_json::{json, Value};
+    use super::configuration_defaults::ConfigurationDefaults;
+
+    /* these keep what the run did before they existed rather than the defaults of a new run */
+    let unversioned_keys = ["seed", "run_save_format", "checkpoint_every_generations", "checkpoint_every_minutes", "checkpoint_keep_last"];
+    let defaults = serde_json::to_value(ConfigurationDefaults::get()).unwrap();
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/neat/trainer/save_fixtures/version_0.neatrun");
+    let bytes = fs::read(&path).unwrap();
+    let (saved_version, format, payload) = Checkpoint::read_header(&bytes).unwrap();
+    assert_eq!((saved_version, format), (0, SaveFormat::Json));
+    let saved: Value = serde_json::from_slice(payload).unwrap();
+    let mut migrated = saved.clone();
+    SaveMigrations::migrate(&mut migrated, 0).unwrap();
+
+    let saved_color = &saved["members"][0]["genome"]["color"]["Rgba"];
+    assert_eq!(migrated["members"][0]["genome"]["color"], json!({ "red": saved_color["red"], "green": saved_color["green"], "blue": saved_color["blue"] }));
+    assert!(!migrated.to_string().contains("Rgba"));
+    for species in migrated["run_context"]["species_index"].as_object().unwrap().values(){
+        assert_eq!(species["raw_objective_fitness"], species["objective_fitness"]);
+        for member in species["members"].as_array().unwrap(){
+            assert_eq!(member["raw_objective_fitness"], member["objective_fitness"]);
+        }
+    }
+
+    let loaded: NeatTrainer = Checkpoint::read(&path).unwrap();
+    assert_eq!(loaded.get_current_generation(), 3);
+    assert_eq!(loaded.members.len(), saved["members"].as_array().unwrap().len());
+    let loaded_configuration = serde_json::to_value(&loaded.configuration).unwrap();
+    for (key, value) in loaded_configuration.as_object().unwrap(){
+        if saved["configuration"].get(key).is_none() && !unversioned_keys.contains(&key.as_str()){
+            assert_eq!(value, &defaults[key], "{} of version 0", key);
+        }
+    }
+}
+
+#[test]
+fn newer_save_versions_are_rejected() {
+    let directory = std::env::temp_dir().join(format!("neatlib-checkpoint-version-{}", std::process::id()));
+    let _ = fs::create_dir_all(&directory);
+    let path = directory.join("future.neatrun");
+    let mut bytes = HEADER_MAGIC.to_vec();
+    bytes.extend((SaveMigrations::current_version() + 1).to_le_bytes());
+    bytes.push(0);
+    bytes.extend(b"{}");
+    fs::write(&path, bytes).unwrap();
+
+    let result = Checkpoint::read::<crate::neat::genome::neat::NeatGenome>(&path);
+
+    assert_eq!(result.err(), Some(LoadError::UnsupportedVersion { version: SaveMigrations::current_version() + 1, current_version: SaveMigrations::current_version() }));
+    let _ = fs::remove_dir_all(&directory);
+}
+
+#[test]
+fn missing_save_is_a_read_error() {
+    let result = NeatTrainer::<crate::neat::genome::neat::NeatGenome>::load("does-not-exist.neatrun".to_string());
+
+    assert!(matches!(result, Err(LoadError::Read { .. })));
+}
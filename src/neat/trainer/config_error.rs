@@ -0,0 +1,51 @@
+use std::fmt;
+use crate::common::NeatFloat;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError{
+    NoNodeGenes,
+    DuplicateNodeNumbers,
+    NoSensors,
+    SensorsMustStartAtOne,
+    NodeNumbersNotContiguous,
+    NoOutputs,
+    ProbabilityOutOfRange{ property: &'static str, value: NeatFloat },
+    MinGreaterThanMax{ min_property: &'static str, max_property: &'static str, min: NeatFloat, max: NeatFloat },
+    MustBeGreaterThanZero{ property: &'static str },
+    MustBeFinite{ property: &'static str },
+    UnknownSpeciationStage(String),
+    UnknownCurriculumStage(String),
+    FileRead{ path: String, message: String },
+    FileParse{ path: String, message: String },
+    UnsupportedFileFormat(String),
+    UnknownKey(String),
+    InvalidOverride(String),
+    InvalidValue(String)
+}
+
+impl fmt::Display for ConfigError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::NoNodeGenes => write!(f, "Must have node genes"),
+            ConfigError::DuplicateNodeNumbers => write!(f, "Node genes must have unique indexes"),
+            ConfigError::NoSensors => write!(f, "Configuration does not contain any sensors"),
+            ConfigError::SensorsMustStartAtOne => write!(f, "Sensors must start at index 1"),
+            ConfigError::NodeNumbersNotContiguous => write!(f, "node indexes should be contiguous. for example [1, 2, 3]"),
+            ConfigError::NoOutputs => write!(f, "Configuration does not contain any outputs"),
+            ConfigError::ProbabilityOutOfRange { property, value } => write!(f, "{} must be between 0 and 1, got {}", property, value),
+            ConfigError::MinGreaterThanMax { min_property, max_property, min, max } => write!(f, "{} ({}) must not be greater than {} ({})", min_property, min, max_property, max),
+            ConfigError::MustBeGreaterThanZero { property } => write!(f, "{} must be greater than 0", property),
+            ConfigError::MustBeFinite { property } => write!(f, "{} must be a finite number", property),
+            ConfigError::UnknownSpeciationStage(name) => write!(f, "speciation stage {} is not a built in stage and has not been added to the trainer", name),
+            ConfigError::UnknownCurriculumStage(name) => write!(f, "the run is on curriculum stage {} which is not in the curriculum", name),
+            ConfigError::FileRead { path, message } => write!(f, "could not read configuration file {}: {}", path, message),
+            ConfigError::FileParse { path, message } => write!(f, "could not parse configuration file {}: {}", path, message),
+            ConfigError::UnsupportedFileFormat(path) => write!(f, "unsupported configuration file format {}, expected .toml or .json", path),
+            ConfigError::UnknownKey(key) => write!(f, "unknown configuration key {}", key),
+            ConfigError::InvalidOverride(value) => write!(f, "invalid override {}, expected KEY=VALUE", value),
+            ConfigError::InvalidValue(message) => write!(f, "invalid configuration value: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
@@ -1,8 +1,8 @@
 #![allow(unused)]
 use array_tool::vec::{Uniq, self};
 use serde::{Deserialize, Serialize, Serializer};
-use crate::{neat::{genome::neat::{node_gene::NodeGene, mutation_add_mode::MutationNodeAddMode}, genetable::connect_gene_table::ConnectGeneTable}, node_kind::NodeKind, activation_functions::ActivationFunction, common::{NeatFloat, event_stream::{event::EventType, listeners::listeners::Listeners, event_subscription::EventSubscription}}};
-use super::{configuration_defaults::ConfigurationDefaults, node_conf::NodeConf};
+use crate::{neat::{genome::neat::{node_gene::NodeGene, mutation_add_mode::MutationNodeAddMode}, population::speciation::{built_in_speciation_stage::BuiltInSpeciationStage, selection_strategy::SelectionStrategy}, genetable::connect_gene_table::ConnectGeneTable}, node_kind::NodeKind, activation_functions::ActivationFunction, common::{NeatFloat, event_stream::{event::EventType, listeners::listeners::Listeners, event_subscription::EventSubscription}}};
+use super::{fitness::{fitness_aggregation::FitnessAggregation, fitness_shaping::FitnessShaping, invalid_fitness_policy::InvalidFitnessPolicy}, configuration_defaults::ConfigurationDefaults, node_conf::NodeConf, config_error::ConfigError, mutation_schedule::MutationSchedule};
 
 pub const MUTABLE_CONFIG_PARAMS: usize = 9;
 #[derive(Serialize, Deserialize)]
@@ -27,6 +27,8 @@ pub struct Configuration{
     pub mutation_remove_unconnected_nodes: bool,
     pub mutation_connection_disable_probability: NeatFloat,
     pub mutation_node_available_activation_functions: ActivationFunction,
+    #[serde(default)]
+    pub mutation_schedule: MutationSchedule,
     pub node_bias_min_value: NeatFloat,
     pub node_bias_max_value: NeatFloat,
     pub connection_weight_min_value: NeatFloat,
@@ -41,6 +43,8 @@ pub struct Configuration{
     pub speciation_offspring_mode: OffSpringMode,
     pub speciation_offspring_outcome_novelty_weight: NeatFloat,
     pub speciation_new_species_protected_for_generations: u32,
+    #[serde(default)]
+    pub speciation_new_species_min_offspring: u32,
     pub speciation_use_best_seed_bank: Option<usize>,
     pub speciation_add_best_member_back_in: bool,
     pub speciation_preserve_elite: bool,
@@ -48,51 +52,202 @@ pub struct Configuration{
     pub speciation_max_threshold: NeatFloat,
     pub speciation_species_min_number_of_members: usize,
     pub speciation_cross_species_reproduction_scale: NeatFloat,
+    #[serde(default = "BuiltInSpeciationStage::default_pipeline")]
+    pub speciation_pipeline: Vec<String>,
+    #[serde(default)]
+    pub speciation_selection_strategy: SelectionStrategy,
+    #[serde(default = "ConfigurationDefaults::speciation_tournament_size")]
+    pub speciation_tournament_size: usize,
+    #[serde(default = "ConfigurationDefaults::speciation_truncation_ratio")]
+    pub speciation_truncation_ratio: NeatFloat,
     pub reproduction_weights_from_fitter_probability: NeatFloat,
     pub print_summary_interval: Option<u32>,
     pub print_summary_number_of_species_to_show: usize,
     pub success_threshold: NeatFloat,
     pub event_subscriptions: Vec<EventSubscription>,
     pub run_save_directory: String,
-    pub run_name: String
+    pub run_name: String,
+    #[serde(default)]
+    pub run_save_format: SaveFormat,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub checkpoint_every_generations: Option<u32>,
+    #[serde(default)]
+    pub checkpoint_every_minutes: Option<u32>,
+    #[serde(default)]
+    pub checkpoint_keep_last: Option<usize>,
+    #[serde(default = "ConfigurationDefaults::fitness_cache_enabled")]
+    pub fitness_cache_enabled: bool,
+    #[serde(default = "ConfigurationDefaults::fitness_evaluations_per_genome")]
+    pub fitness_evaluations_per_genome: u32,
+    #[serde(default)]
+    pub fitness_aggregation: FitnessAggregation,
+    #[serde(default)]
+    pub fitness_reevaluate_elites_every_generations: Option<u32>,
+    #[serde(default)]
+    pub multi_objective_enabled: bool,
+    #[serde(default)]
+    pub fitness_shaping: FitnessShaping,
+    #[serde(default)]
+    pub fitness_invalid_policy: InvalidFitnessPolicy,
+    #[serde(default)]
+    pub fitness_invalid_penalty: NeatFloat,
+    #[serde(default)]
+    pub fitness_time_budget_milliseconds: Option<u64>,
+    #[serde(default)]
+    pub fitness_step_budget: Option<u64>,
+    #[serde(default)]
+    pub fitness_over_budget_penalty: NeatFloat
 }
 
 impl Configuration{
     pub fn neat(node_genes: Box<Vec<NodeGene>>, success_threshold: NeatFloat) -> Self {
-        if !node_genes.len() == 0{
-            panic!("Must have node genes");
+        match Self::try_neat(node_genes, success_threshold) {
+            Ok(configuration) => configuration,
+            Err(error) => panic!("{}", error)
         }
+    }
+    pub fn try_neat(node_genes: Box<Vec<NodeGene>>, success_threshold: NeatFloat) -> Result<Self, ConfigError> {
+        let mut configuration = ConfigurationDefaults::get();
+        configuration.node_genes = node_genes;
+        configuration.success_threshold = success_threshold;
+        configuration.validate()?;
+        Ok(configuration)
+    }
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        Self::validate_node_genes(&self.node_genes)?;
+        self.validate_speciation_pipeline(&[])?;
 
-        if !node_genes.iter().map(|n| n.number).collect::<Vec<i32>>().is_unique(){
-            panic!("Node genes must have unique indexes");
+        let probabilities = [
+            ("genome_minimal_genes_to_connect_ratio", self.genome_minimal_genes_to_connect_ratio),
+            ("mutation_node_add_probability", self.mutation_node_add_probability),
+            ("mutation_node_delete_probability", self.mutation_node_delete_probability),
+            ("mutation_connection_add_probability", self.mutation_connection_add_probability),
+            ("mutation_connection_delete_probability", self.mutation_connection_delete_probability),
+            ("mutation_connection_weight_change_probability", self.mutation_connection_weight_change_probability),
+            ("mutation_connection_weight_replace_probability", self.mutation_connection_weight_replace_probability),
+            ("mutation_node_bias_change_probability", self.mutation_node_bias_change_probability),
+            ("mutation_node_cppn_input_multiplier_change_probability", self.mutation_node_cppn_input_multiplier_change_probability),
+            ("mutation_node_cppn_input_multiplier_replace_probability", self.mutation_node_cppn_input_multiplier_replace_probability),
+            ("mutation_connection_disable_probability", self.mutation_connection_disable_probability),
+            ("survival_threshold", self.survival_threshold),
+            ("reproduction_weights_from_fitter_probability", self.reproduction_weights_from_fitter_probability),
+            ("speciation_truncation_ratio", self.speciation_truncation_ratio),
+        ];
+        for (property, value) in probabilities {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(ConfigError::ProbabilityOutOfRange { property, value });
+            }
+        }
+
+        let bounds = [
+            ("node_bias_min_value", self.node_bias_min_value, "node_bias_max_value", self.node_bias_max_value),
+            ("connection_weight_min_value", self.connection_weight_min_value, "connection_weight_max_value", self.connection_weight_max_value),
+            ("speciation_min_threshold", self.speciation_min_threshold, "speciation_max_threshold", self.speciation_max_threshold),
+            ("mutation_schedule.optimistic_after_generations", self.mutation_schedule.optimistic_after_generations as NeatFloat, "mutation_schedule.fool_hardy_after_generations", self.mutation_schedule.fool_hardy_after_generations as NeatFloat),
+            ("mutation_schedule.fool_hardy_after_generations", self.mutation_schedule.fool_hardy_after_generations as NeatFloat, "mutation_schedule.shoot_for_the_moon_after_generations", self.mutation_schedule.shoot_for_the_moon_after_generations as NeatFloat),
+        ];
+        for (min_property, min, max_property, max) in bounds {
+            if min > max {
+                return Err(ConfigError::MinGreaterThanMax { min_property, max_property, min, max });
+            }
+        }
+
+        if self.population_size == 0 {
+            return Err(ConfigError::MustBeGreaterThanZero { property: "population_size" });
+        }
+        if self.target_species == 0 {
+            return Err(ConfigError::MustBeGreaterThanZero { property: "target_species" });
+        }
+        if self.survival_threshold == 0.0 {
+            return Err(ConfigError::MustBeGreaterThanZero { property: "survival_threshold" });
+        }
+        if self.speciation_tournament_size == 0 {
+            return Err(ConfigError::MustBeGreaterThanZero { property: "speciation_tournament_size" });
+        }
+        if self.speciation_truncation_ratio == 0.0 {
+            return Err(ConfigError::MustBeGreaterThanZero { property: "speciation_truncation_ratio" });
+        }
+        let structural_mutations = [
+            ("mutation_schedule.steady_structural_mutations", self.mutation_schedule.steady_structural_mutations),
+            ("mutation_schedule.optimistic_structural_mutations", self.mutation_schedule.optimistic_structural_mutations),
+            ("mutation_schedule.fool_hardy_structural_mutations", self.mutation_schedule.fool_hardy_structural_mutations),
+            ("mutation_schedule.shoot_for_the_moon_structural_mutations", self.mutation_schedule.shoot_for_the_moon_structural_mutations),
+        ];
+        for (property, value) in structural_mutations {
+            if value == 0 {
+                return Err(ConfigError::MustBeGreaterThanZero { property });
+            }
+        }
+        if self.checkpoint_every_generations == Some(0) {
+            return Err(ConfigError::MustBeGreaterThanZero { property: "checkpoint_every_generations" });
+        }
+        if self.fitness_time_budget_milliseconds == Some(0) {
+            return Err(ConfigError::MustBeGreaterThanZero { property: "fitness_time_budget_milliseconds" });
+        }
+        if self.fitness_step_budget == Some(0) {
+            return Err(ConfigError::MustBeGreaterThanZero { property: "fitness_step_budget" });
+        }
+        if self.checkpoint_every_minutes == Some(0) {
+            return Err(ConfigError::MustBeGreaterThanZero { property: "checkpoint_every_minutes" });
+        }
+        if self.checkpoint_keep_last == Some(0) {
+            return Err(ConfigError::MustBeGreaterThanZero { property: "checkpoint_keep_last" });
+        }
+        if self.fitness_evaluations_per_genome == 0 {
+            return Err(ConfigError::MustBeGreaterThanZero { property: "fitness_evaluations_per_genome" });
+        }
+        if self.fitness_reevaluate_elites_every_generations == Some(0) {
+            return Err(ConfigError::MustBeGreaterThanZero { property: "fitness_reevaluate_elites_every_generations" });
+        }
+        /* penalties are written into members as their fitness, which has to stay comparable */
+        if !self.fitness_invalid_penalty.is_finite() {
+            return Err(ConfigError::MustBeFinite { property: "fitness_invalid_penalty" });
+        }
+        if !self.fitness_over_budget_penalty.is_finite() {
+            return Err(ConfigError::MustBeFinite { property: "fitness_over_budget_penalty" });
+        }
+        Ok(())
+    }
+    /* every stage in the pipeline has to be a built in stage or one of the given stages registered on the trainer */
+    pub fn validate_speciation_pipeline(&self, registered_stage_names: &[&str]) -> Result<(), ConfigError> {
+        for stage_name in &self.speciation_pipeline{
+            if BuiltInSpeciationStage::from_name(stage_name).is_none() && !registered_stage_names.contains(&stage_name.as_str()){
+                return Err(ConfigError::UnknownSpeciationStage(stage_name.clone()));
+            }
+        }
+        Ok(())
+    }
+    fn validate_node_genes(node_genes: &[NodeGene]) -> Result<(), ConfigError> {
+        if node_genes.is_empty(){
+            return Err(ConfigError::NoNodeGenes);
         }
 
-        if !node_genes.iter().any(|n| n.kind == NodeKind::Sensor){
-            panic!("Configuration does not contain any sensors");
+        if !node_genes.iter().map(|n| n.number).collect::<Vec<i32>>().is_unique(){
+            return Err(ConfigError::DuplicateNodeNumbers);
         }
 
         let first_sensor = node_genes.iter().find(|n| n.kind == NodeKind::Sensor);
+        if first_sensor.is_none(){
+            return Err(ConfigError::NoSensors);
+        }
         if first_sensor.unwrap().number != 1{
-            panic!("Sensors must start at index 1");
+            return Err(ConfigError::SensorsMustStartAtOne);
         }
 
         let mut expected_index = 1;
         for n in node_genes.iter() {
             if n.number != expected_index {
-                panic!("node indexes should be contiguous. for example [1, 2, 3]");
+                return Err(ConfigError::NodeNumbersNotContiguous);
             }
             expected_index +=1;
         }
 
         if !node_genes.iter().any(|n| n.kind == NodeKind::Output){
-            panic!("Configuration does not contain any outputs");
+            return Err(ConfigError::NoOutputs);
         }
-
-        let initial_count = node_genes.iter().map(|n| n.number).max().unwrap() as usize;
-        let mut configuration = ConfigurationDefaults::get();
-        configuration.node_genes = node_genes;
-        configuration.success_threshold = success_threshold;
-        configuration
+        Ok(())
     }
     pub fn mutation_no_mutation(mut self) -> Self {
         self.mutation_node_add_probability = 0.0;
@@ -180,6 +335,10 @@ impl Configuration{
         self.mutation_node_available_activation_functions = ActivationFunction::for_cppn();
         self
     }
+    pub fn mutation_schedule(mut self, value: MutationSchedule) -> Self {
+        self.mutation_schedule = value;
+        self
+    }
     pub fn speciation_genetic_distance_threshold(mut self, value: NeatFloat) -> Self {
         self.speciation_genetic_distance_threshold = value;
         self
@@ -212,6 +371,23 @@ impl Configuration{
         self.speciation_offspring_mode = value;
         self
     }
+    pub fn speciation_selection_strategy(mut self, value: SelectionStrategy) -> Self {
+        self.speciation_selection_strategy = value;
+        self
+    }
+    pub fn speciation_tournament_size(mut self, value: usize) -> Self {
+        self.speciation_tournament_size = value;
+        self
+    }
+    pub fn speciation_truncation_ratio(mut self, value: NeatFloat) -> Self {
+        self.speciation_truncation_ratio = value;
+        self
+    }
+    /* offspring a species gets at least while it is protected, taken from the unprotected species with the most */
+    pub fn speciation_new_species_min_offspring(mut self, value: u32) -> Self {
+        self.speciation_new_species_min_offspring = value;
+        self
+    }
     pub fn speciation_offspring_outcome_novelty_weight(mut self, value: NeatFloat) -> Self {
         self.speciation_offspring_outcome_novelty_weight = value;
         self
@@ -239,6 +415,10 @@ impl Configuration{
         self.speciation_cross_species_reproduction_scale = value;
         self
     }
+    pub fn speciation_pipeline(mut self, value: Vec<String>) -> Self {
+        self.speciation_pipeline = value;
+        self
+    }
     pub fn reproduction_weights_from_fitter_probability(mut self, value: NeatFloat) -> Self {
         self.reproduction_weights_from_fitter_probability = value;
         self
@@ -282,6 +462,78 @@ impl Configuration{
         self.run_name = value;
         self
     }
+    pub fn seed(mut self, value: Option<u64>) -> Self{
+        self.seed = value;
+        self
+    }
+    pub fn checkpoint_every_generations(mut self, value: Option<u32>) -> Self{
+        self.checkpoint_every_generations = value;
+        self
+    }
+    pub fn checkpoint_every_minutes(mut self, value: Option<u32>) -> Self{
+        self.checkpoint_every_minutes = value;
+        self
+    }
+    pub fn checkpoint_keep_last(mut self, value: Option<usize>) -> Self{
+        self.checkpoint_keep_last = value;
+        self
+    }
+    pub fn run_save_format(mut self, value: SaveFormat) -> Self{
+        self.run_save_format = value;
+        self
+    }
+    /* turn off when the fitness function is noisy or stochastic so every member is evaluated every generation */
+    pub fn fitness_cache_enabled(mut self, value: bool) -> Self{
+        self.fitness_cache_enabled = value;
+        self
+    }
+    /* with a noisy fitness function each genome is evaluated this many times and the results aggregated */
+    pub fn fitness_evaluations_per_genome(mut self, value: u32) -> Self{
+        self.fitness_evaluations_per_genome = value;
+        self
+    }
+    pub fn fitness_aggregation(mut self, value: FitnessAggregation) -> Self{
+        self.fitness_aggregation = value;
+        self
+    }
+    /* re-tests the champion and the seed bank so a lucky evaluation does not stay on top forever */
+    pub fn fitness_reevaluate_elites_every_generations(mut self, value: Option<u32>) -> Self{
+        self.fitness_reevaluate_elites_every_generations = value;
+        self
+    }
+    /* ranks members by pareto front and crowding distance of the objectives added with FitnessResolver::add_objective */
+    pub fn multi_objective_enabled(mut self, value: bool) -> Self{
+        self.multi_objective_enabled = value;
+        self
+    }
+    /* reshapes the objective fitness before species metrics and offspring are calculated, the raw fitness is still kept for stats */
+    pub fn fitness_shaping(mut self, value: FitnessShaping) -> Self{
+        self.fitness_shaping = value;
+        self
+    }
+    /* how members are treated when the fitness function panics or gives a NaN, infinite or subnormal value */
+    pub fn fitness_invalid_policy(mut self, value: InvalidFitnessPolicy) -> Self{
+        self.fitness_invalid_policy = value;
+        self
+    }
+    pub fn fitness_invalid_penalty(mut self, value: NeatFloat) -> Self{
+        self.fitness_invalid_penalty = value;
+        self
+    }
+    /* per genome evaluation, fitness functions see it through FitnessResolver::should_stop, it does not apply to batch evaluation */
+    pub fn fitness_time_budget_milliseconds(mut self, value: Option<u64>) -> Self{
+        self.fitness_time_budget_milliseconds = value;
+        self
+    }
+    /* per genome evaluation, counted with FitnessResolver::add_steps */
+    pub fn fitness_step_budget(mut self, value: Option<u64>) -> Self{
+        self.fitness_step_budget = value;
+        self
+    }
+    pub fn fitness_over_budget_penalty(mut self, value: NeatFloat) -> Self{
+        self.fitness_over_budget_penalty = value;
+        self
+    }
     
 
 }
@@ -292,7 +544,17 @@ impl Configuration{
 pub enum OffSpringMode {
     Average,
     AdjustedMemberRange,
-    AdjustedSpeciesRange
+    AdjustedSpeciesRange,
+    /* offspring in proportion to the summed fitness of the members divided by the species size */
+    FitnessSharing
+}
+
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SaveFormat {
+    #[default]
+    Json,
+    CompressedBincode
 }
 
 #[test]
@@ -343,4 +605,71 @@ fn property_setters_work() {
     assert_eq!(configuration.mutation_node_delete_probability, 0.2);
     assert_eq!(configuration.mutation_connection_add_probability, 0.3);
     assert_eq!(configuration.mutation_connection_delete_probability, 0.4);
-}
\ No newline at end of file
+}
+
+#[test]
+fn try_neat_returns_error_when_no_nodes() {
+    let result = Configuration::try_neat(Box::default(), 0.0);
+
+    assert_eq!(result.err(), Some(ConfigError::NoNodeGenes));
+}
+
+#[test]
+fn try_neat_returns_error_when_no_outputs() {
+    let result = Configuration::try_neat(Box::new(vec![NodeGene::new(1, NodeKind::Sensor)]), 0.0);
+
+    assert_eq!(result.err(), Some(ConfigError::NoOutputs));
+}
+
+#[test]
+fn validate_rejects_probability_out_of_range() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 0.0)
+        .mutation_node_add_probability(1.5);
+
+    assert_eq!(configuration.validate(), Err(ConfigError::ProbabilityOutOfRange { property: "mutation_node_add_probability", value: 1.5 }));
+}
+
+#[test]
+fn validate_rejects_min_greater_than_max() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 0.0)
+        .speciation_min_threshold(5.0)
+        .speciation_max_threshold(1.0);
+
+    assert!(matches!(configuration.validate(), Err(ConfigError::MinGreaterThanMax { min_property: "speciation_min_threshold", .. })));
+}
+
+#[test]
+fn validate_rejects_unordered_mutation_schedule() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 0.0)
+        .mutation_schedule(MutationSchedule { fool_hardy_after_generations: 20, ..MutationSchedule::default() });
+
+    assert!(matches!(configuration.validate(), Err(ConfigError::MinGreaterThanMax { min_property: "mutation_schedule.fool_hardy_after_generations", .. })));
+}
+
+#[test]
+fn validate_accepts_defaults() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 0.0);
+
+    assert!(configuration.validate().is_ok());
+}
+
+#[test]
+fn validate_rejects_a_penalty_that_is_not_finite() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 0.0)
+        .fitness_invalid_penalty(NeatFloat::NAN);
+
+    assert_eq!(configuration.validate(), Err(ConfigError::MustBeFinite { property: "fitness_invalid_penalty" }));
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 0.0)
+        .fitness_over_budget_penalty(NeatFloat::INFINITY);
+
+    assert_eq!(configuration.validate(), Err(ConfigError::MustBeFinite { property: "fitness_over_budget_penalty" }));
+}
+
+#[test]
+fn validate_rejects_unknown_speciation_stage() {
+    let mut configuration = Configuration::neat(NodeConf::simple(2, 1), 0.0);
+    configuration.speciation_pipeline.push("my_stage".to_string());
+
+    assert_eq!(configuration.validate(), Err(ConfigError::UnknownSpeciationStage("my_stage".to_string())));
+    assert!(configuration.validate_speciation_pipeline(&["my_stage"]).is_ok());
+}
@@ -1,6 +1,6 @@
-use crate::{activation_functions::ActivationFunction, common::NeatFloat, neat::genome::neat::mutation_add_mode::MutationNodeAddMode};
+use crate::{activation_functions::ActivationFunction, common::NeatFloat, neat::{genome::neat::mutation_add_mode::MutationNodeAddMode, population::speciation::{built_in_speciation_stage::BuiltInSpeciationStage, selection_strategy::SelectionStrategy}}};
 
-use super::{configuration::{Configuration, OffSpringMode}, config_regulators::{config_regulator::ConfigRegulator, regulatable_configuration_properties::RegulatableConfigurationProperties, available_regulation_signals::AvailableRegulationSignals}};
+use super::{fitness::{fitness_aggregation::FitnessAggregation, fitness_shaping::FitnessShaping, invalid_fitness_policy::InvalidFitnessPolicy}, configuration::{Configuration, OffSpringMode, SaveFormat}, mutation_schedule::MutationSchedule, config_regulators::{config_regulator::ConfigRegulator, regulatable_configuration_properties::RegulatableConfigurationProperties, available_regulation_signals::AvailableRegulationSignals}};
 
 pub struct ConfigurationDefaults;
 
@@ -26,6 +26,7 @@ impl ConfigurationDefaults{
             mutation_remove_unconnected_nodes: true,
             mutation_connection_disable_probability: 0.0,
             mutation_node_available_activation_functions: ActivationFunction::RELU | ActivationFunction::SIGMOID | ActivationFunction::TANH | ActivationFunction::BINARY,
+            mutation_schedule: MutationSchedule::default(),
             connection_weight_min_value: -1.0,
             connection_weight_max_value: 1.0,
             node_bias_min_value: -1.0,
@@ -40,6 +41,7 @@ impl ConfigurationDefaults{
             speciation_offspring_mode: OffSpringMode::AdjustedSpeciesRange,
             speciation_offspring_outcome_novelty_weight: 0.1,
             speciation_new_species_protected_for_generations: 5,
+            speciation_new_species_min_offspring: 0,
             speciation_use_best_seed_bank: Some(5),
             speciation_add_best_member_back_in: true,
             speciation_preserve_elite: true,
@@ -47,15 +49,47 @@ impl ConfigurationDefaults{
             speciation_min_threshold: 0.1,
             speciation_species_min_number_of_members: 0,
             speciation_cross_species_reproduction_scale: 0.01,
+            speciation_pipeline: BuiltInSpeciationStage::default_pipeline(),
+            speciation_selection_strategy: SelectionStrategy::Random,
+            speciation_tournament_size: Self::speciation_tournament_size(),
+            speciation_truncation_ratio: Self::speciation_truncation_ratio(),
             reproduction_weights_from_fitter_probability: 0.51,
             print_summary_interval: None,
             print_summary_number_of_species_to_show: 10,
             success_threshold: 0.0,
             event_subscriptions: vec![],
             run_save_directory: "\\neatlib\\runs\\".to_string(),
-            run_name: "none".to_string()
+            run_name: "none".to_string(),
+            run_save_format: SaveFormat::Json,
+            seed: None,
+            checkpoint_every_generations: None,
+            checkpoint_every_minutes: None,
+            checkpoint_keep_last: Some(5),
+            fitness_cache_enabled: Self::fitness_cache_enabled(),
+            fitness_evaluations_per_genome: Self::fitness_evaluations_per_genome(),
+            fitness_aggregation: FitnessAggregation::Mean,
+            fitness_reevaluate_elites_every_generations: None,
+            multi_objective_enabled: false,
+            fitness_shaping: FitnessShaping::None,
+            fitness_invalid_policy: InvalidFitnessPolicy::Penalty,
+            fitness_invalid_penalty: 0.0,
+            fitness_time_budget_milliseconds: None,
+            fitness_step_budget: None,
+            fitness_over_budget_penalty: 0.0
         }
     }
+    pub fn fitness_cache_enabled() -> bool{
+        true
+    }
+    pub fn fitness_evaluations_per_genome() -> u32{
+        1
+    }
+    pub fn speciation_tournament_size() -> usize{
+        3
+    }
+    pub fn speciation_truncation_ratio() -> NeatFloat{
+        0.5
+    }
     pub fn get_default_regulators(configuration: &Configuration) -> Vec<ConfigRegulator>{
         vec![ConfigRegulator {
             start_generation: 0,
@@ -0,0 +1,192 @@
+use std::path::Path;
+use serde_json::Value;
+use crate::neat::genome::{genome::Genome, crossover::Crossover, mutation_history::MutationHistory};
+use super::{neat_trainer::NeatTrainer, configuration::Configuration, configuration_defaults::ConfigurationDefaults, config_regulators::config_regulator::ConfigRegulator, config_error::ConfigError};
+
+const CONFIG_REGULATORS_KEY: &str = "config_regulators";
+
+/*
+A configuration file only needs to contain the values that differ from the defaults, for example:
+
+    population_size = 500
+    speciation_offspring_mode = "Average"
+
+    [[node_genes]]
+    ...
+
+KEY=VALUE overrides (e.g. from the command line) are applied on top of the file. Nested values
+are addressed with dots, VALUE is read as a plain string for string values and as JSON otherwise.
+*/
+#[derive(Debug, Clone)]
+pub struct ConfigurationFile{
+    pub configuration: Configuration,
+    pub config_regulators: Vec<ConfigRegulator>
+}
+
+impl ConfigurationFile{
+    pub fn load<P, I, S>(path: P, overrides: I) -> Result<Self, ConfigError> where P: AsRef<Path>, I: IntoIterator<Item = S>, S: AsRef<str>{
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| ConfigError::FileRead { path: path.display().to_string(), message: e.to_string() })?;
+        let file_values = Self::parse(path, &contents)?;
+        Self::from_values(file_values, overrides)
+    }
+    pub fn from_toml_str<I, S>(contents: &str, overrides: I) -> Result<Self, ConfigError> where I: IntoIterator<Item = S>, S: AsRef<str>{
+        let file_values = toml::from_str::<Value>(contents).map_err(|e| ConfigError::FileParse { path: "<toml>".to_string(), message: e.to_string() })?;
+        Self::from_values(file_values, overrides)
+    }
+    fn parse(path: &Path, contents: &str) -> Result<Value, ConfigError>{
+        let parse_error = |message: String| ConfigError::FileParse { path: path.display().to_string(), message };
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => toml::from_str::<Value>(contents).map_err(|e| parse_error(e.to_string())),
+            Some("json") => serde_json::from_str::<Value>(contents).map_err(|e| parse_error(e.to_string())),
+            _ => Err(ConfigError::UnsupportedFileFormat(path.display().to_string()))
+        }
+    }
+    fn from_values<I, S>(file_values: Value, overrides: I) -> Result<Self, ConfigError> where I: IntoIterator<Item = S>, S: AsRef<str>{
+        let Value::Object(mut file_values) = file_values else {
+            return Err(ConfigError::FileParse { path: String::new(), message: "expected a table of configuration values".to_string() });
+        };
+        let mut config_regulators_value = file_values.remove(CONFIG_REGULATORS_KEY);
+
+        let mut values = serde_json::to_value(ConfigurationDefaults::get()).unwrap();
+        Self::merge(&mut values, Value::Object(file_values), "")?;
+
+        for key_value in overrides{
+            let key_value = key_value.as_ref();
+            let (key, raw_value) = key_value.split_once('=').ok_or_else(|| ConfigError::InvalidOverride(key_value.to_string()))?;
+
+            if key == CONFIG_REGULATORS_KEY {
+                config_regulators_value = Some(Self::parse_override(&Value::Null, raw_value));
+            }else{
+                let current = Self::get_path(&mut values, key)?;
+                *current = Self::parse_override(current, raw_value);
+            }
+        }
+
+        let configuration: Configuration = serde_json::from_value(values).map_err(|e| ConfigError::InvalidValue(e.to_string()))?;
+        configuration.validate()?;
+
+        let config_regulators = match config_regulators_value {
+            Some(value) => serde_json::from_value(value).map_err(|e| ConfigError::InvalidValue(e.to_string()))?,
+            None => ConfigurationDefaults::get_default_regulators(&configuration)
+        };
+
+        Ok(Self { configuration, config_regulators })
+    }
+    fn merge(target: &mut Value, source: Value, path: &str) -> Result<(), ConfigError>{
+        match (target, source) {
+            (Value::Object(target), Value::Object(source)) => {
+                for (key, value) in source{
+                    let key_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                    match target.get_mut(&key) {
+                        Some(existing) => Self::merge(existing, value, &key_path)?,
+                        None if path.is_empty() => return Err(ConfigError::UnknownKey(key_path)),
+                        None => { target.insert(key, value); }
+                    }
+                }
+            },
+            (target, source) => *target = source
+        }
+        Ok(())
+    }
+    fn get_path<'a>(values: &'a mut Value, key: &str) -> Result<&'a mut Value, ConfigError>{
+        let mut current = values;
+        for segment in key.split('.'){
+            current = match current {
+                Value::Object(map) => map.get_mut(segment),
+                Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get_mut(i)),
+                _ => None
+            }.ok_or_else(|| ConfigError::UnknownKey(key.to_string()))?;
+        }
+        Ok(current)
+    }
+    /* string values are taken as they are so run_name=123 stays a string, anything else is read as JSON falling back to a string */
+    fn parse_override(current: &Value, raw_value: &str) -> Value{
+        if current.is_string(){
+            return match serde_json::from_str::<Value>(raw_value) {
+                Ok(Value::String(value)) => Value::String(value),
+                _ => Value::String(raw_value.to_string())
+            };
+        }
+        serde_json::from_str::<Value>(raw_value).unwrap_or_else(|_| Value::String(raw_value.to_string()))
+    }
+}
+
+impl<T> NeatTrainer<T> where T: Genome + Crossover + MutationHistory{
+    /* a trainer with the configuration and the config regulators of the file */
+    pub fn from_configuration_file(file: ConfigurationFile) -> Result<Self, ConfigError>{
+        let mut trainer = Self::try_new_for_genome(file.configuration)?;
+        trainer.set_config_regulators(file.config_regulators);
+        Ok(trainer)
+    }
+}
+
+impl Configuration{
+    pub fn from_file<P>(path: P) -> Result<Self, ConfigError> where P: AsRef<Path>{
+        Ok(ConfigurationFile::load(path, Vec::<String>::new())?.configuration)
+    }
+    pub fn from_file_with_overrides<P, I, S>(path: P, overrides: I) -> Result<Self, ConfigError> where P: AsRef<Path>, I: IntoIterator<Item = S>, S: AsRef<str>{
+        Ok(ConfigurationFile::load(path, overrides)?.configuration)
+    }
+}
+
+#[test]
+fn partial_file_overrides_defaults() {
+    let file = ConfigurationFile::from_toml_str(&node_genes_toml("population_size = 123\nspeciation_offspring_mode = \"Average\""), Vec::<String>::new()).unwrap();
+
+    assert_eq!(file.configuration.population_size, 123);
+    assert_eq!(file.configuration.speciation_offspring_mode, super::configuration::OffSpringMode::Average);
+    assert_eq!(file.configuration.target_species, ConfigurationDefaults::get().target_species);
+    assert_eq!(file.config_regulators.len(), ConfigurationDefaults::get_default_regulators(&file.configuration).len());
+}
+
+#[test]
+fn key_value_overrides_apply_after_file() {
+    let file = ConfigurationFile::from_toml_str(&node_genes_toml("population_size = 123"), ["population_size=50", "seed=7", "run_name=test"]).unwrap();
+
+    assert_eq!(file.configuration.population_size, 50);
+    assert_eq!(file.configuration.seed, Some(7));
+    assert_eq!(file.configuration.run_name, "test");
+}
+
+#[test]
+fn string_overrides_keep_their_type() {
+    let file = ConfigurationFile::from_toml_str(&node_genes_toml(""), ["run_name=123", "run_save_directory=\"saves/\""]).unwrap();
+
+    assert_eq!(file.configuration.run_name, "123");
+    assert_eq!(file.configuration.run_save_directory, "saves/");
+}
+
+#[test]
+fn trainer_gets_the_config_regulators_of_the_file() {
+    let file = ConfigurationFile::from_toml_str(&node_genes_toml(""), ["config_regulators=[]"]).unwrap();
+    let trainer: NeatTrainer = NeatTrainer::from_configuration_file(file).unwrap();
+
+    assert!(trainer.config_regulators.is_empty());
+    assert!(!ConfigurationDefaults::get_default_regulators(&trainer.configuration).is_empty());
+}
+
+#[test]
+fn unknown_keys_are_rejected() {
+    let from_file = ConfigurationFile::from_toml_str(&node_genes_toml("populaton_size = 123"), Vec::<String>::new());
+    let from_override = ConfigurationFile::from_toml_str(&node_genes_toml(""), ["populaton_size=5"]);
+
+    assert_eq!(from_file.err(), Some(ConfigError::UnknownKey("populaton_size".to_string())));
+    assert_eq!(from_override.err(), Some(ConfigError::UnknownKey("populaton_size".to_string())));
+}
+
+#[test]
+fn loaded_configuration_is_validated() {
+    let result = ConfigurationFile::from_toml_str(&node_genes_toml("survival_threshold = 2.0"), Vec::<String>::new());
+
+    assert!(matches!(result, Err(ConfigError::ProbabilityOutOfRange { property: "survival_threshold", .. })));
+}
+
+#[cfg(test)]
+fn node_genes_toml(values: &str) -> String {
+    let mut node_genes = String::new();
+    for (number, kind) in [(1, "Sensor"), (2, "Sensor"), (3, "Output")]{
+        node_genes.push_str(&format!("[[node_genes]]\nnumber = {}\nkind = \"{}\"\nbias = 1.0\ninput_multiplier = 1.0\nactivation_function = {{ bits = 0 }}\n", number, kind));
+    }
+    format!("{}\n{}", values, node_genes)
+}
@@ -0,0 +1,45 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use crate::common::NeatFloat;
+use crate::neat::{genome::genome::Genome, population::GenerationMember};
+use crate::neat::trainer::run_context::RunContext;
+
+/* decides when a curriculum moves on from a stage, checked after every evaluated generation */
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AdvancementRule{
+    /* the best member seen during the stage reaches the fitness */
+    BestFitness(NeatFloat),
+    /* the average over species of the average member fitness of the last generation reaches the fitness */
+    AverageSpeciesFitness(NeatFloat),
+    /* the stage has run for this many generations */
+    Generations(u32)
+}
+
+impl AdvancementRule{
+    pub fn is_met<T>(&self, members: &[GenerationMember<T>], run_context: &RunContext<T>) -> bool where T: Genome{
+        match self {
+            AdvancementRule::BestFitness(fitness) => run_context.best_member_so_far.as_ref().and_then(|m| m.genome.get_fitness()).is_some_and(|f| f >= *fitness),
+            AdvancementRule::AverageSpeciesFitness(fitness) => Self::get_average_species_fitness(members).is_some_and(|f| f >= *fitness),
+            AdvancementRule::Generations(generations) => {
+                let started_generation = run_context.curriculum_stage.as_ref().map(|c| c.started_generation).unwrap_or_default();
+                run_context.current_generation.saturating_sub(started_generation) >= *generations
+            }
+        }
+    }
+    /* members are grouped by the species they were produced in, species metrics are only updated on the next speciation */
+    fn get_average_species_fitness<T>(members: &[GenerationMember<T>]) -> Option<NeatFloat> where T: Genome{
+        let mut species_fitness: BTreeMap<uuid::Uuid, (NeatFloat, usize)> = BTreeMap::new();
+        for member in members{
+            if let Some(fitness) = member.genome.get_fitness(){
+                let (sum, count) = species_fitness.entry(member.species_hint).or_insert((0.0, 0));
+                *sum += fitness;
+                *count += 1;
+            }
+        }
+        if species_fitness.is_empty(){
+            return None;
+        }
+        Some(species_fitness.values().map(|(sum, count)| sum / *count as NeatFloat).sum::<NeatFloat>() / species_fitness.len() as NeatFloat)
+    }
+}
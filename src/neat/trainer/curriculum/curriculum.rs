@@ -0,0 +1,181 @@
+use std::time::Instant;
+use serde::Serialize;
+use crate::common::{NeatFloat, network_definition::NetworkDefinition};
+use crate::phenome::Phenome;
+use crate::neat::genome::{genome::Genome, crossover::Crossover, mutation_history::MutationHistory};
+use crate::neat::trainer::{neat_trainer::NeatTrainer, run_report::RunReport, config_error::ConfigError, neat_trainer_host::from_host_events::FromHostEvent};
+use crate::neat::trainer::stop_criteria::{StopCriteria, StopReason};
+use crate::neat::trainer::fitness::fitness_resolver::FitnessResolver;
+use super::{curriculum_stage::CurriculumStage, curriculum_progress::CurriculumProgress, advancement_rule::AdvancementRule};
+
+/*
+Trains one population through a list of named stages, each with its own fitness function. When the
+advancement rule of a stage is met the population carries over to the next stage and is re-evaluated
+with its fitness function, fitness kept from the earlier stage (best member, seed bank, cache, species
+metrics) is dropped as it was measured on a different task. The run completes when the rule of the
+last stage is met. The success threshold only applies to the last stage.
+*/
+pub struct Curriculum<'a>{
+    pub stages: Vec<CurriculumStage<'a>>
+}
+
+impl<'a> Curriculum<'a>{
+    pub fn new() -> Self{
+        Self { stages: Vec::new() }
+    }
+    pub fn stage<F>(mut self, name: &str, fitness_fn: F, advancement_rule: AdvancementRule) -> Self where F: Fn(&dyn Phenome, &mut FitnessResolver) + Sync + 'a{
+        self.stages.push(CurriculumStage::new(name, fitness_fn, advancement_rule));
+        self
+    }
+    /* the stage recorded in the run context, looked up by name so stages can be inserted before resuming */
+    pub fn get_stage_index(&self, progress: Option<&CurriculumProgress>) -> Result<Option<usize>, ConfigError>{
+        let Some(progress) = progress else {
+            return Ok(None);
+        };
+        match self.stages.iter().position(|s| s.name == progress.name) {
+            Some(stage_index) => Ok(Some(stage_index)),
+            None => Err(ConfigError::UnknownCurriculumStage(progress.name.clone()))
+        }
+    }
+    pub fn run<T>(&self, trainer: &mut NeatTrainer<T>, stop_criteria: StopCriteria) -> Result<RunReport<T>, ConfigError> where T: Genome + Crossover + MutationHistory + NetworkDefinition + Serialize{
+        assert!(!self.stages.is_empty(), "a curriculum needs at least one stage");
+        let started = Instant::now();
+        let start_generation = trainer.get_current_generation();
+        let previous_success_threshold_override = trainer.get_success_threshold_override();
+        let success_threshold = stop_criteria.success_threshold.unwrap_or(trainer.get_success_threshold());
+
+        let mut stage_index = match self.get_stage_index(trainer.run_context.curriculum_stage.as_ref())? {
+            Some(stage_index) => stage_index,
+            None => {
+                self.start_stage(trainer, 0);
+                0
+            }
+        };
+        self.set_success_threshold(trainer, stage_index, success_threshold);
+
+        let mut best_fitness: Option<NeatFloat> = None;
+        let mut last_improved_generation = start_generation;
+
+        let stop_reason = loop {
+            trainer.new_generation();
+            trainer.evaluate_generation(&self.stages[stage_index].fitness_fn);
+
+            let current_generation = trainer.get_current_generation();
+            if self.stages[stage_index].advancement_rule.is_met(&trainer.members, &trainer.run_context){
+                if stage_index + 1 == self.stages.len(){
+                    break StopReason::CurriculumCompleted;
+                }
+                stage_index += 1;
+                self.start_stage(trainer, stage_index);
+                self.set_success_threshold(trainer, stage_index, success_threshold);
+                trainer.evaluate_generation(&self.stages[stage_index].fitness_fn);
+                best_fitness = None;
+                last_improved_generation = current_generation;
+            }
+
+            if let Err(error) = trainer.checkpoint_if_due(){
+                trainer.send_event(FromHostEvent::CheckpointError(error.to_string()));
+            }
+
+            let best_fitness_so_far = trainer.run_context.best_member_so_far.as_ref().and_then(|m| m.genome.get_fitness());
+            if best_fitness_so_far.is_some() && (best_fitness.is_none() || best_fitness_so_far.unwrap() > best_fitness.unwrap()){
+                best_fitness = best_fitness_so_far;
+                last_improved_generation = current_generation;
+            }
+
+            if trainer.has_met_success(){
+                break StopReason::SuccessThreshold;
+            }
+            if let Some(stop_reason) = stop_criteria.get_stop_reason(current_generation - start_generation, started.elapsed(), current_generation - last_improved_generation){
+                break stop_reason;
+            }
+        };
+        trainer.set_success_threshold_override(previous_success_threshold_override);
+
+        Ok(RunReport {
+            champion: trainer.run_context.best_member_so_far.clone(),
+            stop_reason,
+            generations: trainer.get_current_generation() - start_generation,
+            elapsed: started.elapsed(),
+            generation_stats: trainer.run_context.last_ten_thousand_generations_stats.clone()
+        })
+    }
+    fn start_stage<T>(&self, trainer: &mut NeatTrainer<T>, stage_index: usize) where T: Genome + Crossover + MutationHistory{
+        let name = self.stages[stage_index].name.clone();
+        trainer.send_event(FromHostEvent::CurriculumStageChange(stage_index, name.clone()));
+        let current_generation = trainer.get_current_generation();
+        trainer.run_context.start_curriculum_stage(CurriculumProgress::new(stage_index, name, current_generation));
+    }
+    /* new_generation stops once the success threshold is met, the override keeps the threshold of earlier stages out of saved runs */
+    fn set_success_threshold<T>(&self, trainer: &mut NeatTrainer<T>, stage_index: usize, success_threshold: NeatFloat) where T: Genome + Crossover + MutationHistory{
+        trainer.set_success_threshold_override(Some(if stage_index + 1 == self.stages.len() { success_threshold } else { NeatFloat::MAX }));
+    }
+}
+
+impl Default for Curriculum<'_>{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+fn curriculum_advances_through_its_stages_without_resetting_the_population() {
+    use crate::neat::trainer::test_fixtures::{xor_configuration, xor_fitness};
+
+    let configuration = xor_configuration(41);
+    let curriculum = Curriculum::new()
+        .stage("constant", |_: &dyn Phenome, fitness_resolver: &mut FitnessResolver| fitness_resolver.add_reward(0, 1.0), AdvancementRule::BestFitness(1.0))
+        .stage("xor", xor_fitness, AdvancementRule::Generations(5));
+
+    let mut neat_trainer = NeatTrainer::new(configuration);
+    let report = curriculum.run(&mut neat_trainer, StopCriteria::new().max_generations(Some(50))).unwrap();
+
+    assert_eq!(report.stop_reason, StopReason::CurriculumCompleted);
+    assert_eq!(report.generations, 6);
+    let progress = neat_trainer.run_context.curriculum_stage.clone().unwrap();
+    assert_eq!((progress.stage, progress.name.as_str(), progress.started_generation), (1, "xor", 1));
+    assert!(report.champion_fitness().unwrap() < 4.0 && report.champion_fitness().unwrap() != 1.0);
+    assert!(report.generation_stats.iter().any(|s| s.curriculum_stage == Some(1)));
+    assert_eq!(neat_trainer.configuration.success_threshold, 4.0);
+
+    /* a resumed run carries on from the recorded stage */
+    let resumed = curriculum.run(&mut neat_trainer, StopCriteria::new().max_generations(Some(50))).unwrap();
+    assert_eq!(resumed.generations, 1);
+    assert_eq!(neat_trainer.run_context.curriculum_stage.unwrap().started_generation, 1);
+}
+
+#[test]
+fn curriculum_keeps_stage_thresholds_out_of_checkpoints_and_reports_stages_as_events() {
+    use crate::neat::trainer::{checkpoint::Checkpoint, test_fixtures::{xor_configuration, xor_fitness}};
+
+    let directory = std::env::temp_dir().join(format!("neatlib-curriculum-checkpoints-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    let configuration = xor_configuration(43)
+        .run_name("curriculum_test".to_string())
+        .run_save_directory(format!("{}/", directory.display()))
+        .checkpoint_every_generations(Some(1));
+    let curriculum = Curriculum::new()
+        .stage("constant", |_: &dyn Phenome, fitness_resolver: &mut FitnessResolver| fitness_resolver.add_reward(0, 1.0), AdvancementRule::Generations(2))
+        .stage("xor", xor_fitness, AdvancementRule::Generations(2));
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut neat_trainer = NeatTrainer::new(configuration);
+    neat_trainer.event_sender = Some(sender);
+
+    curriculum.run(&mut neat_trainer, StopCriteria::new().max_generations(Some(50))).unwrap();
+
+    let stages: Vec<(usize, String)> = receiver.try_iter().filter_map(|e| match e { FromHostEvent::CurriculumStageChange(stage, name) => Some((stage, name)), _ => None }).collect();
+    assert_eq!(stages, vec![(0, "constant".to_string()), (1, "xor".to_string())]);
+    let checkpoints = Checkpoint::list(&directory, Some("curriculum_test")).unwrap();
+    assert!(!checkpoints.is_empty());
+    for path in checkpoints{
+        let saved: NeatTrainer = Checkpoint::read(&path).unwrap();
+        assert_eq!(saved.configuration.success_threshold, 4.0);
+    }
+    assert_eq!(neat_trainer.get_success_threshold_override(), None);
+    let _ = std::fs::remove_dir_all(&directory);
+
+    /* a run on a stage that was renamed since cannot tell which stage to carry on from */
+    neat_trainer.run_context.curriculum_stage = Some(CurriculumProgress::new(0, "renamed".to_string(), 0));
+    let error = curriculum.run(&mut neat_trainer, StopCriteria::new().max_generations(Some(1))).err();
+    assert_eq!(error, Some(ConfigError::UnknownCurriculumStage("renamed".to_string())));
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/* the stage of a curriculum a run is on, kept in the run context so a resumed run carries on from the same stage */
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurriculumProgress{
+    pub stage: usize,
+    pub name: String,
+    pub started_generation: u32
+}
+
+impl CurriculumProgress{
+    pub fn new(stage: usize, name: String, started_generation: u32) -> Self{
+        Self { stage, name, started_generation }
+    }
+}
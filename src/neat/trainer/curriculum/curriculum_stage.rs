@@ -0,0 +1,21 @@
+use crate::phenome::Phenome;
+use crate::neat::trainer::fitness::fitness_resolver::FitnessResolver;
+use super::advancement_rule::AdvancementRule;
+
+pub type StageFitness<'a> = Box<dyn Fn(&dyn Phenome, &mut FitnessResolver) + Sync + 'a>;
+
+pub struct CurriculumStage<'a>{
+    pub name: String,
+    pub fitness_fn: StageFitness<'a>,
+    pub advancement_rule: AdvancementRule
+}
+
+impl<'a> CurriculumStage<'a>{
+    pub fn new<F>(name: &str, fitness_fn: F, advancement_rule: AdvancementRule) -> Self where F: Fn(&dyn Phenome, &mut FitnessResolver) + Sync + 'a{
+        Self {
+            name: name.to_string(),
+            fitness_fn: Box::new(fitness_fn),
+            advancement_rule
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod curriculum;
+pub mod curriculum_stage;
+pub mod curriculum_progress;
+pub mod advancement_rule;
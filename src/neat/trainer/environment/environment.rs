@@ -0,0 +1,14 @@
+use crate::common::NeatFloat;
+
+/*
+A control task evaluated in episodes. The evaluator resets the environment, feeds the observation to the
+network, steps with its outputs as actions and adds up the rewards until the environment is done.
+*/
+pub trait Environment{
+    /* starts a new episode, the seed comes from the member's random stream so evaluations are reproducible */
+    fn reset(&mut self, seed: u64);
+    fn observe(&self) -> Vec<NeatFloat>;
+    /* applies the actions and returns the reward for the step */
+    fn step(&mut self, actions: &[NeatFloat]) -> NeatFloat;
+    fn is_done(&self) -> bool;
+}
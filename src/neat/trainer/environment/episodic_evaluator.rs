@@ -0,0 +1,159 @@
+use crate::common::NeatFloat;
+use crate::phenome::Phenome;
+use crate::neat::trainer::fitness::fitness_resolver::FitnessResolver;
+use super::environment::Environment;
+
+/*
+Runs a number of episodes of an environment per genome and adds the average episode reward to the
+fitness. Every evaluation creates its own environment, so genomes can be evaluated in parallel. The
+network state is kept through an episode for recurrent connections and cleared between episodes.
+Steps count against the step budget and an episode ends early once the fitness resolver should stop.
+*/
+pub struct EpisodicEvaluator<F>{
+    create_environment: F,
+    pub episodes: u32,
+    pub max_steps_per_episode: u32
+}
+
+impl<F, E> EpisodicEvaluator<F> where F: Fn() -> E + Sync, E: Environment{
+    pub fn new(create_environment: F) -> Self{
+        Self {
+            create_environment,
+            episodes: 1,
+            max_steps_per_episode: 1000
+        }
+    }
+    pub fn episodes(mut self, value: u32) -> Self{
+        self.episodes = value;
+        self
+    }
+    pub fn max_steps_per_episode(mut self, value: u32) -> Self{
+        self.max_steps_per_episode = value;
+        self
+    }
+    /* has the signature of a fitness function, so it can be passed as `&|p, r| evaluator.evaluate(p, r)` */
+    pub fn evaluate(&self, phenome: &dyn Phenome, fitness_resolver: &mut FitnessResolver){
+        let mut environment = (self.create_environment)();
+        let mut total_reward = 0.0;
+        for _ in 0..self.episodes{
+            let seed = fitness_resolver.random().gen_u64();
+            total_reward += self.run_episode(phenome, &mut environment, seed, fitness_resolver);
+        }
+        fitness_resolver.add_reward(0, total_reward / self.episodes.max(1) as NeatFloat);
+    }
+    /* returns the sum of the rewards of the episode */
+    pub fn run_episode(&self, phenome: &dyn Phenome, environment: &mut E, seed: u64, fitness_resolver: &mut FitnessResolver) -> NeatFloat{
+        environment.reset(seed);
+        let mut state = Vec::new();
+        let mut episode_reward = 0.0;
+        for _ in 0..self.max_steps_per_episode{
+            if environment.is_done() || fitness_resolver.should_stop(){
+                break;
+            }
+            let actions = phenome.activate_with_state(&environment.observe(), &mut state);
+            episode_reward += environment.step(&actions);
+            fitness_resolver.add_steps(1);
+        }
+        episode_reward
+    }
+}
+
+#[cfg(test)]
+struct Corridor{
+    position: NeatFloat,
+    target: NeatFloat
+}
+#[cfg(test)]
+impl Environment for Corridor{
+    fn reset(&mut self, seed: u64){
+        self.position = 0.0;
+        self.target = (seed % 5 + 1) as NeatFloat;
+    }
+    fn observe(&self) -> Vec<NeatFloat>{
+        vec![self.target - self.position]
+    }
+    fn step(&mut self, actions: &[NeatFloat]) -> NeatFloat{
+        self.position += actions[0];
+        1.0
+    }
+    fn is_done(&self) -> bool{
+        self.position >= self.target
+    }
+}
+#[cfg(test)]
+struct StepForward;
+#[cfg(test)]
+impl Phenome for StepForward{
+    fn activate(&self, _sensor_values: &Vec<NeatFloat>) -> Vec<NeatFloat>{
+        vec![1.0]
+    }
+    fn get_id(&self) -> uuid::Uuid{
+        uuid::Uuid::nil()
+    }
+}
+
+#[test]
+fn episodes_add_the_average_reward() {
+    use crate::neat::trainer::fitness::novelty_component_store::NoveltyComponentStore;
+    use crate::common::seeded_random::SeededRandom;
+
+    let evaluator = EpisodicEvaluator::new(|| Corridor { position: 0.0, target: 0.0 }).episodes(4);
+    let novelty_component_store = NoveltyComponentStore::new();
+    let mut fitness_resolver = FitnessResolver::new(&novelty_component_store).with_random(SeededRandom::new(Some(3)));
+    let mut random = SeededRandom::new(Some(3));
+    let expected = (0..4).map(|_| (random.gen_u64() % 5 + 1) as NeatFloat).sum::<NeatFloat>() / 4.0;
+
+    evaluator.evaluate(&StepForward, &mut fitness_resolver);
+
+    assert_eq!(fitness_resolver.compute().objective_fitness, expected);
+    assert_eq!(fitness_resolver.get_steps() as NeatFloat, expected * 4.0);
+
+    let capped = EpisodicEvaluator::new(|| Corridor { position: 0.0, target: 0.0 }).max_steps_per_episode(1);
+    let mut fitness_resolver = FitnessResolver::new(&novelty_component_store);
+    capped.evaluate(&StepForward, &mut fitness_resolver);
+    assert_eq!(fitness_resolver.compute().objective_fitness, 1.0);
+}
+
+#[test]
+fn episodic_evaluator_runs_environments_for_every_member() {
+    use crate::neat::genome::genome::Genome;
+    use crate::neat::trainer::{neat_trainer::NeatTrainer, test_fixtures::xor_configuration};
+
+    /* keep the pole upright, every step it stays within bounds is rewarded */
+    struct Pole{
+        angle: NeatFloat
+    }
+    impl Environment for Pole{
+        fn reset(&mut self, seed: u64){
+            self.angle = (seed % 100) as NeatFloat / 200.0 - 0.25;
+        }
+        fn observe(&self) -> Vec<NeatFloat>{
+            vec![self.angle, -self.angle]
+        }
+        fn step(&mut self, actions: &[NeatFloat]) -> NeatFloat{
+            self.angle += self.angle * 0.1 + (actions[0] - 0.5) * 0.2;
+            1.0
+        }
+        fn is_done(&self) -> bool{
+            self.angle.abs() > 1.0
+        }
+    }
+
+    let configuration = xor_configuration(59).success_threshold(100.0);
+    let evaluator = EpisodicEvaluator::new(|| Pole { angle: 0.0 }).episodes(3).max_steps_per_episode(50);
+    let fitness = |phenome: &dyn Phenome, fitness_resolver: &mut FitnessResolver| evaluator.evaluate(phenome, fitness_resolver);
+
+    let mut neat_trainer = NeatTrainer::new(configuration.clone());
+    let mut repeated_neat_trainer = NeatTrainer::new(configuration);
+    for _ in 0..5{
+        neat_trainer.new_generation();
+        neat_trainer.evaluate_generation(&fitness);
+        repeated_neat_trainer.new_generation();
+        repeated_neat_trainer.evaluate_generation(&fitness);
+    }
+
+    assert!(neat_trainer.members.iter().all(|m| m.genome.get_fitness().is_some_and(|f| (1.0..=50.0).contains(&f))));
+    let fitnesses: Vec<Option<NeatFloat>> = neat_trainer.members.iter().map(|m| m.genome.get_fitness()).collect();
+    let repeated_fitnesses: Vec<Option<NeatFloat>> = repeated_neat_trainer.members.iter().map(|m| m.genome.get_fitness()).collect();
+    assert_eq!(fitnesses, repeated_fitnesses);
+}
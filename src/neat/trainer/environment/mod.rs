@@ -0,0 +1,2 @@
+pub mod environment;
+pub mod episodic_evaluator;
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+use crate::common::NeatFloat;
+
+/* what one objective fitness component, usually one test case, added to the objective fitness */
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComponentScore{
+    pub component_id: u32,
+    pub score: NeatFloat
+}
+
+impl ComponentScore{
+    pub fn new(component_id: u32, score: NeatFloat) -> Self{
+        Self {
+            component_id,
+            score
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+/* how long each genome evaluated in the last generation took, the repeated evaluations of a genome are added together */
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EvaluationTimes{
+    pub count: usize,
+    pub over_budget: usize,
+    pub mean: Duration,
+    pub median: Duration,
+    pub p90: Duration,
+    pub max: Duration
+}
+
+impl EvaluationTimes{
+    pub fn new(durations: &[Duration], over_budget: usize) -> Self{
+        if durations.is_empty(){
+            return Self { over_budget, ..Self::default() };
+        }
+        let mut sorted = durations.to_vec();
+        sorted.sort();
+        let percentile = |fraction: f64| sorted[((sorted.len() - 1) as f64 * fraction).round() as usize];
+        Self {
+            count: sorted.len(),
+            over_budget,
+            mean: sorted.iter().sum::<Duration>() / sorted.len() as u32,
+            median: percentile(0.5),
+            p90: percentile(0.9),
+            max: sorted[sorted.len() - 1]
+        }
+    }
+}
+
+#[test]
+fn evaluation_times_summarise_the_durations() {
+    let durations: Vec<Duration> = (1..=10).map(Duration::from_millis).collect();
+
+    let times = EvaluationTimes::new(&durations, 2);
+
+    assert_eq!(times.count, 10);
+    assert_eq!(times.over_budget, 2);
+    assert_eq!(times.mean, Duration::from_micros(5500));
+    assert_eq!(times.median, Duration::from_millis(6));
+    assert_eq!(times.p90, Duration::from_millis(9));
+    assert_eq!(times.max, Duration::from_millis(10));
+    assert_eq!(EvaluationTimes::new(&[], 0), EvaluationTimes::default());
+}
@@ -1,30 +1,133 @@
+use std::time::Duration;
 use serde::{Serialize, Deserialize};
 
 use crate::common::NeatFloat;
 
-use super::number_line::ComponentNoveltyQuantizedValue;
+use super::{number_line::ComponentNoveltyQuantizedValue, fitness_aggregation::FitnessAggregation, objective::Objective, component_score::ComponentScore, fitness_failure::FitnessFailure};
 
 #[derive(Serialize, Deserialize)]
 #[derive(Clone, Default, Debug)]
 pub struct Fitness{
     pub objective_fitness: NeatFloat,
     pub outcome_novelty: NeatFloat,
-    pub outcome_novelty_quantized_values: Option<Vec<ComponentNoveltyQuantizedValue>>
+    pub outcome_novelty_quantized_values: Option<Vec<ComponentNoveltyQuantizedValue>>,
+    #[serde(default)]
+    pub objective_fitness_variance: NeatFloat,
+    #[serde(default)]
+    pub objectives: Vec<Objective>,
+    #[serde(default)]
+    pub component_scores: Vec<ComponentScore>,
+    #[serde(default)]
+    pub failure: Option<FitnessFailure>,
+    #[serde(default)]
+    pub evaluation_duration: Duration
 }
 impl Fitness{
     pub fn objective(fitness: NeatFloat) -> Self{
         Self {
             objective_fitness: fitness,
             outcome_novelty: 0.0,
-            outcome_novelty_quantized_values: None
+            outcome_novelty_quantized_values: None,
+            objective_fitness_variance: 0.0,
+            objectives: Vec::new(),
+            component_scores: Vec::new(),
+            failure: None,
+            evaluation_duration: Duration::ZERO
         }
     }
+    pub fn failed(failure: FitnessFailure) -> Self{
+        let mut fitness = Self::objective(0.0);
+        fitness.failure = Some(failure);
+        fitness
+    }
     pub fn average_fitness(fitness: Vec<Fitness>) -> Self{
         let x = fitness.iter().flat_map(|f| f.outcome_novelty_quantized_values.as_ref().unwrap_or(&vec![]).clone()).collect::<Vec<ComponentNoveltyQuantizedValue>>();
         Self {
             objective_fitness: fitness.iter().map(|f| f.objective_fitness).sum::<NeatFloat>() / fitness.len() as NeatFloat,
             outcome_novelty: fitness.iter().map(|f| f.outcome_novelty).sum::<NeatFloat>() / fitness.len() as NeatFloat,
-            outcome_novelty_quantized_values: Some(x)
+            outcome_novelty_quantized_values: Some(x),
+            objective_fitness_variance: 0.0,
+            objectives: Self::average_objectives(&fitness),
+            component_scores: Self::average_component_scores(&fitness),
+            failure: fitness.iter().find_map(|f| f.failure.clone()),
+            evaluation_duration: fitness.iter().map(|f| f.evaluation_duration).sum()
         }
     }
+    fn average_objectives(fitness: &[Fitness]) -> Vec<Objective>{
+        let mut objectives: Vec<Objective> = Vec::new();
+        for objective in fitness.iter().flat_map(|f| f.objectives.iter()){
+            match objectives.iter_mut().find(|o| o.name == objective.name) {
+                Some(total) => total.value += objective.value,
+                None => objectives.push(objective.clone())
+            }
+        }
+        for objective in objectives.iter_mut(){
+            objective.value /= fitness.len() as NeatFloat;
+        }
+        objectives
+    }
+    fn average_component_scores(fitness: &[Fitness]) -> Vec<ComponentScore>{
+        let mut component_scores: Vec<ComponentScore> = Vec::new();
+        for component_score in fitness.iter().flat_map(|f| f.component_scores.iter()){
+            match component_scores.iter_mut().find(|s| s.component_id == component_score.component_id) {
+                Some(total) => total.score += component_score.score,
+                None => component_scores.push(*component_score)
+            }
+        }
+        for component_score in component_scores.iter_mut(){
+            component_score.score /= fitness.len() as NeatFloat;
+        }
+        component_scores
+    }
+    /* combines several evaluations of the same genome, novelty, named objectives and component scores are always averaged */
+    pub fn aggregate(fitnesses: Vec<Fitness>, aggregation: FitnessAggregation) -> Self{
+        /* a single failed evaluation fails the genome, the values of the others are not trusted either */
+        if let Some(failed) = fitnesses.iter().find(|f| f.failure.is_some()){
+            let mut failed = failed.clone();
+            failed.evaluation_duration = fitnesses.iter().map(|f| f.evaluation_duration).sum();
+            return failed;
+        }
+        if fitnesses.len() == 1 {
+            return fitnesses.into_iter().next().unwrap();
+        }
+        let mut objective_fitnesses = fitnesses.iter().map(|f| f.objective_fitness).collect::<Vec<NeatFloat>>();
+        objective_fitnesses.sort_by(|a, b| a.total_cmp(b));
+        let count = objective_fitnesses.len() as NeatFloat;
+        let mean = objective_fitnesses.iter().sum::<NeatFloat>() / count;
+
+        let mut fitness = Self::average_fitness(fitnesses);
+        fitness.objective_fitness = match aggregation {
+            FitnessAggregation::Mean => mean,
+            FitnessAggregation::Median => {
+                let middle = objective_fitnesses.len() / 2;
+                if objective_fitnesses.len() % 2 == 0 {
+                    (objective_fitnesses[middle - 1] + objective_fitnesses[middle]) / 2.0
+                }else{
+                    objective_fitnesses[middle]
+                }
+            },
+            FitnessAggregation::Min => objective_fitnesses[0]
+        };
+        fitness.objective_fitness_variance = objective_fitnesses.iter().map(|f| (f - mean) * (f - mean)).sum::<NeatFloat>() / count;
+        fitness
+    }
+}
+
+#[test]
+fn aggregate_combines_objective_fitness() {
+    let fitnesses = vec![Fitness::objective(1.0), Fitness::objective(5.0), Fitness::objective(3.0)];
+
+    assert_eq!(Fitness::aggregate(fitnesses.clone(), FitnessAggregation::Mean).objective_fitness, 3.0);
+    assert_eq!(Fitness::aggregate(fitnesses.clone(), FitnessAggregation::Median).objective_fitness, 3.0);
+    let min = Fitness::aggregate(fitnesses, FitnessAggregation::Min);
+    assert_eq!(min.objective_fitness, 1.0);
+    assert!((min.objective_fitness_variance - 8.0 / 3.0).abs() < 0.0001);
+}
+
+#[test]
+fn aggregate_does_not_panic_on_a_fitness_that_is_not_a_number() {
+    let fitnesses = vec![Fitness::objective(1.0), Fitness::objective(NeatFloat::NAN), Fitness::objective(3.0)];
+
+    assert_eq!(Fitness::aggregate(fitnesses.clone(), FitnessAggregation::Min).objective_fitness, 1.0);
+    assert!(Fitness::aggregate(fitnesses, FitnessAggregation::Mean).objective_fitness.is_nan());
 }
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+/* how the objective fitness of several evaluations of the same genome is combined */
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, EnumIter)]
+pub enum FitnessAggregation{
+    #[default]
+    Mean,
+    Median,
+    /* the worst evaluation, for when a lucky evaluation must not be rewarded */
+    Min
+}
@@ -0,0 +1,132 @@
+use hashbrown::{HashMap, HashSet};
+use nohash_hasher::BuildNoHashHasher;
+use serde::{Deserialize, Serialize};
+use crate::common::NeatFloat;
+use super::Fitness;
+
+/*
+Fitnesses of the last evaluated generation by structure hash, so elites, members added back in and clones
+are not evaluated again. Only used when configuration.fitness_cache_enabled is set and every genome is evaluated
+once, as it assumes the fitness function always gives the same result for the same network. Re-tested elites
+replace the fitness of their structure. The outcome novelty of a cached fitness is
+recomputed from its quantized values on every hit.
+*/
+#[derive(Debug, Clone, Default)]
+#[derive(Serialize, Deserialize)]
+pub struct FitnessCache{
+    fitnesses: HashMap<u64, Fitness, BuildNoHashHasher<u64>>,
+    pub hits: u64,
+    pub misses: u64
+}
+
+impl FitnessCache{
+    pub fn new() -> Self{
+        Self::default()
+    }
+    pub fn get(&self, structure_hash: u64) -> Option<&Fitness>{
+        self.fitnesses.get(&structure_hash)
+    }
+    pub fn contains(&self, structure_hash: u64) -> bool{
+        self.fitnesses.contains_key(&structure_hash)
+    }
+    pub fn insert(&mut self, structure_hash: u64, fitness: Fitness){
+        self.fitnesses.insert(structure_hash, fitness);
+    }
+    /* drops the fitnesses of networks that are no longer in the population */
+    pub fn retain(&mut self, structure_hashes: &[u64]){
+        let structure_hashes: HashSet<u64, BuildNoHashHasher<u64>> = structure_hashes.iter().copied().collect();
+        self.fitnesses.retain(|hash, _| structure_hashes.contains(hash));
+    }
+    pub fn len(&self) -> usize{
+        self.fitnesses.len()
+    }
+    pub fn is_empty(&self) -> bool{
+        self.fitnesses.is_empty()
+    }
+    pub fn get_hit_rate(&self) -> NeatFloat{
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            return 0.0;
+        }
+        self.hits as NeatFloat / lookups as NeatFloat
+    }
+    pub fn clear(&mut self){
+        self.fitnesses.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+#[test]
+fn fitness_cache_skips_unchanged_genomes() {
+    use crate::neat::genome::genome::Genome;
+    use crate::neat::trainer::test_fixtures::{xor_configuration, run_counting_xor_generations};
+
+    let configuration = xor_configuration(13).population_size(100);
+
+    let (uncached_evaluations, _) = run_counting_xor_generations(configuration.clone().fitness_cache_enabled(false), 10);
+    let (cached_evaluations, neat_trainer) = run_counting_xor_generations(configuration, 10);
+
+    assert!(cached_evaluations < uncached_evaluations);
+    assert!(neat_trainer.run_context.fitness_cache.hits > 0);
+    assert_eq!(neat_trainer.run_context.fitness_cache.misses, cached_evaluations as u64);
+    assert!(neat_trainer.members.iter().all(|m| m.genome.get_fitness().is_some()));
+}
+
+#[test]
+fn cached_fitnesses_get_the_outcome_novelty_of_the_current_store() {
+    use crate::common::{NeatFloat, network_definition::NetworkDefinition};
+    use crate::neat::genome::genome::Genome;
+    use crate::neat::trainer::{neat_trainer::NeatTrainer, test_fixtures::{xor_configuration, xor_fitness}};
+
+    let configuration = xor_configuration(14);
+    let mut neat_trainer = NeatTrainer::new(configuration);
+    neat_trainer.new_generation();
+    neat_trainer.evaluate_generation(&xor_fitness);
+    let first_novelties: Vec<NeatFloat> = neat_trainer.members.iter().map(|m| m.genome.get_novelty()).collect();
+
+    /* every structure is cached now, but the store has seen the outcomes so they are less novel than the first time */
+    let novelty_component_store = neat_trainer.run_context.novelty_component_store.clone();
+    let misses = neat_trainer.run_context.fitness_cache.misses;
+    neat_trainer.evaluate_generation(&xor_fitness);
+
+    assert_eq!(neat_trainer.run_context.fitness_cache.misses, misses);
+    for member in neat_trainer.members.iter(){
+        let cached = neat_trainer.run_context.fitness_cache.get(member.genome.get_structure_hash()).unwrap();
+        let expected = novelty_component_store.get_outcome_novelty(cached.outcome_novelty_quantized_values.as_ref().unwrap());
+        assert_eq!(member.genome.get_novelty(), expected);
+    }
+    assert!(neat_trainer.members.iter().zip(first_novelties).any(|(m, first)| m.genome.get_novelty() != first));
+}
+
+#[test]
+fn failed_fitnesses_are_not_cached() {
+    use hashbrown::HashSet;
+    use crate::phenome::Phenome;
+    use crate::common::network_definition::NetworkDefinition;
+    use crate::neat::genome::genome::Genome;
+    use crate::neat::trainer::{neat_trainer::NeatTrainer, fitness::fitness_resolver::FitnessResolver, test_fixtures::xor_configuration};
+
+    let failing_fitness = |_: &dyn Phenome, fitness_resolver: &mut FitnessResolver| {
+        match fitness_resolver.random().gen_range_usize(0, 3) {
+            0 => panic!("fitness function failed"),
+            _ => fitness_resolver.add_reward(0, 1.0)
+        }
+    };
+    let mut neat_trainer = NeatTrainer::new(xor_configuration(15).fitness_invalid_penalty(-5.0));
+    neat_trainer.new_generation();
+    neat_trainer.evaluate_generation(&failing_fitness);
+
+    let fitness_cache = &neat_trainer.run_context.fitness_cache;
+    let failed_hashes: HashSet<u64> = neat_trainer.members.iter().filter(|m| m.genome.get_fitness() == Some(-5.0)).map(|m| m.genome.get_structure_hash()).collect();
+    assert!(!failed_hashes.is_empty());
+    for member in neat_trainer.members.iter(){
+        let cached = fitness_cache.get(member.genome.get_structure_hash());
+        assert_eq!(cached.is_none(), failed_hashes.contains(&member.genome.get_structure_hash()));
+    }
+
+    /* the failed structures get another chance on the next evaluation */
+    let misses = fitness_cache.misses;
+    neat_trainer.evaluate_generation(&failing_fitness);
+    assert_eq!(neat_trainer.run_context.fitness_cache.misses, misses + failed_hashes.len() as u64);
+}
@@ -0,0 +1,51 @@
+use std::{any::Any, fmt};
+use serde::{Deserialize, Serialize};
+use crate::common::NeatFloat;
+
+/* why the fitness of a member could not be used, reported with the genome id so the failure can be reproduced */
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum FitnessFailure{
+    Panicked(String),
+    NotANumber,
+    Infinite,
+    Subnormal,
+    OverBudget
+}
+
+impl FitnessFailure{
+    pub fn check(value: NeatFloat) -> Option<Self>{
+        if value.is_subnormal(){
+            return Some(FitnessFailure::Subnormal);
+        }
+        Self::check_finite(value)
+    }
+    pub fn check_finite(value: NeatFloat) -> Option<Self>{
+        if value.is_nan(){
+            Some(FitnessFailure::NotANumber)
+        }else if value.is_infinite(){
+            Some(FitnessFailure::Infinite)
+        }else{
+            None
+        }
+    }
+    pub fn from_panic(payload: Box<dyn Any + Send>) -> Self{
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => payload.downcast_ref::<&str>().map(|m| m.to_string()).unwrap_or_else(|| "unknown panic".to_string())
+        };
+        FitnessFailure::Panicked(message)
+    }
+}
+
+impl fmt::Display for FitnessFailure{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FitnessFailure::Panicked(message) => write!(f, "fitness function panicked: {}", message),
+            FitnessFailure::NotANumber => write!(f, "fitness is not a number"),
+            FitnessFailure::Infinite => write!(f, "fitness is infinite"),
+            FitnessFailure::Subnormal => write!(f, "fitness is subnormal"),
+            FitnessFailure::OverBudget => write!(f, "evaluation used up its time or step budget")
+        }
+    }
+}
@@ -1,12 +1,22 @@
-use crate::{common::{NeatFloat}};
-use super::{Fitness, novelty_component_store::{NoveltyComponentStore}, number_line::{ComponentNoveltyQuantizedValue}};
+use std::time::{Duration, Instant};
+use crate::{common::{NeatFloat, seeded_random::SeededRandom}};
+use super::{Fitness, fitness_failure::FitnessFailure, objective::Objective, component_score::ComponentScore, novelty_component_store::{NoveltyComponentStore}, number_line::{ComponentNoveltyQuantizedValue}};
 
 pub struct FitnessResolver<'a>{
     novelty_component_store: &'a NoveltyComponentStore,
     objective_fitness: NeatFloat,
     total_novelty: NeatFloat,
     novelty_component_count: u64,
-    outcome_novelty_quantized_values: Vec<ComponentNoveltyQuantizedValue>
+    outcome_novelty_quantized_values: Vec<ComponentNoveltyQuantizedValue>,
+    objectives: Vec<Objective>,
+    component_scores: Vec<ComponentScore>,
+    random: SeededRandom,
+    failure: Option<FitnessFailure>,
+    started: Instant,
+    time_budget: Option<Duration>,
+    step_budget: Option<u64>,
+    steps: u64,
+    stopped_early: bool
 }
 
 impl<'a> FitnessResolver<'a>{
@@ -16,19 +26,69 @@ impl<'a> FitnessResolver<'a>{
             objective_fitness: 0.0,
             total_novelty: 0.0,
             novelty_component_count: 0,
-            outcome_novelty_quantized_values: Vec::new()
+            outcome_novelty_quantized_values: Vec::new(),
+            objectives: Vec::new(),
+            component_scores: Vec::new(),
+            random: SeededRandom::new(None),
+            failure: None,
+            started: Instant::now(),
+            time_budget: None,
+            step_budget: None,
+            steps: 0,
+            stopped_early: false
         }
     }
+    pub fn with_budget(mut self, time_budget: Option<Duration>, step_budget: Option<u64>) -> Self{
+        self.time_budget = time_budget;
+        self.step_budget = step_budget;
+        self
+    }
+    pub fn with_random(mut self, random: SeededRandom) -> Self{
+        self.random = random;
+        self
+    }
+    pub fn random(&mut self) -> &mut SeededRandom{
+        &mut self.random
+    }
+    pub fn elapsed(&self) -> Duration{
+        self.started.elapsed()
+    }
+    /* counts simulation steps against configuration.fitness_step_budget */
+    pub fn add_steps(&mut self, steps: u64){
+        self.steps += steps;
+    }
+    pub fn get_steps(&self) -> u64{
+        self.steps
+    }
+    /*
+    The budget is cooperative, long running fitness functions are expected to check this and return early.
+    A member that was told to stop, or went past its budget without asking, gets configuration.fitness_over_budget_penalty as its fitness.
+    Using exactly the budget is allowed.
+    */
+    pub fn should_stop(&mut self) -> bool{
+        if self.time_budget.is_some_and(|budget| self.elapsed() >= budget) || self.step_budget.is_some_and(|budget| self.steps >= budget){
+            self.stopped_early = true;
+        }
+        self.stopped_early
+    }
+    fn is_over_budget(&self) -> bool{
+        self.stopped_early || self.time_budget.is_some_and(|budget| self.elapsed() > budget) || self.step_budget.is_some_and(|budget| self.steps > budget)
+    }
     pub fn add_objective_fitness_component(&mut self, component_id:u32, importance: NeatFloat, expected_value: NeatFloat, actual_value: NeatFloat){
-        if expected_value.is_subnormal(){
-            println!("expected_value is subnormal for component_id {}", component_id);
+        /* an input that is not finite fails the whole evaluation, it is handled by configuration.fitness_invalid_policy */
+        if let Some(failure) = FitnessFailure::check_finite(expected_value).or_else(|| FitnessFailure::check_finite(actual_value)){
+            self.failure.get_or_insert(failure);
             return;
         }
-        if actual_value.is_subnormal(){
-            println!("actual_value is subnormal for component_id {}", component_id);
-            return;
+        /* networks can output values too small to be normal, they are as good as zero */
+        let expected_value = if expected_value.is_subnormal() { 0.0 } else { expected_value };
+        let actual_value = if actual_value.is_subnormal() { 0.0 } else { actual_value };
+        let score = importance - (importance * NeatFloat::powi(NeatFloat::abs(expected_value - actual_value), 2));
+        self.objective_fitness += score;
+        match self.component_scores.iter_mut().find(|s| s.component_id == component_id) {
+            Some(component_score) => component_score.score += score,
+            None => self.component_scores.push(ComponentScore::new(component_id, score))
         }
-        self.objective_fitness += importance - (importance * NeatFloat::powi(NeatFloat::abs(expected_value - actual_value), 2));
     }
     pub fn add_objective_fitness_component_with_novelty(&mut self, component_id: u32, importance: NeatFloat, expected_value: NeatFloat, actual_value: NeatFloat, quantization_to_int_multiplier: i64){
         self.add_objective_fitness_component(component_id, importance, expected_value, actual_value);
@@ -40,9 +100,17 @@ impl<'a> FitnessResolver<'a>{
     pub fn add_punishment(&mut self, _component_id: u32, value: NeatFloat){
         self.objective_fitness -= value;
     }
+    /* kept apart for multi objective selection, the value is also added to the objective fitness */
+    pub fn add_objective(&mut self, name: &str, value: NeatFloat){
+        self.objective_fitness += value;
+        match self.objectives.iter_mut().find(|o| o.name == name) {
+            Some(objective) => objective.value += value,
+            None => self.objectives.push(Objective::new(name, value))
+        }
+    }
     
     pub fn add_novelty_component(&mut self, component_id: u32, actual_value: NeatFloat, quantization_to_int_multiplier: i64){
-        let quantized_value = (actual_value * quantization_to_int_multiplier as f32) as i64;
+        let quantized_value = (actual_value * quantization_to_int_multiplier as NeatFloat) as i64;
         let novelty = self.novelty_component_store.get_novelty_component_score(component_id, quantized_value) / 100.0;
         self.outcome_novelty_quantized_values.push(ComponentNoveltyQuantizedValue {component_id, quantized_value: quantized_value, count: 1 });
         self.total_novelty += novelty;
@@ -51,13 +119,76 @@ impl<'a> FitnessResolver<'a>{
     pub fn compute(&mut self) -> Fitness{
         let mut outcome_novelty = 0.0;
         if self.novelty_component_count > 0{
-            outcome_novelty = self.total_novelty / self.novelty_component_count as f32;
+            outcome_novelty = self.total_novelty / self.novelty_component_count as NeatFloat;
         }
 
+        let failure = Some(FitnessFailure::OverBudget).filter(|_| self.is_over_budget())
+            .or_else(|| self.failure.clone())
+            .or_else(|| FitnessFailure::check(self.objective_fitness))
+            .or_else(|| self.objectives.iter().find_map(|o| FitnessFailure::check(o.value)));
+
         Fitness{
             objective_fitness: self.objective_fitness,
             outcome_novelty: outcome_novelty,
-            outcome_novelty_quantized_values: Some(self.outcome_novelty_quantized_values.clone())
+            outcome_novelty_quantized_values: Some(self.outcome_novelty_quantized_values.clone()),
+            objective_fitness_variance: 0.0,
+            objectives: self.objectives.clone(),
+            component_scores: self.component_scores.clone(),
+            failure,
+            evaluation_duration: self.elapsed()
         }
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn members_over_their_step_budget_get_the_penalty() {
+    use crate::phenome::Phenome;
+    use crate::neat::genome::genome::Genome;
+    use crate::neat::trainer::{neat_trainer::NeatTrainer, test_fixtures::xor_configuration};
+
+    let configuration = xor_configuration(47)
+        .fitness_cache_enabled(false)
+        .fitness_step_budget(Some(100))
+        .fitness_over_budget_penalty(-2.0);
+    /* a third of the members never finish on their own and rely on the budget to stop */
+    let budgeted_fitness = |_: &dyn Phenome, fitness_resolver: &mut FitnessResolver| {
+        let steps_needed = if fitness_resolver.random().gen_range_usize(0, 3) == 0 { u64::MAX } else { 10 };
+        let mut steps = 0;
+        while steps < steps_needed && !fitness_resolver.should_stop(){
+            fitness_resolver.add_steps(1);
+            steps += 1;
+        }
+        fitness_resolver.add_reward(0, 1.0);
+    };
+
+    let mut neat_trainer = NeatTrainer::new(configuration);
+    neat_trainer.new_generation();
+    neat_trainer.evaluate_generation(&budgeted_fitness);
+
+    let over_budget = neat_trainer.members.iter().filter(|m| m.genome.get_fitness() == Some(-2.0)).count();
+    assert!(over_budget > 0);
+    assert!(neat_trainer.members.iter().all(|m| m.genome.get_fitness() == Some(-2.0) || m.genome.get_fitness() == Some(1.0)));
+    let evaluation_times = &neat_trainer.run_context.evaluation_times;
+    assert_eq!(evaluation_times.count, 50);
+    assert_eq!(evaluation_times.over_budget, over_budget);
+    assert!(evaluation_times.max >= evaluation_times.median);
+}
+
+#[test]
+fn using_exactly_the_step_budget_is_not_over_budget() {
+    let novelty_component_store = NoveltyComponentStore::new();
+
+    let mut fitness_resolver = FitnessResolver::new(&novelty_component_store).with_budget(None, Some(10));
+    fitness_resolver.add_steps(10);
+    assert_eq!(fitness_resolver.compute().failure, None);
+
+    let mut fitness_resolver = FitnessResolver::new(&novelty_component_store).with_budget(None, Some(10));
+    fitness_resolver.add_steps(11);
+    assert_eq!(fitness_resolver.compute().failure, Some(FitnessFailure::OverBudget));
+
+    /* asking after the last step and being told to stop means the evaluation was cut short */
+    let mut fitness_resolver = FitnessResolver::new(&novelty_component_store).with_budget(None, Some(10));
+    fitness_resolver.add_steps(10);
+    assert!(fitness_resolver.should_stop());
+    assert_eq!(fitness_resolver.compute().failure, Some(FitnessFailure::OverBudget));
+}
@@ -1,5 +1,7 @@
-use crate::{neat::{genome::genome::Genome, trainer::neat_trainer::NeatTrainer}};
-use super::Fitness;
+use hashbrown::{HashMap, HashSet};
+use nohash_hasher::BuildNoHashHasher;
+use crate::{neat::{genome::{genome::Genome, crossover::Crossover, mutation_history::MutationHistory}, trainer::{neat_trainer::NeatTrainer, neat_trainer_host::from_host_events::FromHostEvent}}};
+use super::{Fitness, fitness_failure::FitnessFailure, invalid_fitness_policy::InvalidFitnessPolicy};
 
 struct MemberFitness{
     pub id: uuid::Uuid,
@@ -8,34 +10,183 @@ struct MemberFitness{
 
 //The purpose of this struct is to centralize the logic of fitness setting.
 pub struct FitnessSetter{
-    fitnesses: Vec<MemberFitness>
+    fitnesses: Vec<MemberFitness>,
+    reported_failures: Vec<(uuid::Uuid, FitnessFailure)>
 }
 
 impl FitnessSetter{
     pub fn new() -> Self{
         Self{
-            fitnesses: Vec::new()
+            fitnesses: Vec::new(),
+            reported_failures: Vec::new()
         }
     }
     pub fn set_fitness(&mut self,id: uuid::Uuid, fitness:Fitness){
         self.fitnesses.push(MemberFitness{id, fitness});
     }
-    pub fn commit(&self, trainer: &mut NeatTrainer){
+    /* for failures that were already dealt with outside of the members, such as re-evaluated elites */
+    pub fn report_failure(&mut self, id: uuid::Uuid, failure: FitnessFailure){
+        self.reported_failures.push((id, failure));
+    }
+    pub fn get_failures(&self) -> Vec<(uuid::Uuid, FitnessFailure)>{
+        let member_failures = self.fitnesses.iter().filter_map(|m| m.fitness.failure.clone().map(|failure| (m.id, failure)));
+        self.reported_failures.iter().cloned().chain(member_failures).collect()
+    }
+    /* every failure is sent as an event, however the fitnesses were computed */
+    pub fn commit<T>(&self, trainer: &mut NeatTrainer<T>) where T: Genome + Crossover + MutationHistory {
+        let failures = self.get_failures();
+        for (id, failure) in failures.iter(){
+            trainer.send_event(FromHostEvent::FitnessFailure(*id, failure.clone()));
+        }
+
+        let policy = trainer.configuration.fitness_invalid_policy;
+        /* going over budget is expected of some members so it always gets its own penalty instead of the policy */
+        if policy == InvalidFitnessPolicy::Abort{
+            if let Some((id, failure)) = failures.iter().find(|(_, f)| *f != FitnessFailure::OverBudget){
+                panic!("{} for member {}", failure, id);
+            }
+        }
+
+        let penalty = Fitness::objective(trainer.configuration.fitness_invalid_penalty);
+        let over_budget_penalty = Fitness::objective(trainer.configuration.fitness_over_budget_penalty);
+        let mut discarded_ids = HashSet::new();
+        let mut fitnesses_by_id: HashMap<u64, &Fitness, BuildNoHashHasher<u64>> = HashMap::with_capacity_and_hasher(self.fitnesses.len(), BuildNoHashHasher::default());
         for member_fitness in &self.fitnesses{
+            let id = member_fitness.id.as_u64_pair().0;
+            if member_fitness.fitness.failure == Some(FitnessFailure::OverBudget){
+                fitnesses_by_id.insert(id, &over_budget_penalty);
+                continue;
+            }
+            if member_fitness.fitness.failure.is_some(){
+                match policy {
+                    InvalidFitnessPolicy::Penalty => { fitnesses_by_id.insert(id, &penalty); },
+                    InvalidFitnessPolicy::Discard => { discarded_ids.insert(member_fitness.id); },
+                    InvalidFitnessPolicy::Abort => unreachable!()
+                }
+                continue;
+            }
 
             if member_fitness.fitness.outcome_novelty_quantized_values.is_some(){
                 trainer.run_context.novelty_component_store.add_quantized_values(&member_fitness.fitness.outcome_novelty_quantized_values.as_ref().unwrap());
             }
+            fitnesses_by_id.insert(id, &member_fitness.fitness);
+        }
 
-            let member = trainer.members_map.get(&member_fitness.id.as_u64_pair().0);
-            if member.is_some(){
-                if member_fitness.fitness.objective_fitness.is_subnormal(){
-                    panic!("fitness is subnormal for member {}", member_fitness.id);
-                }
-                let member = &mut trainer.members[*member.unwrap()];
-                member.genome.set_objective_fitness(member_fitness.fitness.objective_fitness);
-                member.genome.set_novelty(member_fitness.fitness.outcome_novelty);
+        /* with nothing left speciation would start the run over, which hides a fitness function that always fails */
+        if !discarded_ids.is_empty() && trainer.members.iter().all(|m| discarded_ids.contains(&m.genome.get_id())){
+            panic!("every member failed its fitness evaluation, there is nothing left after discarding them");
+        }
+
+        /* preserved elites are copies with the same id, so every member with the id gets the fitness and none keep a stale one */
+        for member in trainer.members.iter_mut(){
+            if let Some(fitness) = fitnesses_by_id.get(&member.genome.get_id().as_u64_pair().0){
+                member.genome.set_objective_fitness(fitness.objective_fitness);
+                member.genome.set_novelty(fitness.outcome_novelty);
+                member.objective_fitness_variance = fitness.objective_fitness_variance;
+                member.objectives = fitness.objectives.clone();
+                member.component_scores = fitness.component_scores.clone();
+            }
+        }
+
+        if !discarded_ids.is_empty(){
+            trainer.members.retain(|m| !discarded_ids.contains(&m.genome.get_id()));
+            for species in trainer.run_context.species_index.values_mut(){
+                species.members.retain(|m| !discarded_ids.contains(&m.id));
+            }
+            trainer.members_map.clear();
+            for (index, member) in trainer.members.iter().enumerate(){
+                trainer.members_map.insert(member.genome.get_id().as_u64_pair().0, index);
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn failing_fitness_functions_are_isolated_per_member() {
+    use crate::phenome::Phenome;
+    use crate::common::NeatFloat;
+    use crate::neat::trainer::{fitness::fitness_resolver::FitnessResolver, test_fixtures::{xor_configuration, run_xor_generations}};
+    use crate::neat::trainer::neat_trainer_host::from_host_events::FromHostEvent;
+
+    /* the random stream of a member depends on its id, so the same members fail on every run with the seed */
+    let failing_fitness = |_: &dyn Phenome, fitness_resolver: &mut FitnessResolver| {
+        match fitness_resolver.random().gen_range_usize(0, 3) {
+            0 => panic!("fitness function failed"),
+            1 => fitness_resolver.add_reward(0, NeatFloat::NAN),
+            _ => fitness_resolver.add_reward(0, 1.0)
+        }
+    };
+    let configuration = xor_configuration(43)
+        .fitness_cache_enabled(false);
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut neat_trainer = NeatTrainer::new(configuration.clone().fitness_invalid_penalty(-5.0));
+    neat_trainer.event_sender = Some(sender);
+    neat_trainer.new_generation();
+    neat_trainer.evaluate_generation(&failing_fitness);
+    let failures: Vec<(uuid::Uuid, FitnessFailure)> = receiver.try_iter().filter_map(|e| match e { FromHostEvent::FitnessFailure(id, failure) => Some((id, failure)), _ => None }).collect();
+    assert!(failures.iter().any(|(_, f)| *f == FitnessFailure::Panicked("fitness function failed".to_string())));
+    assert!(failures.iter().any(|(_, f)| *f == FitnessFailure::NotANumber));
+    for member in &neat_trainer.members{
+        let expected = if failures.iter().any(|(id, _)| *id == member.genome.get_id()) { -5.0 } else { 1.0 };
+        assert_eq!(member.genome.get_fitness(), Some(expected));
+    }
+
+    let mut neat_trainer = NeatTrainer::new(configuration.clone().fitness_invalid_policy(InvalidFitnessPolicy::Discard));
+    neat_trainer.new_generation();
+    neat_trainer.evaluate_generation(&failing_fitness);
+    assert_eq!(neat_trainer.members.len(), 50 - failures.len());
+    assert!(neat_trainer.members.iter().all(|m| m.genome.get_fitness() == Some(1.0)));
+    assert_eq!(neat_trainer.members_map.len(), neat_trainer.members.len());
+    run_xor_generations(&mut neat_trainer, 3);
+
+    let mut neat_trainer = NeatTrainer::new(configuration.fitness_invalid_policy(InvalidFitnessPolicy::Abort));
+    neat_trainer.new_generation();
+    let aborted = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| neat_trainer.evaluate_generation(&failing_fitness)));
+    assert!(aborted.is_err());
+}
+
+#[test]
+fn discarding_every_member_fails_the_run() {
+    use crate::phenome::Phenome;
+    use crate::common::NeatFloat;
+    use crate::neat::trainer::{fitness::fitness_resolver::FitnessResolver, test_fixtures::xor_configuration};
+
+    let always_failing_fitness = |_: &dyn Phenome, fitness_resolver: &mut FitnessResolver| fitness_resolver.add_reward(0, NeatFloat::NAN);
+    let mut neat_trainer = NeatTrainer::new(xor_configuration(44).fitness_invalid_policy(InvalidFitnessPolicy::Discard));
+    neat_trainer.new_generation();
+    let discarded = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| neat_trainer.evaluate_generation(&always_failing_fitness)));
+    assert!(discarded.is_err());
+    assert_eq!(neat_trainer.members.len(), 50);
+}
+
+#[test]
+fn committing_directly_reports_failures_and_subnormal_inputs_count_as_zero() {
+    use crate::phenome::Phenome;
+    use crate::common::NeatFloat;
+    use crate::neat::trainer::{fitness::fitness_resolver::FitnessResolver, test_fixtures::xor_configuration, activation_strategies::activation_strategies::ActivationStrategies};
+    use crate::neat::trainer::neat_trainer_host::from_host_events::FromHostEvent;
+
+    let fitness = |_: &dyn Phenome, fitness_resolver: &mut FitnessResolver| {
+        match fitness_resolver.random().gen_range_usize(0, 2) {
+            0 => fitness_resolver.add_objective_fitness_component(0, 1.0, 0.0, NeatFloat::INFINITY),
+            _ => fitness_resolver.add_objective_fitness_component(0, 1.0, 0.0, NeatFloat::MIN_POSITIVE / 2.0)
+        }
+    };
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut neat_trainer = NeatTrainer::new(xor_configuration(43).fitness_cache_enabled(false).fitness_invalid_penalty(-5.0));
+    neat_trainer.event_sender = Some(sender);
+    neat_trainer.new_generation();
+    let mut activation_strategy = ActivationStrategies::get_cpu_parallel(&mut neat_trainer);
+    let mut fitness_setter = FitnessSetter::new();
+    activation_strategy.compute(fitness, &mut fitness_setter);
+    fitness_setter.commit(&mut neat_trainer);
+
+    let failures: Vec<(uuid::Uuid, FitnessFailure)> = receiver.try_iter().filter_map(|e| match e { FromHostEvent::FitnessFailure(id, failure) => Some((id, failure)), _ => None }).collect();
+    assert!(!failures.is_empty());
+    assert!(failures.iter().all(|(_, f)| *f == FitnessFailure::Infinite));
+    for member in &neat_trainer.members{
+        let expected = if failures.iter().any(|(id, _)| *id == member.genome.get_id()) { -5.0 } else { 1.0 };
+        assert_eq!(member.genome.get_fitness(), Some(expected));
+    }
+}
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+use crate::common::NeatFloat;
+
+/*
+Transforms the objective fitness of the whole population before species metrics and offspring are worked
+out, so a few outliers or large punishments do not decide how the offspring are shared. Every shaping keeps
+the order of the members, the genomes keep their raw fitness.
+*/
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, EnumIter)]
+pub enum FitnessShaping{
+    #[default]
+    None,
+    /* the rank of the member scaled to -0.5..=0.5, equal fitnesses share their average rank */
+    CenteredRanks,
+    /* standard deviations from the mean */
+    ZScore,
+    /* ln(1 + fitness - least fitness) */
+    Log
+}
+
+impl FitnessShaping{
+    pub fn shape(&self, fitnesses: &[NeatFloat]) -> Vec<NeatFloat>{
+        if fitnesses.is_empty(){
+            return Vec::new();
+        }
+        match self {
+            FitnessShaping::None => fitnesses.to_vec(),
+            FitnessShaping::CenteredRanks => Self::centered_ranks(fitnesses),
+            FitnessShaping::ZScore => {
+                let count = fitnesses.len() as NeatFloat;
+                let mean = fitnesses.iter().sum::<NeatFloat>() / count;
+                let standard_deviation = NeatFloat::sqrt(fitnesses.iter().map(|f| (f - mean) * (f - mean)).sum::<NeatFloat>() / count);
+                if standard_deviation == 0.0 {
+                    return vec![0.0; fitnesses.len()];
+                }
+                fitnesses.iter().map(|f| (f - mean) / standard_deviation).collect()
+            },
+            FitnessShaping::Log => {
+                let least_fit = fitnesses.iter().copied().fold(NeatFloat::MAX, NeatFloat::min);
+                fitnesses.iter().map(|f| NeatFloat::ln(1.0 + f - least_fit)).collect()
+            }
+        }
+    }
+    fn centered_ranks(fitnesses: &[NeatFloat]) -> Vec<NeatFloat>{
+        if fitnesses.len() == 1 {
+            return vec![0.0];
+        }
+        let mut order: Vec<usize> = (0..fitnesses.len()).collect();
+        order.sort_by(|a, b| fitnesses[*a].total_cmp(&fitnesses[*b]));
+
+        let mut ranks = vec![0.0; fitnesses.len()];
+        let mut start = 0;
+        while start < order.len(){
+            let mut end = start;
+            while end + 1 < order.len() && fitnesses[order[end + 1]] == fitnesses[order[start]]{
+                end += 1;
+            }
+            let average_rank = (start + end) as NeatFloat / 2.0;
+            for index in &order[start..=end]{
+                ranks[*index] = average_rank / (fitnesses.len() - 1) as NeatFloat - 0.5;
+            }
+            start = end + 1;
+        }
+        ranks
+    }
+}
+
+#[test]
+fn shaping_keeps_the_order_of_the_members() {
+    let fitnesses = [3.0, -100.0, 1.0, 1.0, 50.0];
+
+    assert_eq!(FitnessShaping::CenteredRanks.shape(&fitnesses), vec![0.25, -0.5, -0.125, -0.125, 0.5]);
+    let z_scores = FitnessShaping::ZScore.shape(&fitnesses);
+    assert!(z_scores.iter().sum::<NeatFloat>().abs() < 0.0001);
+    assert!(z_scores[4] > z_scores[0] && z_scores[0] > z_scores[2] && z_scores[2] > z_scores[1]);
+    assert_eq!(FitnessShaping::Log.shape(&fitnesses)[1], 0.0);
+    assert_eq!(FitnessShaping::ZScore.shape(&[2.0, 2.0]), vec![0.0, 0.0]);
+    assert_eq!(FitnessShaping::None.shape(&fitnesses), fitnesses.to_vec());
+}
+
+#[test]
+fn centered_ranks_do_not_panic_on_a_fitness_that_is_not_a_number() {
+    let ranks = FitnessShaping::CenteredRanks.shape(&[2.0, NeatFloat::NAN, 1.0]);
+
+    assert!(ranks[2] < ranks[0]);
+}
+
+#[test]
+fn fitness_shaping_keeps_the_raw_fitness_for_stats() {
+    use crate::neat::trainer::{neat_trainer::NeatTrainer, test_fixtures::{xor_configuration, run_xor_generations}};
+    use crate::neat::trainer::fitness::fitness_shaping::FitnessShaping;
+    use strum::IntoEnumIterator;
+
+    for fitness_shaping in FitnessShaping::iter(){
+        let configuration = xor_configuration(37)
+            .fitness_shaping(fitness_shaping);
+
+        let mut neat_trainer = NeatTrainer::new(configuration);
+        run_xor_generations(&mut neat_trainer, 5);
+
+        for species in neat_trainer.run_context.species_index.values(){
+            match fitness_shaping {
+                FitnessShaping::None => assert_eq!(species.raw_objective_fitness.average, species.objective_fitness.average),
+                FitnessShaping::CenteredRanks => assert!(species.objective_fitness.max <= 0.5),
+                _ => {}
+            }
+        }
+        assert!(neat_trainer.run_context.species_index.values().any(|s| s.raw_objective_fitness.max > 0.5), "{:?}", fitness_shaping);
+        let stats = neat_trainer.run_context.last_ten_thousand_generations_stats.last().unwrap();
+        assert!(stats.max_objective_fitness >= 0.0 && stats.max_objective_fitness <= 4.0, "{:?}", fitness_shaping);
+    }
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+/* what happens to a member whose fitness function panicked or produced a NaN, infinite or subnormal value */
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, EnumIter)]
+pub enum InvalidFitnessPolicy{
+    /* the member gets configuration.fitness_invalid_penalty as its fitness */
+    #[default]
+    Penalty,
+    /* the member is removed from the population before speciation, the run panics if every member failed */
+    Discard,
+    /* the run panics once the failures have been reported */
+    Abort
+}
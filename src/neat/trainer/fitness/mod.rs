@@ -3,4 +3,13 @@ pub mod fitness;
 pub use fitness::Fitness as Fitness;
 pub mod fitness_resolver;
 pub mod novelty_component_store;
-pub mod number_line;
\ No newline at end of file
+pub mod number_line;
+pub mod fitness_cache;
+pub mod fitness_aggregation;
+pub mod objective;
+pub mod pareto_front;
+pub mod component_score;
+pub mod fitness_shaping;
+pub mod fitness_failure;
+pub mod invalid_fitness_policy;
+pub mod evaluation_times;
\ No newline at end of file
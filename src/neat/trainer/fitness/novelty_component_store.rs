@@ -26,6 +26,14 @@ impl NoveltyComponentStore{
         }
         DistanceResult::default().distance_to_nearest_neighbor
     }
+    /* the average novelty of the outcomes against what the store has seen so far, as the fitness resolver computes it */
+    pub fn get_outcome_novelty(&self, quantized_values: &[ComponentNoveltyQuantizedValue]) -> NeatFloat{
+        if quantized_values.is_empty(){
+            return 0.0;
+        }
+        let total_novelty: NeatFloat = quantized_values.iter().map(|v| self.get_novelty_component_score(v.component_id, v.quantized_value) / 100.0).sum();
+        total_novelty / quantized_values.len() as NeatFloat
+    }
     pub fn add_quantized_values(&mut self, quantized_values: &Vec<ComponentNoveltyQuantizedValue>) {
         for quantized_value in quantized_values{
             let line_opt = self.components.get_mut(&quantized_value.component_id);
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use crate::common::NeatFloat;
+
+/* a named objective kept apart from the scalar objective fitness, higher values are better */
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Objective{
+    pub name: String,
+    pub value: NeatFloat
+}
+
+impl Objective{
+    pub fn new(name: &str, value: NeatFloat) -> Self{
+        Self {
+            name: name.to_string(),
+            value
+        }
+    }
+    /* objectives a member did not report count as the worst possible value */
+    pub fn get_value(objectives: &[Objective], name: &str) -> NeatFloat{
+        objectives.iter().find(|o| o.name == name).map(|o| o.value).unwrap_or(NeatFloat::MIN)
+    }
+}
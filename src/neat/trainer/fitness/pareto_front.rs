@@ -0,0 +1,179 @@
+use std::cmp::Ordering;
+use crate::common::NeatFloat;
+use super::objective::Objective;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParetoRank{
+    pub front: usize,
+    pub crowding_distance: NeatFloat
+}
+
+/*
+NSGA-II ranking. Members are put into fronts where nothing in a front is dominated by anything in the same
+or a later front, front 0 being the pareto front. Within a front, members in less crowded parts of the
+objective space have a larger crowding distance and are preferred so the front stays spread out.
+*/
+pub struct ParetoFront;
+
+impl ParetoFront{
+    pub fn dominates(a: &[Objective], b: &[Objective]) -> bool{
+        let mut is_better_in_any = false;
+        for objective in a.iter().chain(b.iter()){
+            let a_value = Objective::get_value(a, &objective.name);
+            let b_value = Objective::get_value(b, &objective.name);
+            if a_value < b_value {
+                return false;
+            }
+            if a_value > b_value {
+                is_better_in_any = true;
+            }
+        }
+        is_better_in_any
+    }
+    pub fn rank(objectives: &[&[Objective]]) -> Vec<ParetoRank>{
+        let mut ranks = vec![ParetoRank { front: 0, crowding_distance: 0.0 }; objectives.len()];
+        let mut dominated_by_count = vec![0; objectives.len()];
+        let mut dominates: Vec<Vec<usize>> = vec![Vec::new(); objectives.len()];
+        for a in 0..objectives.len(){
+            for b in (a+1)..objectives.len(){
+                if Self::dominates(objectives[a], objectives[b]){
+                    dominates[a].push(b);
+                    dominated_by_count[b] += 1;
+                }else if Self::dominates(objectives[b], objectives[a]){
+                    dominates[b].push(a);
+                    dominated_by_count[a] += 1;
+                }
+            }
+        }
+
+        let mut front: Vec<usize> = (0..objectives.len()).filter(|i| dominated_by_count[*i] == 0).collect();
+        let mut front_number = 0;
+        while !front.is_empty(){
+            for crowding in Self::get_crowding_distances(objectives, &front){
+                ranks[crowding.0] = ParetoRank { front: front_number, crowding_distance: crowding.1 };
+            }
+            let mut next_front = Vec::new();
+            for member in &front{
+                for dominated in &dominates[*member]{
+                    dominated_by_count[*dominated] -= 1;
+                    if dominated_by_count[*dominated] == 0 {
+                        next_front.push(*dominated);
+                    }
+                }
+            }
+            next_front.sort_unstable();
+            front = next_front;
+            front_number += 1;
+        }
+        ranks
+    }
+    /* indexes ordered best first, by front and then by crowding distance */
+    pub fn get_order(objectives: &[&[Objective]]) -> Vec<usize>{
+        let ranks = Self::rank(objectives);
+        let mut order: Vec<usize> = (0..objectives.len()).collect();
+        order.sort_by(|a, b| Self::compare(&ranks[*a], &ranks[*b]).then(a.cmp(b)));
+        order
+    }
+    pub fn compare(a: &ParetoRank, b: &ParetoRank) -> Ordering{
+        a.front.cmp(&b.front).then(b.crowding_distance.total_cmp(&a.crowding_distance))
+    }
+    fn get_crowding_distances(objectives: &[&[Objective]], front: &[usize]) -> Vec<(usize, NeatFloat)>{
+        let mut distances: Vec<(usize, NeatFloat)> = front.iter().map(|i| (*i, 0.0)).collect();
+        let mut names: Vec<&str> = front.iter().flat_map(|i| objectives[*i].iter().map(|o| o.name.as_str())).collect();
+        names.sort_unstable();
+        names.dedup();
+
+        for name in names{
+            distances.sort_by(|a, b| Objective::get_value(objectives[a.0], name).total_cmp(&Objective::get_value(objectives[b.0], name)).then(a.0.cmp(&b.0)));
+            let min = Objective::get_value(objectives[distances[0].0], name);
+            let max = Objective::get_value(objectives[distances[distances.len()-1].0], name);
+            let range = max - min;
+
+            distances[0].1 = NeatFloat::INFINITY;
+            let last = distances.len() - 1;
+            distances[last].1 = NeatFloat::INFINITY;
+            if range <= 0.0 || !range.is_finite() {
+                continue;
+            }
+            for i in 1..last{
+                let lower = Objective::get_value(objectives[distances[i-1].0], name);
+                let upper = Objective::get_value(objectives[distances[i+1].0], name);
+                distances[i].1 += (upper - lower) / range;
+            }
+        }
+        distances
+    }
+}
+
+#[test]
+fn members_are_ranked_into_fronts() {
+    let objectives = [
+        vec![Objective::new("speed", 1.0), Objective::new("accuracy", 5.0)],
+        vec![Objective::new("speed", 5.0), Objective::new("accuracy", 1.0)],
+        vec![Objective::new("speed", 3.0), Objective::new("accuracy", 3.0)],
+        vec![Objective::new("speed", 2.0), Objective::new("accuracy", 2.0)],
+        vec![Objective::new("speed", 1.0), Objective::new("accuracy", 1.0)]
+    ];
+    let objectives: Vec<&[Objective]> = objectives.iter().map(|o| o.as_slice()).collect();
+
+    assert!(ParetoFront::dominates(objectives[2], objectives[3]));
+    assert!(!ParetoFront::dominates(objectives[0], objectives[1]));
+    assert!(!ParetoFront::dominates(objectives[4], objectives[4]));
+
+    let ranks = ParetoFront::rank(&objectives);
+    assert_eq!(ranks.iter().map(|r| r.front).collect::<Vec<usize>>(), vec![0, 0, 0, 1, 2]);
+    assert_eq!(ranks[0].crowding_distance, NeatFloat::INFINITY);
+    assert!(ranks[2].crowding_distance.is_finite());
+    assert_eq!(ParetoFront::get_order(&objectives), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn an_objective_that_is_not_a_number_does_not_break_ranking() {
+    let objectives = [
+        vec![Objective::new("speed", 1.0), Objective::new("accuracy", 5.0)],
+        vec![Objective::new("speed", NeatFloat::NAN), Objective::new("accuracy", 1.0)],
+        vec![Objective::new("speed", 5.0), Objective::new("accuracy", 1.0)]
+    ];
+    let objectives: Vec<&[Objective]> = objectives.iter().map(|o| o.as_slice()).collect();
+
+    assert_eq!(ParetoFront::get_order(&objectives).len(), 3);
+}
+
+#[test]
+fn multi_objective_run_keeps_a_pareto_front() {
+    use crate::phenome::Phenome;
+    use crate::cpu_phenome::CpuPhenome;
+    use crate::neat::trainer::{neat_trainer::NeatTrainer, fitness::fitness_resolver::FitnessResolver, test_fixtures::xor_configuration};
+
+    let configuration = xor_configuration(19).population_size(100).success_threshold(10.0)
+        .multi_objective_enabled(true)
+        .run_name("multi-objective-test".to_string());
+    let fitness = |phenotype: &dyn Phenome, fitness_resolver: &mut FitnessResolver| {
+        for test in [[0.0, 0.0, 0.0], [0.0, 1.0, 1.0], [1.0, 0.0, 1.0], [1.0, 1.0, 0.0]]{
+            let result = phenotype.activate(&vec![test[0], test[1]])[0];
+            let objective = if test[2] == 1.0 { "true_cases" } else { "false_cases" };
+            fitness_resolver.add_objective(objective, 1.0 - (test[2] - result).abs());
+        }
+    };
+
+    let mut neat_trainer = NeatTrainer::new(configuration);
+    for _ in 0..10{
+        neat_trainer.new_generation();
+        neat_trainer.evaluate_generation(&fitness);
+    }
+
+    let pareto_front = neat_trainer.get_pareto_front();
+    assert!(!pareto_front.is_empty());
+    assert!(pareto_front.iter().all(|m| m.objectives.len() == 2));
+    for member in pareto_front{
+        assert!(!pareto_front.iter().any(|other| ParetoFront::dominates(&other.objectives, &member.objectives)));
+        assert!(!neat_trainer.members.iter().any(|other| ParetoFront::dominates(&other.objectives, &member.objectives)));
+    }
+
+    let directory = std::env::temp_dir().join(format!("neatlib-pareto-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let paths = neat_trainer.export_pareto_front(&directory).unwrap();
+    assert_eq!(paths.len(), pareto_front.len());
+    assert!(paths.iter().all(|p| CpuPhenome::from_network_file(p).is_ok()));
+    let _ = std::fs::remove_dir_all(&directory);
+}
@@ -1,6 +1,6 @@
 use serde::{Serialize, Deserialize};
 
-use crate::{neat::trainer::run_context::RunContext, common::NeatFloat};
+use crate::{neat::{trainer::run_context::RunContext, genome::genome::Genome}, common::NeatFloat};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct GenerationStats{
@@ -9,10 +9,12 @@ pub struct GenerationStats{
     pub avg_generations_since_last_objective_fitness_improvement: NeatFloat,
     pub max_outcome_novelty: NeatFloat,
     pub avg_outcome_novelty: NeatFloat,
+    #[serde(default)]
+    pub curriculum_stage: Option<usize>
 }
 
 impl GenerationStats{
-    pub fn new(run_context: &RunContext) -> Self{
+    pub fn new<T: Genome>(run_context: &RunContext<T>) -> Self{
         let mut max_objective_fitness: NeatFloat = NeatFloat::MIN;
         let mut avg_positively_adjusted_objective_fitness: NeatFloat = 0.0;
         let mut avg_generations_since_last_objective_fitness_improvement: NeatFloat = 0.0;
@@ -22,13 +24,13 @@ impl GenerationStats{
         let number_of_species = run_context.species_index.len() as NeatFloat;
 
         for (_, species) in &run_context.species_index{
-            if species.objective_fitness.max > max_objective_fitness{
-                max_objective_fitness = species.objective_fitness.max_positively_adjusted;
+            if species.raw_objective_fitness.max > max_objective_fitness{
+                max_objective_fitness = species.raw_objective_fitness.max_positively_adjusted;
             }
 
-            avg_positively_adjusted_objective_fitness += species.objective_fitness.positively_adjusted_average;
+            avg_positively_adjusted_objective_fitness += species.raw_objective_fitness.positively_adjusted_average;
 
-            avg_generations_since_last_objective_fitness_improvement += species.objective_fitness.last_generation_improved as NeatFloat;
+            avg_generations_since_last_objective_fitness_improvement += species.raw_objective_fitness.last_generation_improved as NeatFloat;
 
             if species.outcome_novelty.max > max_outcome_novelty{
                 max_outcome_novelty = species.outcome_novelty.max;
@@ -38,7 +40,7 @@ impl GenerationStats{
         }
 
         if run_context.best_member_so_far.is_some(){
-            max_objective_fitness = run_context.best_member_so_far.as_ref().unwrap().genome.objective_fitness.unwrap();
+            max_objective_fitness = run_context.best_member_so_far.as_ref().unwrap().genome.get_fitness().unwrap();
         }
 
         Self { 
@@ -46,7 +48,20 @@ impl GenerationStats{
             avg_positive_objective_fitness: avg_positively_adjusted_objective_fitness / number_of_species, 
             avg_generations_since_last_objective_fitness_improvement: avg_generations_since_last_objective_fitness_improvement / number_of_species,
             max_outcome_novelty: max_outcome_novelty, 
-            avg_outcome_novelty: avg_outcome_novelty / number_of_species
+            avg_outcome_novelty: avg_outcome_novelty / number_of_species,
+            curriculum_stage: run_context.curriculum_stage.as_ref().map(|c| c.stage)
         }
     }
-}
\ No newline at end of file
+    /* merges the stats of several populations evolved side by side, such as the islands of an IslandModel */
+    pub fn combine(stats: &[GenerationStats]) -> Self{
+        let count = stats.len().max(1) as NeatFloat;
+        Self {
+            max_objective_fitness: stats.iter().map(|s| s.max_objective_fitness).fold(NeatFloat::MIN, NeatFloat::max),
+            avg_positive_objective_fitness: stats.iter().map(|s| s.avg_positive_objective_fitness).sum::<NeatFloat>() / count,
+            avg_generations_since_last_objective_fitness_improvement: stats.iter().map(|s| s.avg_generations_since_last_objective_fitness_improvement).sum::<NeatFloat>() / count,
+            max_outcome_novelty: stats.iter().map(|s| s.max_outcome_novelty).fold(NeatFloat::MIN, NeatFloat::max),
+            avg_outcome_novelty: stats.iter().map(|s| s.avg_outcome_novelty).sum::<NeatFloat>() / count,
+            curriculum_stage: stats.first().and_then(|s| s.curriculum_stage)
+        }
+    }
+}
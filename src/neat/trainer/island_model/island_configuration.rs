@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use crate::neat::trainer::config_error::ConfigError;
+use super::{migration_topology::MigrationTopology, migrant_selection::MigrantSelection};
+
+/*
+Every migration_interval generations each island sends up to migrants_per_island copies of its best members
+to the islands its topology points at, where they replace the worst members.
+*/
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct IslandConfiguration{
+    pub number_of_islands: usize,
+    pub migration_topology: MigrationTopology,
+    pub migration_interval: u32,
+    pub migrants_per_island: usize,
+    pub migrant_selection: MigrantSelection
+}
+
+impl Default for IslandConfiguration{
+    fn default() -> Self {
+        Self {
+            number_of_islands: 4,
+            migration_topology: MigrationTopology::Ring,
+            migration_interval: 10,
+            migrants_per_island: 2,
+            migrant_selection: MigrantSelection::BestPerIsland
+        }
+    }
+}
+
+impl IslandConfiguration{
+    pub fn new() -> Self{
+        Self::default()
+    }
+    pub fn number_of_islands(mut self, value: usize) -> Self{
+        self.number_of_islands = value;
+        self
+    }
+    pub fn migration_topology(mut self, value: MigrationTopology) -> Self{
+        self.migration_topology = value;
+        self
+    }
+    pub fn migration_interval(mut self, value: u32) -> Self{
+        self.migration_interval = value;
+        self
+    }
+    pub fn migrants_per_island(mut self, value: usize) -> Self{
+        self.migrants_per_island = value;
+        self
+    }
+    pub fn migrant_selection(mut self, value: MigrantSelection) -> Self{
+        self.migrant_selection = value;
+        self
+    }
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.number_of_islands == 0 {
+            return Err(ConfigError::MustBeGreaterThanZero { property: "number_of_islands" });
+        }
+        if self.migration_interval == 0 {
+            return Err(ConfigError::MustBeGreaterThanZero { property: "migration_interval" });
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,353 @@
+use std::time::Instant;
+use crate::common::{NeatFloat, seeded_random::SeededRandom};
+use crate::common::network_definition::NetworkDefinition;
+use crate::phenome::Phenome;
+use crate::neat::genetable::{connect_gene_table::ConnectGeneTable, node_gene_index::NoneGeneIndex};
+use crate::neat::genome::{genome::Genome, crossover::Crossover, mutation_history::MutationHistory, neat::NeatGenome};
+use crate::neat::population::GenerationMember;
+use crate::neat::trainer::{configuration::Configuration, neat_trainer::NeatTrainer, generation_stats::GenerationStats, run_report::RunReport};
+use crate::neat::trainer::stop_criteria::{StopCriteria, StopReason};
+use crate::neat::trainer::fitness::fitness_resolver::FitnessResolver;
+use serde::Serialize;
+use crate::neat::trainer::{config_error::ConfigError, neat_trainer_host::from_host_events::FromHostEvent};
+use super::{island_configuration::IslandConfiguration, migrant_selection::MigrantSelection};
+
+/*
+Evolves several populations side by side. Each island keeps its own run context and species, while the
+innovation table and node index are shared so the same structural mutation has the same numbers on every
+island and migrants can be compared and crossed over with the members of the island they arrive on.
+The shared table is swapped into an island while it mutates and while its checkpoint is written, so an
+island resumed from its checkpoint has the innovation numbers of its genomes.
+*/
+pub struct IslandModel<T = NeatGenome> where T: Genome{
+    pub islands: Vec<NeatTrainer<T>>,
+    pub island_configuration: IslandConfiguration,
+    pub generation_stats: Vec<GenerationStats>,
+    gene_table: ConnectGeneTable,
+    node_index: NoneGeneIndex
+}
+
+impl IslandModel{
+    pub fn new(configuration: Configuration, island_configuration: IslandConfiguration) -> Self {
+        Self::new_for_genome(configuration, island_configuration)
+    }
+    pub fn try_new(configuration: Configuration, island_configuration: IslandConfiguration) -> Result<Self, ConfigError> {
+        Self::try_new_for_genome(configuration, island_configuration)
+    }
+}
+impl<T> IslandModel<T> where T: Genome + Crossover + MutationHistory{
+    pub fn new_for_genome(configuration: Configuration, island_configuration: IslandConfiguration) -> Self {
+        match Self::try_new_for_genome(configuration, island_configuration) {
+            Ok(island_model) => island_model,
+            Err(error) => panic!("{}", error)
+        }
+    }
+    pub fn try_new_for_genome(configuration: Configuration, island_configuration: IslandConfiguration) -> Result<Self, ConfigError> {
+        island_configuration.validate()?;
+
+        let mut islands = Vec::with_capacity(island_configuration.number_of_islands);
+        for island in 0..island_configuration.number_of_islands{
+            let seed = configuration.seed.map(|seed| SeededRandom::for_stream(seed, island).gen_u64());
+            let run_name = format!("{}-island-{}", configuration.run_name, island);
+            let mut trainer = NeatTrainer::try_new_for_genome(configuration.clone().seed(seed).run_name(run_name))?;
+            trainer.run_context.keep_innovations_on_reset = true;
+            islands.push(trainer);
+        }
+
+        Ok(IslandModel {
+            islands,
+            island_configuration,
+            generation_stats: Vec::new(),
+            gene_table: ConnectGeneTable::new(),
+            node_index: NoneGeneIndex::new(configuration.node_genes.len()+1)
+        })
+    }
+    pub fn new_generation(&mut self) {
+        for island in self.islands.iter_mut(){
+            Self::with_shared_innovations(island, &mut self.gene_table, &mut self.node_index, |island| island.new_generation());
+        }
+
+        let island_stats: Vec<GenerationStats> = self.islands.iter().filter_map(|i| i.run_context.last_ten_thousand_generations_stats.last().cloned()).collect();
+        if !island_stats.is_empty() && island_stats.len() == self.islands.len(){
+            self.generation_stats.push(GenerationStats::combine(&island_stats));
+            while self.generation_stats.len() > 5_000 {
+                self.generation_stats.remove(0);
+            }
+        }
+    }
+    /* evaluates every island and, when due, moves migrants while their fitness is still known */
+    pub fn evaluate_generation<F>(&mut self, fitness_fn: &F) where F: Fn(&dyn Phenome, &mut FitnessResolver) + Sync, T: NetworkDefinition{
+        for island in self.islands.iter_mut(){
+            island.evaluate_generation(fitness_fn);
+        }
+
+        let generation = self.get_current_generation();
+        if generation > 0 && generation % self.island_configuration.migration_interval == 0{
+            self.migrate();
+        }
+    }
+    fn with_shared_innovations<R, F>(island: &mut NeatTrainer<T>, gene_table: &mut ConnectGeneTable, node_index: &mut NoneGeneIndex, action: F) -> R where F: FnOnce(&mut NeatTrainer<T>) -> R{
+        std::mem::swap(&mut island.run_context.gene_table, gene_table);
+        std::mem::swap(&mut island.run_context.node_index, node_index);
+        let result = action(island);
+        std::mem::swap(&mut island.run_context.gene_table, gene_table);
+        std::mem::swap(&mut island.run_context.node_index, node_index);
+        result
+    }
+    pub fn migrate(&mut self){
+        let number_of_islands = self.islands.len();
+        let mut incoming: Vec<Vec<GenerationMember<T>>> = vec![Vec::new(); number_of_islands];
+
+        for (source, island) in self.islands.iter().enumerate(){
+            let migrants = Self::select_migrants(island, &self.island_configuration);
+            for destination in self.island_configuration.migration_topology.get_destinations(source, number_of_islands){
+                incoming[destination].extend(migrants.iter().cloned());
+            }
+        }
+
+        for (island, migrants) in self.islands.iter_mut().zip(incoming){
+            Self::receive_migrants(island, migrants);
+        }
+    }
+    fn select_migrants(island: &NeatTrainer<T>, island_configuration: &IslandConfiguration) -> Vec<GenerationMember<T>>{
+        let mut candidates: Vec<&GenerationMember<T>> = island.members.iter().filter(|m| m.genome.get_fitness().is_some()).collect();
+        candidates.sort();
+
+        if island_configuration.migrant_selection == MigrantSelection::BestPerSpecies {
+            /* candidates are sorted fittest first, so the first member seen of each species is its best */
+            let mut seen_species = Vec::new();
+            candidates.retain(|m| {
+                if seen_species.contains(&m.species_hint){
+                    return false;
+                }
+                seen_species.push(m.species_hint);
+                true
+            });
+        }
+
+        candidates.into_iter().take(island_configuration.migrants_per_island).cloned().collect()
+    }
+    /* migrants replace the worst members of the island and are speciated with the rest on the next generation */
+    fn receive_migrants(island: &mut NeatTrainer<T>, mut migrants: Vec<GenerationMember<T>>){
+        migrants.truncate(island.members.len());
+        if migrants.is_empty(){
+            return;
+        }
+
+        let mut worst_first: Vec<usize> = (0..island.members.len()).collect();
+        /* members without a usable fitness are replaced first */
+        let fitness = |index: usize| island.members[index].genome.get_fitness().filter(|f| !f.is_nan()).unwrap_or(NeatFloat::MIN);
+        worst_first.sort_by(|a, b| fitness(*a).total_cmp(&fitness(*b)));
+
+        let current_generation = island.get_current_generation();
+        let mut replaced_ids = Vec::with_capacity(migrants.len());
+        for (index, mut migrant) in worst_first.into_iter().zip(migrants){
+            replaced_ids.push(island.members[index].genome.get_id());
+            migrant.genome.set_id(island.run_context.random.new_uuid());
+            migrant.created_generation = current_generation;
+            migrant.number_of_generations_since_species_improved = 0;
+            migrant.species_hint = uuid::Uuid::nil();
+            island.members[index] = migrant;
+        }
+
+        /* species still list the members that were replaced from the last speciation */
+        for (_, species) in island.run_context.species_index.iter_mut(){
+            species.members.retain(|m| !replaced_ids.contains(&m.id));
+        }
+
+        island.recompute_indexes();
+    }
+    pub fn get_current_generation(&self) -> u32{
+        self.islands.first().map(|i| i.get_current_generation()).unwrap_or_default()
+    }
+    pub fn get_best_member_so_far(&self) -> Option<GenerationMember<T>>{
+        /* members are ordered fittest first */
+        self.islands.iter()
+            .filter_map(|i| i.run_context.best_member_so_far.as_ref())
+            .filter(|m| m.genome.get_fitness().is_some())
+            .min()
+            .cloned()
+    }
+    pub fn has_met_success(&mut self) -> bool{
+        self.islands.iter_mut().any(|i| i.has_met_success())
+    }
+    pub fn run<F>(&mut self, fitness_fn: F, stop_criteria: StopCriteria) -> RunReport<T> where F: Fn(&dyn Phenome, &mut FitnessResolver) + Sync, T: NetworkDefinition + Serialize{
+        let started = Instant::now();
+        let start_generation = self.get_current_generation();
+        let previous_success_thresholds: Vec<Option<NeatFloat>> = self.islands.iter().map(|i| i.get_success_threshold_override()).collect();
+        if stop_criteria.success_threshold.is_some(){
+            for island in self.islands.iter_mut(){
+                island.set_success_threshold_override(stop_criteria.success_threshold);
+            }
+        }
+
+        let mut best_fitness: Option<NeatFloat> = None;
+        let mut last_improved_generation = start_generation;
+
+        let stop_reason = loop {
+            self.new_generation();
+            self.evaluate_generation(&fitness_fn);
+
+            for island in self.islands.iter_mut(){
+                if let Err(error) = Self::with_shared_innovations(island, &mut self.gene_table, &mut self.node_index, |island| island.checkpoint_if_due()){
+                    island.send_event(FromHostEvent::CheckpointError(error.to_string()));
+                }
+            }
+
+            let current_generation = self.get_current_generation();
+            let best_fitness_so_far = self.get_best_member_so_far().and_then(|m| m.genome.get_fitness());
+            if best_fitness_so_far.is_some() && (best_fitness.is_none() || best_fitness_so_far.unwrap() > best_fitness.unwrap()){
+                best_fitness = best_fitness_so_far;
+                last_improved_generation = current_generation;
+            }
+
+            if self.has_met_success(){
+                break StopReason::SuccessThreshold;
+            }
+            if let Some(stop_reason) = stop_criteria.get_stop_reason(current_generation - start_generation, started.elapsed(), current_generation - last_improved_generation){
+                break stop_reason;
+            }
+        };
+        for (island, previous_success_threshold) in self.islands.iter_mut().zip(previous_success_thresholds){
+            island.set_success_threshold_override(previous_success_threshold);
+        }
+
+        RunReport {
+            champion: self.get_best_member_so_far(),
+            stop_reason,
+            generations: self.get_current_generation() - start_generation,
+            elapsed: started.elapsed(),
+            generation_stats: self.generation_stats.clone()
+        }
+    }
+}
+
+#[test]
+fn island_model_runs_islands_in_step() {
+    use crate::neat::trainer::{stop_criteria::{StopCriteria, StopReason}, test_fixtures::{xor_configuration, xor_fitness}};
+    use super::island_configuration::IslandConfiguration;
+
+    let configuration = xor_configuration(11).target_species(3).print_summary_interval(None);
+    let island_configuration = IslandConfiguration::new()
+        .number_of_islands(3)
+        .migration_interval(2)
+        .migrants_per_island(2);
+    let mut island_model = IslandModel::new(configuration, island_configuration);
+
+    let report = island_model.run(xor_fitness, StopCriteria::new().max_generations(Some(8)).success_threshold(Some(10.0)));
+
+    assert_eq!(report.stop_reason, StopReason::MaxGenerations);
+    assert!(island_model.islands.iter().all(|i| i.get_current_generation() == 8));
+    assert!(report.champion_fitness().is_some());
+    assert!(!report.generation_stats.is_empty());
+    assert!(island_model.islands.iter().all(|i| i.configuration.success_threshold == 4.0 && i.get_success_threshold() == 4.0));
+}
+
+#[test]
+fn island_model_validates_its_configuration() {
+    use crate::neat::trainer::{configuration::Configuration, node_conf::NodeConf, config_error::ConfigError};
+    use super::island_configuration::IslandConfiguration;
+
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0);
+
+    assert_eq!(IslandModel::try_new(configuration.clone(), IslandConfiguration::new().migration_interval(0)).err(), Some(ConfigError::MustBeGreaterThanZero { property: "migration_interval" }));
+    assert_eq!(IslandModel::try_new(configuration.population_size(0), IslandConfiguration::new()).err(), Some(ConfigError::MustBeGreaterThanZero { property: "population_size" }));
+}
+
+#[test]
+fn islands_do_not_reuse_hidden_node_ids() {
+    use crate::neat::trainer::test_fixtures::{xor_configuration, xor_fitness};
+    use super::island_configuration::IslandConfiguration;
+    use crate::node_kind::NodeKind;
+
+    let configuration = xor_configuration(13)
+        .population_size(20)
+        .target_species(3)
+        .mutation_node_add_probability(1.0)
+        .print_summary_interval(None);
+    let island_configuration = IslandConfiguration::new()
+        .number_of_islands(3)
+        .migration_interval(1000);
+    let mut island_model = IslandModel::new(configuration, island_configuration);
+    for _ in 0..3{
+        island_model.new_generation();
+        island_model.evaluate_generation(&xor_fitness);
+    }
+
+    /* no migration has happened, so every hidden node was added on one island only */
+    let hidden_node_ids: Vec<std::collections::HashSet<i32>> = island_model.islands.iter().map(|island| {
+        island.members.iter().flat_map(|m| m.genome.genes.nodes.iter().filter(|n| n.kind == NodeKind::Hidden).map(|n| n.number)).collect()
+    }).collect();
+    assert!(hidden_node_ids.iter().all(|ids| !ids.is_empty()));
+    for (index, ids) in hidden_node_ids.iter().enumerate(){
+        for other_ids in hidden_node_ids.iter().skip(index + 1){
+            assert!(ids.is_disjoint(other_ids));
+        }
+    }
+}
+
+#[test]
+fn island_migrants_replace_the_worst_members() {
+    use crate::common::NeatFloat;
+    use crate::neat::genome::genome::Genome;
+    use crate::neat::trainer::test_fixtures::{xor_configuration, xor_fitness};
+    use super::{island_configuration::IslandConfiguration, migration_topology::MigrationTopology, migrant_selection::MigrantSelection};
+
+    let configuration = xor_configuration(12).target_species(3).print_summary_interval(None);
+    let island_configuration = IslandConfiguration::new()
+        .number_of_islands(2)
+        .migration_topology(MigrationTopology::FullyConnected)
+        .migration_interval(1000)
+        .migrants_per_island(3)
+        .migrant_selection(MigrantSelection::BestPerSpecies);
+    let mut island_model = IslandModel::new(configuration, island_configuration);
+    island_model.new_generation();
+    island_model.evaluate_generation(&xor_fitness);
+    let best_of_first_island = island_model.islands[0].members.iter().filter_map(|m| m.genome.get_fitness()).fold(NeatFloat::MIN, NeatFloat::max);
+    let members_of_second_island = island_model.islands[1].members.len();
+    let not_a_number_id = island_model.islands[1].members[0].genome.get_id();
+    island_model.islands[1].members[0].genome.set_objective_fitness(NeatFloat::NAN);
+
+    island_model.migrate();
+
+    assert_eq!(island_model.islands[1].members.len(), members_of_second_island);
+    assert!(island_model.islands[1].members.iter().any(|m| m.genome.get_fitness() == Some(best_of_first_island)));
+    assert_eq!(island_model.islands[1].members_map.len(), members_of_second_island);
+    assert!(island_model.islands[1].members.iter().all(|m| m.genome.get_id() != not_a_number_id));
+}
+
+#[test]
+fn island_checkpoints_keep_the_shared_innovation_table() {
+    use crate::neat::trainer::{checkpoint::Checkpoint, configuration::SaveFormat, stop_criteria::StopCriteria, test_fixtures::{xor_configuration, xor_fitness}};
+    use super::island_configuration::IslandConfiguration;
+
+    let directory = std::env::temp_dir().join(format!("neatlib-island-checkpoints-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    let configuration = xor_configuration(15)
+        .population_size(20)
+        .target_species(3)
+        .mutation_node_add_probability(1.0)
+        .print_summary_interval(None)
+        .checkpoint_every_generations(Some(3))
+        .run_save_directory(directory.to_string_lossy().to_string())
+        .run_name("islands".to_string())
+        .run_save_format(SaveFormat::CompressedBincode);
+    let island_configuration = IslandConfiguration::new()
+        .number_of_islands(2)
+        .migration_interval(1000);
+    let mut island_model = IslandModel::new(configuration, island_configuration);
+    island_model.run(xor_fitness, StopCriteria::new().max_generations(Some(3)).success_threshold(Some(10.0)));
+
+    /* islands hold an empty table between generations, the one in the checkpoint is the shared one */
+    for island in 0..2{
+        let checkpoints = Checkpoint::list(&directory, Some(&format!("islands-island-{}", island))).unwrap();
+        assert_eq!(checkpoints.len(), 1);
+        let resumed: NeatTrainer = Checkpoint::read(&checkpoints[0]).unwrap();
+        assert_eq!(resumed.run_context.gene_table.len(), island_model.gene_table.len());
+        assert_eq!(resumed.run_context.gene_table.next_innovation_number(), island_model.gene_table.next_innovation_number());
+        assert_eq!(serde_json::to_string(&resumed.run_context.node_index).unwrap(), serde_json::to_string(&island_model.node_index).unwrap());
+    }
+    assert!(island_model.gene_table.len() > 0);
+    let _ = std::fs::remove_dir_all(&directory);
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, EnumIter)]
+pub enum MigrantSelection{
+    /* the fittest members of the island */
+    #[default]
+    BestPerIsland,
+    /* the fittest member of each species, fittest species first */
+    BestPerSpecies
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, EnumIter)]
+pub enum MigrationTopology{
+    /* island i sends its migrants to island i + 1, the last island sends to the first */
+    #[default]
+    Ring,
+    /* every island sends its migrants to every other island */
+    FullyConnected
+}
+
+impl MigrationTopology{
+    pub fn get_destinations(&self, island: usize, number_of_islands: usize) -> Vec<usize>{
+        if number_of_islands < 2 {
+            return Vec::new();
+        }
+        match self {
+            MigrationTopology::Ring => vec![(island + 1) % number_of_islands],
+            MigrationTopology::FullyConnected => (0..number_of_islands).filter(|i| *i != island).collect()
+        }
+    }
+}
+
+#[test]
+fn destinations_follow_the_topology() {
+    assert_eq!(MigrationTopology::Ring.get_destinations(2, 3), vec![0]);
+    assert_eq!(MigrationTopology::FullyConnected.get_destinations(1, 3), vec![0, 2]);
+    assert!(MigrationTopology::FullyConnected.get_destinations(0, 1).is_empty());
+}
@@ -0,0 +1,4 @@
+pub mod island_model;
+pub mod island_configuration;
+pub mod migration_topology;
+pub mod migrant_selection;
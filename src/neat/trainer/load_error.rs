@@ -0,0 +1,29 @@
+use std::fmt;
+use super::config_error::ConfigError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadError{
+    Read{ path: String, message: String },
+    UnknownFormat(u8),
+    UnsupportedVersion{ version: u32, current_version: u32 },
+    BinaryMigrationUnsupported{ version: u32 },
+    Migration{ version: u32, message: String },
+    Deserialize{ path: String, message: String },
+    Configuration(ConfigError)
+}
+
+impl fmt::Display for LoadError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Read { path, message } => write!(f, "could not read saved run {}: {}", path, message),
+            LoadError::UnknownFormat(format) => write!(f, "unknown save format {}", format),
+            LoadError::UnsupportedVersion { version, current_version } => write!(f, "saved run has version {} but the newest supported version is {}", version, current_version),
+            LoadError::BinaryMigrationUnsupported { version } => write!(f, "compressed bincode saves from version {} can not be migrated, re-save it as json with that version first", version),
+            LoadError::Migration { version, message } => write!(f, "could not migrate saved run from version {}: {}", version, message),
+            LoadError::Deserialize { path, message } => write!(f, "could not deserialize saved run {}: {}", path, message),
+            LoadError::Configuration(error) => write!(f, "saved run has an invalid configuration: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
@@ -1,13 +1,27 @@
 pub mod node_conf;
 pub mod run_context;
 pub mod configuration;
+pub mod config_error;
+pub mod configuration_file;
+pub mod mutation_schedule;
 pub mod neat_trainer;
+pub mod checkpoint;
+pub mod load_error;
+pub mod save_migrations;
+pub mod stop_criteria;
+pub mod run_report;
+pub mod resumed_run;
 pub mod neat_trainer_host;
 pub mod seed_bank;
 mod neat_trainer_tests;
+#[cfg(test)]
+pub mod test_fixtures;
 mod configuration_defaults;
 pub mod activation_strategies;
 pub mod fitness;
 pub mod config_regulators;
 pub mod run_signals;
-pub mod generation_stats;
\ No newline at end of file
+pub mod generation_stats;
+pub mod island_model;
+pub mod curriculum;
+pub mod environment;
\ No newline at end of file
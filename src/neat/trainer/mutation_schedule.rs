@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+use crate::neat::genome::neat::mutation_mode::MutationMode;
+
+/*
+Picks how hard a member is mutated from how long it has been stagnant. A member is mutated
+with the most intense mode whose threshold its stagnation is above, the number of structural
+mutations for that mode is drawn between 1 and the configured value.
+
+Species: generations since the member's species last improved.
+MemberAge: generations since the member was created, i.e. how long it has survived unchanged
+by reproduction.
+*/
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MutationSchedule{
+    pub basis: MutationScheduleBasis,
+    pub optimistic_after_generations: u32,
+    pub fool_hardy_after_generations: u32,
+    pub shoot_for_the_moon_after_generations: u32,
+    pub steady_structural_mutations: usize,
+    pub optimistic_structural_mutations: usize,
+    pub fool_hardy_structural_mutations: usize,
+    pub shoot_for_the_moon_structural_mutations: usize
+}
+
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, EnumIter)]
+pub enum MutationScheduleBasis{
+    Species,
+    MemberAge
+}
+
+impl MutationSchedule{
+    pub fn get_mode(&self, generations_stagnant: u32) -> MutationMode{
+        if generations_stagnant > self.shoot_for_the_moon_after_generations {
+            MutationMode::ShootForTheMoon
+        }else if generations_stagnant > self.fool_hardy_after_generations {
+            MutationMode::FoolHardy
+        }else if generations_stagnant > self.optimistic_after_generations {
+            MutationMode::Optimistic
+        }else{
+            MutationMode::Steady
+        }
+    }
+    pub fn get_max_structural_mutations(&self, mutation_mode: MutationMode) -> usize{
+        match mutation_mode {
+            MutationMode::Steady => self.steady_structural_mutations,
+            MutationMode::Optimistic => self.optimistic_structural_mutations,
+            MutationMode::FoolHardy => self.fool_hardy_structural_mutations,
+            MutationMode::ShootForTheMoon => self.shoot_for_the_moon_structural_mutations
+        }
+    }
+}
+
+impl Default for MutationSchedule{
+    fn default() -> Self {
+        Self {
+            basis: MutationScheduleBasis::Species,
+            optimistic_after_generations: 4,
+            fool_hardy_after_generations: 8,
+            shoot_for_the_moon_after_generations: 10,
+            steady_structural_mutations: 1,
+            optimistic_structural_mutations: 2,
+            fool_hardy_structural_mutations: 5,
+            shoot_for_the_moon_structural_mutations: 11
+        }
+    }
+}
+
+#[test]
+fn every_mode_is_reachable() {
+    let schedule = MutationSchedule::default();
+
+    assert_eq!(schedule.get_mode(0), MutationMode::Steady);
+    assert_eq!(schedule.get_mode(4), MutationMode::Steady);
+    assert_eq!(schedule.get_mode(5), MutationMode::Optimistic);
+    assert_eq!(schedule.get_mode(9), MutationMode::FoolHardy);
+    assert_eq!(schedule.get_mode(11), MutationMode::ShootForTheMoon);
+}
@@ -1,56 +1,106 @@
-use std::fs::File;
-use std::io::prelude::*;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::Sender;
-use hashbrown::HashMap;
+use std::time::{Duration, Instant};
+use hashbrown::{HashMap, HashSet};
 use nohash_hasher::BuildNoHashHasher;
 use crate::common::NeatFloat;
-use crate::neat::genome::neat::mutation_mode::MutationMode;
 use crate::neat::population::speciation::speciation::Speciation;
+use crate::neat::population::speciation::speciation_stage::SpeciationStage;
 use crate::neat::population::speciation::species::Species;
 use crate::neat::trainer::config_regulators::config_regulator_handler::ConfigRegulatorHandler;
 use crate::neat::trainer::run_signals::run_signals::RunSignals;
 use crate::neat::genome::neat::NeatGenome;
 use crate::neat::genome::genome::Genome;
+use crate::neat::genome::crossover::Crossover;
+use crate::neat::genome::mutation_history::MutationHistory;
 use crate::neat::population::GenerationMember;
-use serde::{Deserialize, Serialize};
+use crate::phenome::Phenome;
+use crate::common::network_definition::NetworkDefinition;
+use crate::common::{network_file::NetworkFile, network_file_error::NetworkFileError};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use super::configuration::{Configuration};
+use super::config_error::ConfigError;
+use super::mutation_schedule::MutationScheduleBasis;
 use super::configuration_defaults::ConfigurationDefaults;
 use super::neat_trainer_host::from_host_events::FromHostEvent;
 use super::neat_trainer_host::models::run_stats::RunStats;
 use super::config_regulators::config_regulator::ConfigRegulator;
 use super::run_context::{RunContext};
+use super::stop_criteria::{StopCriteria, StopReason};
+use super::run_report::RunReport;
+use super::resumed_run::ResumedRun;
+use super::checkpoint::Checkpoint;
+use super::load_error::LoadError;
+use super::activation_strategies::activation_strategies::ActivationStrategies;
+use super::fitness::{fitness_setter::FitnessSetter, fitness_resolver::FitnessResolver, objective::Objective, pareto_front::{ParetoFront, ParetoRank}};
 
 #[derive(Serialize, Deserialize)]
-pub struct NeatTrainer{
-    pub members: Vec<GenerationMember<NeatGenome>>,
+#[serde(bound(deserialize = "T: DeserializeOwned"))]
+pub struct NeatTrainer<T = NeatGenome> where T: Genome{
+    pub members: Vec<GenerationMember<T>>,
     pub members_map: HashMap<u64, usize, nohash_hasher::BuildNoHashHasher<u64>>,
     pub configuration: Configuration,
-    pub run_context: RunContext,
+    pub run_context: RunContext<T>,
     has_printed_summary: bool,
     #[serde(skip_serializing,skip_deserializing)]
-    pub event_sender: Option<Sender<FromHostEvent>>,
-    pub config_regulators: Vec<ConfigRegulator>
+    pub event_sender: Option<Sender<FromHostEvent<T>>>,
+    pub config_regulators: Vec<ConfigRegulator>,
+    #[serde(skip_serializing,skip_deserializing)]
+    last_checkpoint_generation: Option<u32>,
+    #[serde(skip_serializing,skip_deserializing)]
+    last_checkpoint_time: Option<Instant>,
+    #[serde(skip_serializing,skip_deserializing)]
+    speciation_stages: Vec<Box<dyn SpeciationStage<T>>>,
+    #[serde(skip_serializing,skip_deserializing)]
+    success_threshold_override: Option<NeatFloat>
 }
 impl NeatTrainer{
     pub fn new(configuration: Configuration) -> Self {
-        let run_context = RunContext::new(configuration.node_genes.len()+1, configuration.speciation_use_best_seed_bank.unwrap_or_default());
+        Self::new_for_genome(configuration)
+    }
+    pub fn try_new(configuration: Configuration) -> Result<Self, ConfigError> {
+        Self::try_new_for_genome(configuration)
+    }
+}
+impl<T> NeatTrainer<T> where T: Genome + Crossover + MutationHistory{
+    pub fn new_for_genome(configuration: Configuration) -> Self {
+        match Self::try_new_for_genome(configuration) {
+            Ok(trainer) => trainer,
+            Err(error) => panic!("{}", error)
+        }
+    }
+    pub fn try_new_for_genome(configuration: Configuration) -> Result<Self, ConfigError> {
+        configuration.validate()?;
+
+        let run_context = RunContext::new_for_genome(configuration.node_genes.len()+1, configuration.speciation_use_best_seed_bank.unwrap_or_default()).with_seed(configuration.seed);
         let regulators = ConfigurationDefaults::get_default_regulators(&configuration);
-        NeatTrainer {
+        Ok(NeatTrainer {
             configuration,
             run_context,
             members: Vec::new(),
             members_map: HashMap::with_capacity_and_hasher(0, BuildNoHashHasher::default()),
             has_printed_summary: false,
             event_sender: None,
-            config_regulators: regulators
-        }
+            config_regulators: regulators,
+            last_checkpoint_generation: None,
+            last_checkpoint_time: None,
+            speciation_stages: Vec::new(),
+            success_threshold_override: None
+        })
     }
     pub fn new_generation(&mut self) {
+        if let Err(error) = self.try_new_generation() {
+            panic!("{}", error)
+        }
+    }
+    /* fails without changing the population when the speciation pipeline names a stage that has not been added */
+    pub fn try_new_generation(&mut self) -> Result<(), ConfigError> {
         {
             optick::next_frame();
 
             if self.has_met_success(){
-                return;
+                return Ok(());
             }
 
             self.set_best_member_and_worst_fitness();
@@ -59,22 +109,17 @@ impl NeatTrainer{
                 self.print_summary(self.configuration.print_summary_number_of_species_to_show);
             }
 
-            Speciation::speciate(&mut self.members, &self.configuration, &mut self.run_context);
+            Speciation::speciate_with_stages(&mut self.members, &self.configuration, &mut self.run_context, &self.speciation_stages)?;
 
             self.recompute_indexes();
 
             let run_context = &mut self.run_context;
             for m in self.members.iter_mut(){
-                
-                let mut mutation_mode = MutationMode::Steady;
-
-                if m.number_of_generations_since_species_improved > 4 {
-                    mutation_mode = MutationMode::Optimistic;
-                }else if m.number_of_generations_since_species_improved > 8{
-                    mutation_mode = MutationMode::FoolHardy;
-                }else if m.number_of_generations_since_species_improved > 10{
-                    mutation_mode = MutationMode::ShootForTheMoon;
-                }
+                let generations_stagnant = match self.configuration.mutation_schedule.basis {
+                    MutationScheduleBasis::Species => m.number_of_generations_since_species_improved,
+                    MutationScheduleBasis::MemberAge => run_context.current_generation.saturating_sub(m.created_generation)
+                };
+                let mutation_mode = self.configuration.mutation_schedule.get_mode(generations_stagnant);
                 
                 m.genome.mutate(&mut self.configuration, run_context, mutation_mode);
             }
@@ -92,10 +137,107 @@ impl NeatTrainer{
         self.send_event(FromHostEvent::ConfigUpdate(self.configuration.clone()));
         self.send_event(FromHostEvent::GenerationChange(self.run_context.current_generation));
         self.send_event(FromHostEvent::RegulatorUpdate(self.config_regulators.clone()));
+        Ok(())
+    }
+    /* with empty stop criteria the run only stops once the success threshold is met */
+    pub fn run<F>(&mut self, fitness_fn: F, stop_criteria: StopCriteria) -> RunReport<T> where F: Fn(&dyn Phenome, &mut FitnessResolver) + Sync, T: NetworkDefinition + Serialize{
+        let started = Instant::now();
+        let start_generation = self.get_current_generation();
+        let previous_success_threshold_override = self.success_threshold_override;
+        if stop_criteria.success_threshold.is_some(){
+            self.success_threshold_override = stop_criteria.success_threshold;
+        }
+
+        let mut best_fitness: Option<NeatFloat> = None;
+        let mut last_improved_generation = start_generation;
+
+        let stop_reason = loop {
+            self.new_generation();
+            self.evaluate_generation(&fitness_fn);
+
+            if let Err(error) = self.checkpoint_if_due(){
+                self.send_event(FromHostEvent::CheckpointError(error.to_string()));
+            }
+
+            let current_generation = self.get_current_generation();
+            let best_fitness_so_far = self.run_context.best_member_so_far.as_ref().and_then(|m| m.genome.get_fitness());
+            if best_fitness_so_far.is_some() && (best_fitness.is_none() || best_fitness_so_far.unwrap() > best_fitness.unwrap()){
+                best_fitness = best_fitness_so_far;
+                last_improved_generation = current_generation;
+            }
+
+            if self.has_met_success(){
+                break StopReason::SuccessThreshold;
+            }
+            if let Some(stop_reason) = stop_criteria.get_stop_reason(current_generation - start_generation, started.elapsed(), current_generation - last_improved_generation){
+                break stop_reason;
+            }
+        };
+        self.success_threshold_override = previous_success_threshold_override;
+
+        RunReport {
+            champion: self.run_context.best_member_so_far.clone(),
+            stop_reason,
+            generations: self.get_current_generation() - start_generation,
+            elapsed: started.elapsed(),
+            generation_stats: self.run_context.last_ten_thousand_generations_stats.clone()
+        }
+    }
+    /* computes the fitness of the current members, new_generation only picks up the best member of the evaluated generation on its next call so it is set here too */
+    pub fn evaluate_generation<F>(&mut self, fitness_fn: &F) where F: Fn(&dyn Phenome, &mut FitnessResolver) + Sync, T: NetworkDefinition{
+        let mut activation_strategy = ActivationStrategies::get_cpu_parallel(self);
+        let mut fitness_setter = FitnessSetter::new();
+        activation_strategy.compute(fitness_fn, &mut fitness_setter);
+        self.commit_fitnesses(fitness_setter);
+    }
+    /* like evaluate_generation but the fitness function gets the phenomes of every member that needs evaluating at once, with the fitness resolver of each at the same index */
+    pub fn evaluate_generation_batch<F>(&mut self, batch_fitness_fn: F) where F: FnMut(&[&dyn Phenome], &mut [FitnessResolver]), T: NetworkDefinition{
+        let mut activation_strategy = ActivationStrategies::get_cpu_batch(self);
+        let mut fitness_setter = FitnessSetter::new();
+        activation_strategy.compute(batch_fitness_fn, &mut fitness_setter);
+        self.commit_fitnesses(fitness_setter);
+    }
+    fn commit_fitnesses(&mut self, fitness_setter: FitnessSetter){
+        fitness_setter.commit(self);
+        self.set_best_member_and_worst_fitness();
+        if self.configuration.multi_objective_enabled{
+            self.update_pareto_front();
+        }
+    }
+    /* keeps the non dominated members seen so far, at most population_size of them preferring the least crowded */
+    fn update_pareto_front(&mut self){
+        let member_ids: HashSet<uuid::Uuid> = self.members.iter().map(|m| m.genome.get_id()).collect();
+        let mut candidates: Vec<GenerationMember<T>> = Vec::with_capacity(self.members.len() + self.run_context.pareto_front.len());
+        let mut candidate_ids = HashSet::new();
+        for member in self.members.iter().chain(self.run_context.pareto_front.iter().filter(|m| !member_ids.contains(&m.genome.get_id()))){
+            if candidate_ids.insert(member.genome.get_id()){
+                candidates.push(member.clone());
+            }
+        }
+
+        let objectives: Vec<&[Objective]> = candidates.iter().map(|m| m.objectives.as_slice()).collect();
+        let ranks = ParetoFront::rank(&objectives);
+        let mut front: Vec<(GenerationMember<T>, ParetoRank)> = candidates.into_iter().zip(ranks).filter(|(_, rank)| rank.front == 0).collect();
+        front.sort_by(|a, b| ParetoFront::compare(&a.1, &b.1));
+        front.truncate(self.configuration.population_size as usize);
+        self.run_context.pareto_front = front.into_iter().map(|(member, _)| member).collect();
+    }
+    pub fn get_pareto_front(&self) -> &Vec<GenerationMember<T>>{
+        &self.run_context.pareto_front
+    }
+    /* writes every member of the pareto front as a network file named {run_name}-pareto-{index}.json */
+    pub fn export_pareto_front<P>(&self, directory: P) -> Result<Vec<PathBuf>, NetworkFileError> where P: AsRef<Path>, T: NetworkDefinition{
+        self.run_context.pareto_front.iter().enumerate().map(|(index, member)| {
+            let path = directory.as_ref().join(format!("{}-pareto-{}.json", self.configuration.run_name, index));
+            NetworkFile::from_member(member).save(&path)?;
+            Ok(path)
+        }).collect()
     }
     pub fn reset(&mut self){
         self.members.clear();
-        self.run_context = RunContext::new(self.configuration.node_genes.len()+1, self.configuration.speciation_use_best_seed_bank.unwrap_or_default());
+        let keep_innovations_on_reset = self.run_context.keep_innovations_on_reset;
+        self.run_context = RunContext::new_for_genome(self.configuration.node_genes.len()+1, self.configuration.speciation_use_best_seed_bank.unwrap_or_default()).with_seed(self.configuration.seed);
+        self.run_context.keep_innovations_on_reset = keep_innovations_on_reset;
         self.recompute_indexes();
 
         self.send_event(FromHostEvent::ConfigUpdate(self.configuration.clone()));
@@ -104,16 +246,16 @@ impl NeatTrainer{
     }
     pub fn are_all_fitnesses_set(&self) -> bool{
         
-        self.members.iter().all(|m| m.genome.objective_fitness.is_some() && m.genome.objective_fitness.unwrap() >= 0.0)
+        self.members.iter().all(|m| m.genome.get_fitness().is_some() && m.genome.get_fitness().unwrap() >= 0.0)
     }
     pub fn print_summary(&mut self, number_of_species_to_show: usize){
         
         let num_species = self.run_context.species_index.len();
         let mut best_in_latest_generation : Option<NeatFloat> = None;
         for m in &self.members{
-            if m.genome.objective_fitness.is_some() {
-                if best_in_latest_generation.is_none() || m.genome.objective_fitness.unwrap() > best_in_latest_generation.unwrap(){
-                    best_in_latest_generation = m.genome.objective_fitness;
+            if m.genome.get_fitness().is_some() {
+                if best_in_latest_generation.is_none() || m.genome.get_fitness().unwrap() > best_in_latest_generation.unwrap(){
+                    best_in_latest_generation = m.genome.get_fitness();
                 }
             }
         }
@@ -133,11 +275,11 @@ impl NeatTrainer{
         );
     
         if number_of_species_to_show > 0 && self.run_context.species_index.len() > 0{
-            let mut species = self.run_context.species_index.iter().map(|s| s.1.clone()).collect::<Vec<Species>>();
+            let mut species = self.run_context.species_index.iter().map(|s| s.1.clone()).collect::<Vec<Species<T>>>();
             species.sort();
             let mut best_species = self.run_context.species_index.values().find(|_a| true).unwrap();
             for (_n, species) in &self.run_context.species_index{
-                if species.objective_fitness.average > best_species.objective_fitness.average {
+                if species.raw_objective_fitness.average > best_species.raw_objective_fitness.average {
                     best_species = &species;
                 }
             }
@@ -147,7 +289,7 @@ impl NeatTrainer{
                 println!("id: {}\tmem no: {}\tav.fit: {:.4}\t ofs'g:{}\tdis f'm b'st:{:.2}\tadj-fit:{:.4}",
                 species.id.simple(),
                 species.members.len(),
-                species.objective_fitness.average,
+                species.raw_objective_fitness.average,
                 species.allowed_number_of_offspring_based_on_objective_fitness,
                 distance_from_best,
                 species.adjusted_average_objective_fitness_based_on_member_range
@@ -161,10 +303,10 @@ impl NeatTrainer{
             return false;
         }
         let best = self.run_context.best_member_so_far.as_ref().unwrap();
-        if best.genome.objective_fitness.is_none(){
+        if best.genome.get_fitness().is_none(){
             return false;
         }
-        if best.genome.objective_fitness.unwrap_or_default() >= self.configuration.success_threshold{
+        if best.genome.get_fitness().unwrap_or_default() >= self.get_success_threshold(){
             if self.configuration.print_summary_interval.is_some() && !self.has_printed_summary{
                 self.print_summary(999);
                 self.has_printed_summary = true;
@@ -173,12 +315,22 @@ impl NeatTrainer{
         }
         return false;
     }
-    pub fn get_best_member_so_far(&mut self) -> &Option<GenerationMember<NeatGenome>> {
+    pub fn get_best_member_so_far(&mut self) -> &Option<GenerationMember<T>> {
         &self.run_context.best_member_so_far
     }
-    pub fn get_best_member_in_last_gen(&mut self) -> &Option<GenerationMember<NeatGenome>> {
+    pub fn get_best_member_in_last_gen(&mut self) -> &Option<GenerationMember<T>> {
         &self.run_context.get_best_member_in_this_gen
     }
+    /* the threshold of the current run or curriculum stage, which is not saved with the configuration */
+    pub fn get_success_threshold(&self) -> NeatFloat{
+        self.success_threshold_override.unwrap_or(self.configuration.success_threshold)
+    }
+    pub fn get_success_threshold_override(&self) -> Option<NeatFloat>{
+        self.success_threshold_override
+    }
+    pub fn set_success_threshold_override(&mut self, value: Option<NeatFloat>){
+        self.success_threshold_override = value;
+    }
     pub fn get_current_generation(&self) -> u32{
         self.run_context.current_generation
     }
@@ -194,10 +346,10 @@ impl NeatTrainer{
                 self.run_context.get_best_member_in_this_gen = Some(m.clone());
             } 
     
-            let best_member_ever_fitness= self.run_context.best_member_so_far.as_ref().unwrap().genome.objective_fitness.unwrap();
-            let best_member_this_gen_fitness = self.run_context.get_best_member_in_this_gen.as_ref().unwrap().genome.objective_fitness.unwrap();
-            if m.genome.objective_fitness.is_some() {
-                let fitness =  m.genome.objective_fitness.unwrap();
+            let best_member_ever_fitness= self.run_context.best_member_so_far.as_ref().unwrap().genome.get_fitness().unwrap();
+            let best_member_this_gen_fitness = self.run_context.get_best_member_in_this_gen.as_ref().unwrap().genome.get_fitness().unwrap();
+            if m.genome.get_fitness().is_some() {
+                let fitness =  m.genome.get_fitness().unwrap();
 
                 if self.run_context.worst_objective_fitness_so_far.is_none() || fitness < self.run_context.worst_objective_fitness_so_far.unwrap(){
                     self.run_context.worst_objective_fitness_so_far = Some(fitness);
@@ -214,37 +366,98 @@ impl NeatTrainer{
             }
         }
     }
-    pub fn save(&mut self) -> std::io::Result<()>{
-        let json = serde_json::to_string(&self).unwrap();
-        let mut file = File::create(format!("{}{}-{}.neatrun", self.configuration.run_save_directory, self.configuration.run_name, self.get_current_generation()))?;
-        file.write_all(json.as_bytes())
+    pub fn save(&mut self) -> io::Result<()> where T: Serialize{
+        let path = format!("{}{}-{}.neatrun", self.configuration.run_save_directory, self.configuration.run_name, self.get_current_generation());
+        Checkpoint::write(self, Path::new(&path), self.configuration.run_save_format)
+    }
+    pub fn load(name:String) -> Result<NeatTrainer<T>, LoadError> where T: DeserializeOwned{
+        Self::load_with_speciation_stages(name, Vec::new())
+    }
+    /* custom stages are not saved, so a run whose speciation pipeline names one needs it again when loaded */
+    pub fn load_with_speciation_stages(name:String, stages: Vec<Box<dyn SpeciationStage<T>>>) -> Result<NeatTrainer<T>, LoadError> where T: DeserializeOwned{
+        Self::read_with_speciation_stages(Path::new(&name), stages)
+    }
+    fn read_with_speciation_stages(path: &Path, stages: Vec<Box<dyn SpeciationStage<T>>>) -> Result<NeatTrainer<T>, LoadError> where T: DeserializeOwned{
+        let mut trainer: NeatTrainer<T> = Checkpoint::read(path)?;
+        let stage_names: Vec<&str> = stages.iter().map(|s| s.get_name()).collect();
+        trainer.configuration.validate_speciation_pipeline(&stage_names).map_err(LoadError::Configuration)?;
+        for stage in stages{
+            trainer.add_speciation_stage(stage);
+        }
+        Ok(trainer)
+    }
+    pub fn checkpoint_if_due(&mut self) -> io::Result<Option<PathBuf>> where T: Serialize{
+        let now = Instant::now();
+        let generation = self.get_current_generation();
+        let last_checkpoint_time = *self.last_checkpoint_time.get_or_insert(now);
+
+        let due_by_generation = self.configuration.checkpoint_every_generations.is_some() && generation % self.configuration.checkpoint_every_generations.unwrap() == 0;
+        let due_by_time = self.configuration.checkpoint_every_minutes.is_some() && now - last_checkpoint_time >= Duration::from_secs(60 * self.configuration.checkpoint_every_minutes.unwrap() as u64);
+        if generation == 0 || self.last_checkpoint_generation == Some(generation) || !(due_by_generation || due_by_time){
+            return Ok(None);
+        }
+
+        let directory = Checkpoint::directory(&self.configuration.run_save_directory).to_path_buf();
+        let path = directory.join(Checkpoint::file_name(&self.configuration.run_name, generation));
+        Checkpoint::write(self, &path, self.configuration.run_save_format)?;
+        self.last_checkpoint_generation = Some(generation);
+        self.last_checkpoint_time = Some(now);
+
+        if let Some(keep_last) = self.configuration.checkpoint_keep_last{
+            Checkpoint::rotate(&directory, &self.configuration.run_name, keep_last)?;
+        }
+        Ok(Some(path))
+    }
+    pub fn resume_latest<P>(directory: P) -> Result<ResumedRun<T>, LoadError> where P: AsRef<Path>, T: DeserializeOwned{
+        Self::resume_latest_with_speciation_stages(directory, Vec::new())
     }
-    pub fn load(name:String) -> Option<NeatTrainer>{
-        let mut json = String::new();
-        let file_result = File::open(name);
-        file_result.unwrap().read_to_string(&mut json).unwrap();
-        let result = serde_json::from_str(&mut json);
-        if result.is_ok(){
-            return result.unwrap()
+    /* unreadable checkpoints are skipped for an older one, a configuration error is returned since every checkpoint of the run would have it */
+    pub fn resume_latest_with_speciation_stages<P>(directory: P, mut stages: Vec<Box<dyn SpeciationStage<T>>>) -> Result<ResumedRun<T>, LoadError> where P: AsRef<Path>, T: DeserializeOwned{
+        let paths = Checkpoint::list(directory.as_ref(), None).map_err(|e| LoadError::Read { path: directory.as_ref().display().to_string(), message: e.to_string() })?;
+        let stage_names: Vec<&str> = stages.iter().map(|s| s.get_name()).collect();
+        let mut skipped_checkpoints = Vec::new();
+        for path in paths.into_iter().rev(){
+            match Checkpoint::read::<T>(&path) {
+                Ok(mut trainer) => {
+                    trainer.configuration.validate_speciation_pipeline(&stage_names).map_err(LoadError::Configuration)?;
+                    for stage in stages.drain(..){
+                        trainer.add_speciation_stage(stage);
+                    }
+                    return Ok(ResumedRun { trainer: Some(trainer), skipped_checkpoints })
+                },
+                Err(error) => skipped_checkpoints.push((path, error))
+            }
         }
-        println!("Error Loading Saved Run: {}", result.err().unwrap());
-        return None
+        Ok(ResumedRun { trainer: None, skipped_checkpoints })
     }
-    fn send_event(&self, event: FromHostEvent){
+    pub fn send_event(&self, event: FromHostEvent<T>){
         if self.event_sender.is_some(){
             let _ = self.event_sender.as_ref().unwrap().send(event);
         }
     }
-    fn recompute_indexes(&mut self){
+    pub fn recompute_indexes(&mut self){
         
         self.members_map.clear();
         for i in 0..self.members.len(){
             let member = &self.members[i];
-            self.members_map.insert(member.genome.id.as_u64_pair().0, i);
+            self.members_map.insert(member.genome.get_id().as_u64_pair().0, i);
         }
     }
 
     pub fn set_config_regulators(&mut self, regulators: Vec<ConfigRegulator>) {
         self.config_regulators = regulators
     }
+    /* registers a stage that runs wherever its name is in configuration.speciation_pipeline, replacing a built in stage with the same name */
+    pub fn add_speciation_stage(&mut self, stage: Box<dyn SpeciationStage<T>>) {
+        self.speciation_stages.retain(|s| s.get_name() != stage.get_name());
+        self.speciation_stages.push(stage);
+    }
+    pub fn insert_speciation_stage(&mut self, index: usize, stage: Box<dyn SpeciationStage<T>>) {
+        self.configuration.speciation_pipeline.insert(index, stage.get_name().to_string());
+        self.add_speciation_stage(stage);
+    }
+    pub fn remove_speciation_stage(&mut self, name: &str) {
+        self.configuration.speciation_pipeline.retain(|s| s != name);
+        self.speciation_stages.retain(|s| s.get_name() != name);
+    }
 }
@@ -1,11 +1,15 @@
-use crate::neat::{trainer::{configuration::Configuration, config_regulators::config_regulator::ConfigRegulator}, population::GenerationMember, genome::neat::NeatGenome};
+use crate::neat::{trainer::{configuration::Configuration, config_regulators::config_regulator::ConfigRegulator}, population::GenerationMember, genome::{neat::NeatGenome, genome::Genome}};
+use crate::neat::trainer::fitness::fitness_failure::FitnessFailure;
 use super::models::run_stats::RunStats;
-pub enum FromHostEvent {
-    BestNewGenome(GenerationMember<NeatGenome>),
+pub enum FromHostEvent<T = NeatGenome> where T: Genome {
+    BestNewGenome(GenerationMember<T>),
     ConfigUpdate(Configuration),
     RegulatorUpdate(Vec<ConfigRegulator>),
     GenerationChange(u32),
     RunStats(RunStats),
     HitSuccessThreshold(u32),
-    SetRunUntil(u32)
+    SetRunUntil(u32),
+    FitnessFailure(uuid::Uuid, FitnessFailure),
+    CheckpointError(String),
+    CurriculumStageChange(usize, String)
 }
@@ -1,4 +1,4 @@
-use crate::neat::trainer::{neat_trainer::NeatTrainer, generation_stats::GenerationStats};
+use crate::{neat::{trainer::{neat_trainer::NeatTrainer, generation_stats::GenerationStats, fitness::evaluation_times::EvaluationTimes}, genome::genome::Genome}, common::NeatFloat};
 
 use super::species::SpeciesList;
 
@@ -6,14 +6,18 @@ use super::species::SpeciesList;
 pub struct RunStats{
     pub last_ten_thousand_generations_stats: Vec<GenerationStats>,
     pub number_of_species: usize,
-    pub species_list: SpeciesList
+    pub species_list: SpeciesList,
+    pub fitness_cache_hit_rate: NeatFloat,
+    pub evaluation_times: EvaluationTimes
 }
 impl RunStats {
-    pub fn new(trainer: &NeatTrainer) -> Self {
+    pub fn new<T>(trainer: &NeatTrainer<T>) -> Self where T: Genome {
         Self {  
             last_ten_thousand_generations_stats: trainer.run_context.last_ten_thousand_generations_stats.clone(),
             number_of_species: trainer.run_context.species_index.len(),
-            species_list: SpeciesList::new(&trainer.run_context.species_index)
+            species_list: SpeciesList::new(&trainer.run_context.species_index),
+            fitness_cache_hit_rate: trainer.run_context.fitness_cache.get_hit_rate(),
+            evaluation_times: trainer.run_context.evaluation_times.clone()
         }
     }
 }
\ No newline at end of file
@@ -1,5 +1,4 @@
-use hashbrown::HashMap;
-use crate::{neat::population::{self, speciation::species_metric::SpeciesMetric}, common::NeatFloat};
+use crate::{neat::{population::{self, speciation::species_metric::SpeciesMetric}, trainer::run_context::SpeciesIndex, genome::genome::Genome}, common::{NeatFloat, color::Rgb}};
 
 #[derive(Debug)]
 pub struct Species{
@@ -8,23 +7,27 @@ pub struct Species{
     pub allowed_number_of_offspring_based_on_objective_fitness: NeatFloat,
     pub allowed_number_of_offspring_based_on_outcome_novelty: NeatFloat,
     pub species_protected_until_generation: u32,
-    pub color: bevy::prelude::Color,
+    pub color: Rgb,
     pub objective_fitness: SpeciesMetric,
     pub outcome_novelty: SpeciesMetric,
+    pub objective_fitness_variance: SpeciesMetric,
+    pub raw_objective_fitness: SpeciesMetric,
     pub no_of_members: usize
 }
 
 impl Species{
-    pub fn new(species: &population::speciation::species::Species) -> Self {
+    pub fn new<T>(species: &population::speciation::species::Species<T>) -> Self where T: Genome {
         Self{
             id: species.id,
-            color: species.primary.genome.color,
+            color: species.primary.genome.get_color(),
             created_generation: species.created_generation,
             allowed_number_of_offspring_based_on_objective_fitness: species.allowed_number_of_offspring_based_on_objective_fitness,
             allowed_number_of_offspring_based_on_outcome_novelty: species.allowed_number_of_offspring_based_on_outcome_novelty,
             species_protected_until_generation: species.species_protected_until_generation,
             objective_fitness: species.objective_fitness.clone(),
             outcome_novelty: species.outcome_novelty.clone(),
+            objective_fitness_variance: species.objective_fitness_variance.clone(),
+            raw_objective_fitness: species.raw_objective_fitness.clone(),
             no_of_members: species.members.len()
         }
     }
@@ -35,7 +38,7 @@ pub struct SpeciesList{
     pub species_models: Vec<Species>
 }
 impl SpeciesList{
-    pub fn new(species_index: &HashMap<uuid::Uuid, population::speciation::species::Species>) -> Self {
+    pub fn new<T>(species_index: &SpeciesIndex<T>) -> Self where T: Genome {
         let species_models = species_index.values().map(Species::new).collect::<Vec<Species>>();
         Self{
             species_models
@@ -1,17 +1,20 @@
 use std::thread::{self, JoinHandle};
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::time::Duration;
+#[cfg(feature = "sql")]
 use crate::common::event_stream::listeners::sql_listener::{SqlRepositoryEventListener};
 use crate::neat::trainer::config_regulators::config_regulator::ConfigRegulator;
 use crate::neat::trainer::configuration::Configuration;
 use crate::neat::trainer::neat_trainer::NeatTrainer;
+use crate::neat::genome::{genome::Genome, crossover::Crossover, mutation_history::MutationHistory, neat::NeatGenome};
+use serde::{Serialize, de::DeserializeOwned};
 use super::from_host_events::FromHostEvent;
 use super::models::run_stats::RunStats;
 use super::neat_trainer_host_state::NeatTrainerHostState;
 use super::to_host_events::ToHostEvents;
 
-pub struct NeatTrainerHostClient{
-    pub from_host_receiver: Receiver<FromHostEvent>,
+pub struct NeatTrainerHostClient<T = NeatGenome> where T: Genome{
+    pub from_host_receiver: Receiver<FromHostEvent<T>>,
     pub to_host_sender: Sender<ToHostEvents>
 }
 pub struct NeatTrainerHost{
@@ -21,10 +24,10 @@ pub struct NeatTrainerHost{
     pub initial_config_regulators: Vec<ConfigRegulator>
 }
 impl NeatTrainerHost{
-    pub fn new<F>(trainer: NeatTrainer, generation_func: F) -> (Self, NeatTrainerHostClient) where F:Fn(&mut NeatTrainer) + Send + Sync + 'static {
+    pub fn new<T, F>(trainer: NeatTrainer<T>, generation_func: F) -> (Self, NeatTrainerHostClient<T>) where T: Genome + Crossover + MutationHistory + Serialize + DeserializeOwned + 'static, F:Fn(&mut NeatTrainer<T>) + Send + Sync + 'static {
         let initial_configuration = trainer.configuration.clone();
         let initial_config_regulators = trainer.config_regulators.clone();
-        let (to_client_tx, from_host_rx) = channel::<FromHostEvent>();
+        let (to_client_tx, from_host_rx) = channel::<FromHostEvent<T>>();
         let (to_host_tx, to_host_receiver) = channel::<ToHostEvents>();
         let (tx_stop, stop_host_receiver) = channel::<u8>();
 
@@ -48,6 +51,10 @@ impl NeatTrainerHost{
 
                 generation_func(&mut trainer);
 
+                if let Err(error) = trainer.checkpoint_if_due(){
+                    println!("Checkpoint Error: {}", error);
+                }
+
                 if trainer.has_met_success(){
                     runner_state.is_sleeping = true;
                     let _ = to_client_tx.send(FromHostEvent::HitSuccessThreshold(trainer.get_current_generation()));
@@ -72,7 +79,7 @@ impl NeatTrainerHost{
         let _ = self.runner_join_handle.join();
         println!("Host stopped.")
     }
-    fn process_to_host_events(host_state: &mut NeatTrainerHostState, trainer: &mut NeatTrainer, receiver: &Receiver<ToHostEvents>, tx_stop: &Sender<u8>, to_client_tx: &Sender<FromHostEvent>) {
+    fn process_to_host_events<T>(host_state: &mut NeatTrainerHostState, trainer: &mut NeatTrainer<T>, receiver: &Receiver<ToHostEvents>, tx_stop: &Sender<u8>, to_client_tx: &Sender<FromHostEvent<T>>) where T: Genome + Crossover + MutationHistory + Serialize + DeserializeOwned {
         while let Ok(received) = receiver.try_recv(){
             match received{
                 ToHostEvents::UpdateConfig(config) => {trainer.configuration = config;},
@@ -94,6 +101,7 @@ impl NeatTrainerHost{
                 },
                 ToHostEvents::Reset() => {
                     trainer.reset();
+                    #[cfg(feature = "sql")]
                     SqlRepositoryEventListener::reset();
                 },
                 ToHostEvents::UpdateConfigRegulators(regulators) => {
@@ -102,18 +110,19 @@ impl NeatTrainerHost{
                 ToHostEvents::GenericOperation(request) => {
                     match request {
                         super::models::generic_operation::GenericOperation::Load(name) => {
-                            let trainer_opt = NeatTrainer::load(name);
-                            if trainer_opt.is_some(){
-                                *trainer = trainer_opt.unwrap();
-                                trainer.event_sender = Some(to_client_tx.clone());
-                                let _ = to_client_tx.send(FromHostEvent::RunStats(RunStats::new(trainer)));
-                                let _ = to_client_tx.send(FromHostEvent::ConfigUpdate(trainer.configuration.clone()));
-                                let _ = to_client_tx.send(FromHostEvent::SetRunUntil(trainer.get_current_generation()));
-                                let _ = to_client_tx.send(FromHostEvent::GenerationChange(trainer.get_current_generation()));
-                                if trainer.get_best_member_so_far().is_some(){
-                                    let _ = to_client_tx.send(FromHostEvent::BestNewGenome(trainer.get_best_member_so_far().as_ref().unwrap().clone()));
-                                }
-                                
+                            match NeatTrainer::load(name) {
+                                Ok(loaded_trainer) => {
+                                    *trainer = loaded_trainer;
+                                    trainer.event_sender = Some(to_client_tx.clone());
+                                    let _ = to_client_tx.send(FromHostEvent::RunStats(RunStats::new(trainer)));
+                                    let _ = to_client_tx.send(FromHostEvent::ConfigUpdate(trainer.configuration.clone()));
+                                    let _ = to_client_tx.send(FromHostEvent::SetRunUntil(trainer.get_current_generation()));
+                                    let _ = to_client_tx.send(FromHostEvent::GenerationChange(trainer.get_current_generation()));
+                                    if trainer.get_best_member_so_far().is_some(){
+                                        let _ = to_client_tx.send(FromHostEvent::BestNewGenome(trainer.get_best_member_so_far().as_ref().unwrap().clone()));
+                                    }
+                                },
+                                Err(error) => println!("Error Loading Saved Run: {}", error)
                             }
                         },
                         super::models::generic_operation::GenericOperation::Save() => {
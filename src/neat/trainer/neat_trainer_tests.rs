@@ -2,11 +2,17 @@
 use std::borrow::{Borrow, BorrowMut};
 use crate::{
     phenome::Phenome,
-    neat::genome::genome::Genome, 
+    neat::genome::{genome::Genome, crossover::Crossover, mutation_history::MutationHistory, neat::mutation_mode::MutationMode}, 
+    neat::trainer::run_context::RunContext, common::seeded_random::SeededRandom,
     neat::trainer::{configuration::{Configuration, OffSpringMode}, node_conf::NodeConf, fitness::{fitness_setter::{FitnessSetter}, Fitness}}, 
     neat::{genome::neat::{node_gene::NodeGene}, trainer::activation_strategies::activation_strategies::ActivationStrategies}, 
     neat::population::{ GenerationMember}, activation_functions::ActivationFunction, common::NeatFloat};
-use super::{neat_trainer::NeatTrainer, fitness::fitness_resolver::FitnessResolver};
+use super::config_error::ConfigError;
+use super::{neat_trainer::NeatTrainer, fitness::fitness_resolver::FitnessResolver, stop_criteria::{StopCriteria, StopReason}};
+use crate::neat::population::speciation::speciation_stage::SpeciationStage;
+use std::sync::{Arc, atomic::{AtomicUsize, Ordering}};
+#[cfg(test)]
+use super::test_fixtures::{xor_fitness, run_xor_generations};
 #[test]
 #[ignore = "For performance only"]
 fn xor_mutate_test_parallel_times_x(){
@@ -160,4 +166,324 @@ fn speciation_test() {
             member.genome.set_objective_fitness(1.1);
         }
     }
-}
\ No newline at end of file
+}
+#[test]
+fn same_seed_produces_same_run() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(100)
+        .target_species(5)
+        .seed(Some(42));
+
+    let mut left = NeatTrainer::new(configuration.clone());
+    let mut right = NeatTrainer::new(configuration);
+
+    assert_eq!(run_xor_generations(&mut left, 10), run_xor_generations(&mut right, 10));
+}
+
+#[test]
+fn seeded_run_continues_the_same_after_save_and_load() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(100)
+        .target_species(5)
+        .seed(Some(7));
+
+    let mut uninterrupted = NeatTrainer::new(configuration);
+    run_xor_generations(&mut uninterrupted, 5);
+    let saved = serde_json::to_string(&uninterrupted).unwrap();
+    let mut resumed: NeatTrainer = serde_json::from_str(&saved).unwrap();
+
+    assert_eq!(run_xor_generations(&mut uninterrupted, 5), run_xor_generations(&mut resumed, 5));
+}
+
+#[test]
+fn same_seed_produces_same_genomes_through_node_add_mutations() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(50)
+        .target_species(5)
+        .mutation_node_add_probability(1.0)
+        .mutation_connection_add_probability(1.0)
+        .seed(Some(5));
+
+    let mut left = NeatTrainer::new(configuration.clone());
+    let mut right = NeatTrainer::new(configuration);
+    run_xor_generations(&mut left, 5);
+    run_xor_generations(&mut right, 5);
+
+    assert!(left.members.iter().any(|m| m.genome.genes.nodes.len() > 3));
+    let genomes = |trainer: &NeatTrainer| trainer.members.iter().map(|m| serde_json::to_string(&m.genome).unwrap()).collect::<Vec<String>>();
+    assert_eq!(genomes(&left), genomes(&right));
+}
+
+#[test]
+fn try_new_rejects_an_invalid_configuration() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(0);
+
+    assert_eq!(NeatTrainer::try_new(configuration).err(), Some(ConfigError::MustBeGreaterThanZero { property: "population_size" }));
+}
+
+#[derive(Debug, Clone)]
+struct FixedWeightGenome{
+    id: uuid::Uuid,
+    weights: Vec<NeatFloat>,
+    objective_fitness: Option<NeatFloat>,
+    novelty: NeatFloat,
+    allow_mutation: bool
+}
+impl FixedWeightGenome{
+    fn from_weights(weights: Vec<NeatFloat>, random: &mut SeededRandom) -> Self{
+        Self{ id: random.new_uuid(), weights, objective_fitness: None, novelty: 0.0, allow_mutation: true }
+    }
+}
+impl Genome for FixedWeightGenome{
+    fn get_id(&self) -> uuid::Uuid {
+        self.id
+    }
+    fn set_id(&mut self, id: uuid::Uuid) {
+        self.id = id;
+    }
+    fn minimal(_configuration: &Configuration, run_context: &mut RunContext<Self>) -> Self {
+        let weights = (0..3).map(|_| run_context.random.gen_range_f32(-1.0, 1.0)).collect();
+        Self::from_weights(weights, &mut run_context.random)
+    }
+    fn mutate(&mut self, _configuration: &Configuration, run_context: &mut RunContext<Self>, _mutation_mode: MutationMode) {
+        if self.allow_mutation{
+            let index = run_context.random.gen_range_usize(0, self.weights.len());
+            self.weights[index] += run_context.random.standard_normal() * 0.1;
+        }
+    }
+    fn dont_allow_mutation(&mut self) {
+        self.allow_mutation = false;
+    }
+    fn set_objective_fitness(&mut self, value: NeatFloat) {
+        self.objective_fitness = Some(value);
+    }
+    fn set_novelty(&mut self, value: NeatFloat) {
+        self.novelty = value;
+    }
+    fn get_fitness(&self) -> Option<NeatFloat> {
+        self.objective_fitness
+    }
+    fn get_novelty(&self) -> NeatFloat {
+        self.novelty
+    }
+    fn get_genetic_difference_distance_from(&mut self, other_genome: &Self, _stop_when_hit: NeatFloat) -> NeatFloat {
+        self.weights.iter().zip(other_genome.weights.iter()).map(|(a, b)| (a - b).abs()).sum()
+    }
+    fn get_complexity(&self) -> NeatFloat {
+        self.weights.len() as NeatFloat
+    }
+}
+impl Crossover for FixedWeightGenome{
+    fn reproduce(best_performing: &Self, other: &Self, _configuration: &Configuration, random: &mut SeededRandom) -> Self {
+        let weights = best_performing.weights.iter().zip(other.weights.iter()).map(|(a, b)| if random.gen_bool(0.5) { *a } else { *b }).collect();
+        Self::from_weights(weights, random)
+    }
+    fn reproduce_cross_species(best_performing: &Self, other: &Self, configuration: &Configuration, random: &mut SeededRandom) -> Self {
+        Self::reproduce(best_performing, other, configuration, random)
+    }
+}
+impl MutationHistory for FixedWeightGenome{}
+
+#[test]
+fn trainer_runs_with_custom_genome() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 1.0)
+        .population_size(50)
+        .target_species(3)
+        .seed(Some(3));
+    let mut neat_trainer: NeatTrainer<FixedWeightGenome> = NeatTrainer::new_for_genome(configuration);
+
+    for _ in 0..20{
+        neat_trainer.new_generation();
+        for member in neat_trainer.members.iter_mut(){
+            let error: NeatFloat = member.genome.weights.iter().map(|w| (w - 0.5).abs()).sum();
+            member.genome.set_objective_fitness(1.0 / (1.0 + error));
+        }
+    }
+
+    assert!(!neat_trainer.run_context.species_index.is_empty());
+    assert!(neat_trainer.get_best_member_so_far().as_ref().unwrap().genome.get_fitness().unwrap() > 0.5);
+}
+
+#[test]
+fn run_stops_at_max_generations() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(100)
+        .target_species(5)
+        .print_summary_interval(None)
+        .seed(Some(7));
+    let mut neat_trainer = NeatTrainer::new(configuration);
+
+    let report = neat_trainer.run(xor_fitness, StopCriteria::new().max_generations(Some(10)));
+
+    assert_eq!(report.stop_reason, StopReason::MaxGenerations);
+    assert_eq!(report.generations, 10);
+    assert_eq!(neat_trainer.get_current_generation(), 10);
+    assert!(report.champion_fitness().is_some());
+    assert!(!report.generation_stats.is_empty());
+}
+
+#[test]
+fn run_stops_at_success_threshold() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(100)
+        .target_species(5)
+        .print_summary_interval(None)
+        .seed(Some(7));
+    let mut neat_trainer = NeatTrainer::new(configuration);
+
+    let report = neat_trainer.run(xor_fitness, StopCriteria::new().max_generations(Some(100)).success_threshold(Some(1.0)));
+
+    assert_eq!(report.stop_reason, StopReason::SuccessThreshold);
+    assert!(report.generations < 100);
+    assert!(report.champion_fitness().unwrap() >= 1.0);
+    assert_eq!(neat_trainer.configuration.success_threshold, 4.0);
+    assert_eq!(neat_trainer.get_success_threshold(), 4.0);
+}
+
+#[test]
+fn run_with_empty_stop_criteria_stops_at_the_configured_success_threshold() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 1.0)
+        .population_size(100)
+        .target_species(5)
+        .print_summary_interval(None)
+        .seed(Some(7));
+    let mut neat_trainer = NeatTrainer::new(configuration);
+
+    let report = neat_trainer.run(xor_fitness, StopCriteria::new());
+
+    assert_eq!(report.stop_reason, StopReason::SuccessThreshold);
+    assert!(report.champion_fitness().unwrap() >= 1.0);
+}
+
+#[test]
+fn run_sends_checkpoint_errors_as_events() {
+    use super::neat_trainer_host::from_host_events::FromHostEvent;
+
+    /* a file where the checkpoint directory should be makes every checkpoint fail */
+    let blocking_file = std::env::temp_dir().join(format!("neatlib-checkpoint-error-{}", std::process::id()));
+    std::fs::write(&blocking_file, "").unwrap();
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(20)
+        .target_species(2)
+        .print_summary_interval(None)
+        .run_save_directory(format!("{}/", blocking_file.display()))
+        .checkpoint_every_generations(Some(1))
+        .seed(Some(7));
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut neat_trainer = NeatTrainer::new(configuration);
+    neat_trainer.event_sender = Some(sender);
+
+    neat_trainer.run(xor_fitness, StopCriteria::new().max_generations(Some(2)));
+    let _ = std::fs::remove_file(&blocking_file);
+
+    assert_eq!(receiver.try_iter().filter(|e| matches!(e, FromHostEvent::CheckpointError(_))).count(), 2);
+}
+
+#[test]
+fn run_stops_when_best_fitness_stagnates() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(20)
+        .target_species(2)
+        .print_summary_interval(None)
+        .seed(Some(7));
+    let mut neat_trainer = NeatTrainer::new(configuration);
+    let constant_fitness = |_phenotype: &dyn Phenome, fitness_resolver: &mut FitnessResolver| {
+        fitness_resolver.add_objective_fitness_component(0, 1.0, 1.0, 0.5);
+    };
+
+    let report = neat_trainer.run(constant_fitness, StopCriteria::new().max_generations(Some(100)).stagnant_generations(Some(5)));
+
+    assert_eq!(report.stop_reason, StopReason::Stagnation);
+    assert_eq!(report.generations, 6);
+}
+
+struct CountingStage{
+    name: String,
+    runs: Arc<AtomicUsize>
+}
+
+impl<T> SpeciationStage<T> for CountingStage where T: Genome{
+    fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, _members: &mut Vec<GenerationMember<T>>, _configuration: &Configuration, _run_context: &mut RunContext<T>) {
+        self.runs.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn custom_speciation_stages_run_in_the_pipeline() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(50)
+        .target_species(3)
+        .seed(Some(9));
+    let inserted_runs = Arc::new(AtomicUsize::new(0));
+    let replaced_runs = Arc::new(AtomicUsize::new(0));
+    let mut neat_trainer = NeatTrainer::new(configuration);
+    neat_trainer.insert_speciation_stage(0, Box::new(CountingStage { name: "custom_culling".to_string(), runs: inserted_runs.clone() }));
+    neat_trainer.add_speciation_stage(Box::new(CountingStage { name: "add_new_species_during_run".to_string(), runs: replaced_runs.clone() }));
+
+    run_xor_generations(&mut neat_trainer, 5);
+
+    assert_eq!(neat_trainer.configuration.speciation_pipeline[0], "custom_culling");
+    assert_eq!(inserted_runs.load(Ordering::SeqCst), 4);
+    assert_eq!(replaced_runs.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn speciation_stages_can_be_removed() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(50)
+        .target_species(3)
+        .seed(Some(9));
+    let mut neat_trainer = NeatTrainer::new(configuration);
+    neat_trainer.remove_speciation_stage("remove_old_generation_members");
+
+    run_xor_generations(&mut neat_trainer, 3);
+
+    assert!(neat_trainer.members.len() > 50);
+}
+
+#[test]
+fn removed_speciation_stages_are_no_longer_registered() {
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(50)
+        .target_species(3)
+        .seed(Some(9));
+    let mut neat_trainer = NeatTrainer::new(configuration);
+    neat_trainer.insert_speciation_stage(0, Box::new(CountingStage { name: "custom_culling".to_string(), runs: Arc::new(AtomicUsize::new(0)) }));
+    run_xor_generations(&mut neat_trainer, 2);
+
+    neat_trainer.remove_speciation_stage("custom_culling");
+    neat_trainer.configuration.speciation_pipeline.insert(0, "custom_culling".to_string());
+    let members_before = serde_json::to_string(&neat_trainer.members).unwrap();
+
+    assert_eq!(neat_trainer.try_new_generation(), Err(ConfigError::UnknownSpeciationStage("custom_culling".to_string())));
+    assert_eq!(serde_json::to_string(&neat_trainer.members).unwrap(), members_before);
+}
+
+#[test]
+fn saved_runs_with_custom_speciation_stages_need_them_to_load() {
+    use super::load_error::LoadError;
+
+    let path = std::env::temp_dir().join(format!("neatlib-custom-stage-{}.neatrun", std::process::id()));
+    let configuration = Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(50)
+        .target_species(3)
+        .seed(Some(9));
+    let mut neat_trainer = NeatTrainer::new(configuration);
+    neat_trainer.insert_speciation_stage(0, Box::new(CountingStage { name: "custom_culling".to_string(), runs: Arc::new(AtomicUsize::new(0)) }));
+    run_xor_generations(&mut neat_trainer, 2);
+    super::checkpoint::Checkpoint::write(&neat_trainer, &path, neat_trainer.configuration.run_save_format).unwrap();
+
+    let without_stage = NeatTrainer::<crate::neat::genome::neat::NeatGenome>::load(path.display().to_string());
+    assert_eq!(without_stage.err(), Some(LoadError::Configuration(ConfigError::UnknownSpeciationStage("custom_culling".to_string()))));
+
+    let runs = Arc::new(AtomicUsize::new(0));
+    let mut with_stage: NeatTrainer = NeatTrainer::load_with_speciation_stages(path.display().to_string(), vec![Box::new(CountingStage { name: "custom_culling".to_string(), runs: runs.clone() })]).unwrap();
+    run_xor_generations(&mut with_stage, 2);
+    assert_eq!(runs.load(Ordering::SeqCst), 2);
+    let _ = std::fs::remove_file(&path);
+}
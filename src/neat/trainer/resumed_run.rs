@@ -0,0 +1,9 @@
+use std::path::PathBuf;
+use crate::neat::genome::{genome::Genome, neat::NeatGenome};
+use super::{neat_trainer::NeatTrainer, load_error::LoadError};
+
+/* the newest checkpoint that could be loaded, along with the newer ones that were skipped because they could not be read */
+pub struct ResumedRun<T = NeatGenome> where T: Genome{
+    pub trainer: Option<NeatTrainer<T>>,
+    pub skipped_checkpoints: Vec<(PathBuf, LoadError)>
+}
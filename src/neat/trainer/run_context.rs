@@ -1,29 +1,51 @@
-use crate::{neat::genetable::{connect_gene_table::ConnectGeneTable, node_gene_index::NoneGeneIndex}, neat::population::{GenerationMember}, neat::{genome::neat::{NeatGenome}, population::speciation::species::Species}, common::NeatFloat};
+use crate::{neat::genetable::{connect_gene_table::ConnectGeneTable, node_gene_index::NoneGeneIndex}, neat::population::{GenerationMember}, neat::{genome::{genome::Genome, neat::NeatGenome}, population::speciation::species::Species}, common::{NeatFloat, seeded_random::SeededRandom}};
+use std::{collections::hash_map::DefaultHasher, hash::BuildHasherDefault};
 use hashbrown::{HashMap};
 use serde::{Deserialize, Serialize};
-use super::{seed_bank::SeedBank, fitness::novelty_component_store::NoveltyComponentStore, generation_stats::GenerationStats};
+use crate::neat::population::speciation::species_metric::SpeciesMetric;
+use super::{seed_bank::SeedBank, curriculum::curriculum_progress::CurriculumProgress, fitness::{novelty_component_store::NoveltyComponentStore, fitness_cache::FitnessCache, evaluation_times::EvaluationTimes}, generation_stats::GenerationStats};
+
+pub type SpeciesIndex<T = NeatGenome> = HashMap<uuid::Uuid, Species<T>, BuildHasherDefault<DefaultHasher>>;
 
 #[derive(Serialize, Deserialize, Clone)]
-pub struct RunContext{
+pub struct RunContext<T = NeatGenome> where T: Genome{
     pub gene_table: ConnectGeneTable,
     pub node_index: NoneGeneIndex,
-    pub species_index: HashMap<uuid::Uuid, Species>,
+    pub species_index: SpeciesIndex<T>,
     pub new_species_created_on_last_generation: u32,
     pub current_generation: u32,
-    pub best_member_so_far: Option<GenerationMember<NeatGenome>>,
-    pub get_best_member_in_this_gen: Option<GenerationMember<NeatGenome>>,
+    pub best_member_so_far: Option<GenerationMember<T>>,
+    pub get_best_member_in_this_gen: Option<GenerationMember<T>>,
     pub worst_objective_fitness_so_far: Option<NeatFloat>,
-    pub seed_bank: SeedBank<NeatGenome>,
+    pub seed_bank: SeedBank<T>,
     pub novelty_component_store: NoveltyComponentStore,
-    pub last_ten_thousand_generations_stats: Vec<GenerationStats>
+    pub last_ten_thousand_generations_stats: Vec<GenerationStats>,
+    #[serde(default)]
+    pub random: SeededRandom,
+    #[serde(default)]
+    pub fitness_cache: FitnessCache,
+    #[serde(default = "Vec::new")]
+    pub pareto_front: Vec<GenerationMember<T>>,
+    #[serde(default)]
+    pub curriculum_stage: Option<CurriculumProgress>,
+    #[serde(default)]
+    pub evaluation_times: EvaluationTimes,
+    /* set on the islands of an island model, whose gene table and node index are shared and outlive a reset of one island */
+    #[serde(skip)]
+    pub keep_innovations_on_reset: bool
 }
 
 impl RunContext{
     pub fn new(initial_node_count: usize, seed_bank_limit: usize) -> Self{
+        Self::new_for_genome(initial_node_count, seed_bank_limit)
+    }
+}
+impl<T> RunContext<T> where T: Genome{
+    pub fn new_for_genome(initial_node_count: usize, seed_bank_limit: usize) -> Self{
         RunContext{
             gene_table: ConnectGeneTable::new(),
             node_index: NoneGeneIndex::new(initial_node_count),
-            species_index: HashMap::new(),
+            species_index: SpeciesIndex::default(),
             new_species_created_on_last_generation: 0,
             current_generation: 0,
             best_member_so_far: None,
@@ -31,9 +53,19 @@ impl RunContext{
             worst_objective_fitness_so_far: None,
             seed_bank: SeedBank::new(seed_bank_limit),
             novelty_component_store: NoveltyComponentStore::new(),
-            last_ten_thousand_generations_stats: Vec::new()
+            last_ten_thousand_generations_stats: Vec::new(),
+            random: SeededRandom::new(None),
+            fitness_cache: FitnessCache::new(),
+            pareto_front: Vec::new(),
+            curriculum_stage: None,
+            evaluation_times: EvaluationTimes::default(),
+            keep_innovations_on_reset: false
         }
     }
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self{
+        self.random = SeededRandom::new(seed);
+        self
+    }
     pub fn increment_generation(&mut self){
         self.current_generation += 1;
         if self.current_generation > 2 {
@@ -46,9 +78,34 @@ impl RunContext{
     pub fn reset(&mut self){
         self.current_generation = 0;
         self.species_index.clear();
-        self.node_index.clear();
-        self.gene_table.reset();
+        if !self.keep_innovations_on_reset{
+            self.node_index.clear();
+            self.gene_table.reset();
+        }
         self.novelty_component_store.clear();
+        self.fitness_cache.clear();
+        self.pareto_front.clear();
+        self.curriculum_stage = None;
+        self.evaluation_times = EvaluationTimes::default();
         self.last_ten_thousand_generations_stats.clear();
     }
+    /* fitness remembered from an earlier stage was measured on a different task so it is dropped, species restart their improvement tracking */
+    pub fn start_curriculum_stage(&mut self, progress: CurriculumProgress){
+        self.best_member_so_far = None;
+        self.get_best_member_in_this_gen = None;
+        self.worst_objective_fitness_so_far = None;
+        self.seed_bank.seeds.clear();
+        self.seed_bank.lowest_fitness = None;
+        self.fitness_cache.clear();
+        self.pareto_front.clear();
+        for species in self.species_index.values_mut(){
+            species.objective_fitness = SpeciesMetric::new();
+            species.raw_objective_fitness = SpeciesMetric::new();
+            species.objective_fitness.last_generation_improved = self.current_generation;
+            species.raw_objective_fitness.last_generation_improved = self.current_generation;
+            species.stagnant_generation_counter = 0;
+            species.stagnant_objective_fitness = 0.0;
+        }
+        self.curriculum_stage = Some(progress);
+    }
 }
\ No newline at end of file
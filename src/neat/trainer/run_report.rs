@@ -0,0 +1,19 @@
+use std::time::Duration;
+use crate::common::NeatFloat;
+use crate::neat::{genome::{genome::Genome, neat::NeatGenome}, population::GenerationMember};
+use super::{generation_stats::GenerationStats, stop_criteria::StopReason};
+
+#[derive(Debug, Clone)]
+pub struct RunReport<T = NeatGenome> where T: Genome{
+    pub champion: Option<GenerationMember<T>>,
+    pub stop_reason: StopReason,
+    pub generations: u32,
+    pub elapsed: Duration,
+    pub generation_stats: Vec<GenerationStats>
+}
+
+impl<T> RunReport<T> where T: Genome{
+    pub fn champion_fitness(&self) -> Option<NeatFloat>{
+        self.champion.as_ref().and_then(|c| c.genome.get_fitness())
+    }
+}
@@ -1,5 +1,5 @@
 use serde::{Serialize, Deserialize};
-use crate::{common::NeatFloat, neat::trainer::{generation_stats::GenerationStats, run_context::RunContext}};
+use crate::{common::NeatFloat, neat::{trainer::{generation_stats::GenerationStats, run_context::RunContext}, genome::genome::Genome}};
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug)]
 pub struct RunSignals{
@@ -16,7 +16,7 @@ pub struct RunSignals{
 }
 
 impl RunSignals{
-    pub fn new(run_context: &RunContext) -> Self{
+    pub fn new<T: Genome>(run_context: &RunContext<T>) -> Self{
         let generation_stats = &run_context.last_ten_thousand_generations_stats;
         let number_of_species = run_context.species_index.len() as NeatFloat;
         if generation_stats.len() == 0{
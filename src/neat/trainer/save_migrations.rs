@@ -0,0 +1,99 @@
+use serde_json::{Map, Value};
+use super::load_error::LoadError;
+
+type Migration = fn(&mut Value) -> Result<(), String>;
+
+/*
+MIGRATIONS[n] upgrades a saved run from version n to n + 1, so adding a migration bumps the
+current version. Migrations work on the json value so they only need to know the shape of the
+old data, not the old types. A save written by the version being migrated from goes in save_fixtures.
+Fields that only need their default rely on serde defaults instead of a migration.
+
+version 0: json written before saves had a header, genome colors are bevy colors.
+version 1: header added, genome colors are plain rgb, species and species members keep a raw_objective_fitness.
+*/
+const MIGRATIONS: &[Migration] = &[
+    from_unversioned
+];
+
+pub struct SaveMigrations;
+
+impl SaveMigrations{
+    pub fn current_version() -> u32{
+        MIGRATIONS.len() as u32
+    }
+    pub fn migrate(value: &mut Value, version: u32) -> Result<(), LoadError>{
+        if version > Self::current_version(){
+            return Err(LoadError::UnsupportedVersion { version, current_version: Self::current_version() });
+        }
+        for (from_version, migration) in MIGRATIONS.iter().enumerate().skip(version as usize){
+            migration(value).map_err(|message| LoadError::Migration { version: from_version as u32, message })?;
+        }
+        Ok(())
+    }
+}
+
+fn from_unversioned(value: &mut Value) -> Result<(), String>{
+    bevy_colors_to_rgb(value)?;
+    add_raw_objective_fitness(value)
+}
+
+fn bevy_colors_to_rgb(value: &mut Value) -> Result<(), String>{
+    match value {
+        Value::Object(map) => {
+            if let Some(color) = map.get_mut("color"){
+                if let Some(rgb) = bevy_color_to_rgb(color)?{
+                    *color = rgb;
+                }
+            }
+            for (_, child) in map.iter_mut(){
+                bevy_colors_to_rgb(child)?;
+            }
+        },
+        Value::Array(items) => {
+            for child in items.iter_mut(){
+                bevy_colors_to_rgb(child)?;
+            }
+        },
+        _ => {}
+    }
+    Ok(())
+}
+
+fn bevy_color_to_rgb(color: &Value) -> Result<Option<Value>, String>{
+    let Value::Object(variant) = color else {
+        return Ok(None);
+    };
+    let components = match variant.get("Rgba").or_else(|| variant.get("RgbaLinear")) {
+        Some(Value::Object(components)) => components,
+        Some(_) => return Err("bevy color is not an object".to_string()),
+        None if variant.contains_key("Hsla") => return Err("hsla colors are not supported".to_string()),
+        None => return Ok(None)
+    };
+    let mut rgb = Map::new();
+    for channel in ["red", "green", "blue"]{
+        let channel_value = components.get(channel).ok_or_else(|| format!("bevy color is missing {}", channel))?;
+        rgb.insert(channel.to_string(), channel_value.clone());
+    }
+    Ok(Some(Value::Object(rgb)))
+}
+
+/* without shaping the raw fitness is the objective fitness that was saved */
+fn add_raw_objective_fitness(value: &mut Value) -> Result<(), String>{
+    let species_index = value.get_mut("run_context").and_then(|c| c.get_mut("species_index")).and_then(|s| s.as_object_mut()).ok_or("saved run has no species index")?;
+    for species in species_index.values_mut(){
+        let species = species.as_object_mut().ok_or("saved species is not an object")?;
+        if !species.contains_key("raw_objective_fitness"){
+            let objective_fitness = species.get("objective_fitness").cloned().ok_or("saved species has no objective fitness")?;
+            species.insert("raw_objective_fitness".to_string(), objective_fitness);
+        }
+        for member in species.get_mut("members").and_then(|m| m.as_array_mut()).ok_or("saved species has no members")?.iter_mut(){
+            let member = member.as_object_mut().ok_or("saved species member is not an object")?;
+            if !member.contains_key("raw_objective_fitness"){
+                let objective_fitness = member.get("objective_fitness").cloned().ok_or("saved species member has no objective fitness")?;
+                member.insert("raw_objective_fitness".to_string(), objective_fitness);
+            }
+        }
+    }
+    Ok(())
+}
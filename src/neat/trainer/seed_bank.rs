@@ -45,4 +45,9 @@ impl<T> SeedBank<T> where T: Genome ,T: Clone{
             }
         }
     }
+    /* after the seeds have been re-evaluated their order and the lowest fitness may have changed */
+    pub fn refresh_lowest_fitness(&mut self){
+        self.seeds.sort();
+        self.lowest_fitness = self.seeds.iter().last().and_then(|s| s.genome.get_fitness());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,62 @@
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::common::NeatFloat;
+
+/*
+A run stops as soon as any of the criteria that are set is met. When success_threshold is None the
+configuration's success_threshold is used, so with no criteria set a run only stops once that
+threshold is met. The threshold only applies to the run and is not written into the configuration.
+*/
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StopCriteria{
+    pub max_generations: Option<u32>,
+    pub max_duration: Option<Duration>,
+    pub success_threshold: Option<NeatFloat>,
+    pub stagnant_generations: Option<u32>
+}
+
+#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason{
+    MaxGenerations,
+    MaxDuration,
+    SuccessThreshold,
+    Stagnation,
+    CurriculumCompleted
+}
+
+impl StopCriteria{
+    pub fn new() -> Self{
+        Self::default()
+    }
+    pub fn max_generations(mut self, value: Option<u32>) -> Self{
+        self.max_generations = value;
+        self
+    }
+    pub fn max_duration(mut self, value: Option<Duration>) -> Self{
+        self.max_duration = value;
+        self
+    }
+    pub fn success_threshold(mut self, value: Option<NeatFloat>) -> Self{
+        self.success_threshold = value;
+        self
+    }
+    pub fn stagnant_generations(mut self, value: Option<u32>) -> Self{
+        self.stagnant_generations = value;
+        self
+    }
+    /* success_threshold is left to the caller as it depends on how the fitness is compared */
+    pub fn get_stop_reason(&self, generations: u32, elapsed: Duration, generations_since_improvement: u32) -> Option<StopReason>{
+        if self.max_generations.is_some() && generations >= self.max_generations.unwrap(){
+            return Some(StopReason::MaxGenerations);
+        }
+        if self.max_duration.is_some() && elapsed >= self.max_duration.unwrap(){
+            return Some(StopReason::MaxDuration);
+        }
+        if self.stagnant_generations.is_some() && generations_since_improvement >= self.stagnant_generations.unwrap(){
+            return Some(StopReason::Stagnation);
+        }
+        None
+    }
+}
@@ -0,0 +1,49 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::{phenome::Phenome, common::NeatFloat};
+use super::{configuration::Configuration, node_conf::NodeConf, neat_trainer::NeatTrainer, activation_strategies::activation_strategies::ActivationStrategies, fitness::{fitness_resolver::FitnessResolver, fitness_setter::FitnessSetter}};
+
+/* the small xor run the trainer feature tests start from, every test picks its own seed */
+pub fn xor_configuration(seed: u64) -> Configuration{
+    Configuration::neat(NodeConf::simple(2, 1), 4.0)
+        .population_size(50)
+        .target_species(5)
+        .seed(Some(seed))
+}
+
+pub fn xor_fitness(phenotype: &dyn Phenome, fitness_resolver: &mut FitnessResolver){
+    let xor_results= &[[0.0, 0.0, 0.0], [0.0, 1.0, 1.0], [1.0, 0.0, 1.0], [1.0, 1.0, 0.0]];
+
+    let mut fitness_component = 0;
+    for test in xor_results{
+        let result = phenotype.activate(&vec![test[0], test[1]])[0];
+        let expected_result = test[2];
+        fitness_resolver.add_objective_fitness_component_with_novelty(fitness_component, 1.0, expected_result, result, 100);
+        fitness_component +=1;
+    }
+}
+
+pub fn run_xor_generations(neat_trainer: &mut NeatTrainer, generations: u32) -> Vec<(uuid::Uuid, NeatFloat)>{
+    for _ in 0..generations{
+        neat_trainer.new_generation();
+        let mut activation_strategy = ActivationStrategies::get_cpu_parallel(neat_trainer);
+        let mut fitness_setter = FitnessSetter::new();
+        activation_strategy.compute(xor_fitness, &mut fitness_setter);
+        fitness_setter.commit(neat_trainer);
+    }
+    neat_trainer.members.iter().map(|m| (m.genome.id, m.genome.objective_fitness.unwrap())).collect()
+}
+
+/* returns how many times the fitness function was called along with the trainer */
+pub fn run_counting_xor_generations(configuration: Configuration, generations: u32) -> (usize, NeatTrainer){
+    let evaluations = AtomicUsize::new(0);
+    let counting_fitness = |phenotype: &dyn Phenome, fitness_resolver: &mut FitnessResolver| {
+        evaluations.fetch_add(1, Ordering::SeqCst);
+        xor_fitness(phenotype, fitness_resolver);
+    };
+    let mut neat_trainer = NeatTrainer::new(configuration);
+    for _ in 0..generations{
+        neat_trainer.new_generation();
+        neat_trainer.evaluate_generation(&counting_fitness);
+    }
+    (evaluations.load(Ordering::SeqCst), neat_trainer)
+}
@@ -5,4 +5,8 @@ use crate::common::NeatFloat;
 pub trait Phenome{
     fn activate(&self, sensor_values: &Vec<NeatFloat>) -> Vec<NeatFloat>;
     fn get_id(&self) -> Uuid;
+    /* keeps the node values in state between calls so recurrent connections carry over, phenomes without state just activate */
+    fn activate_with_state(&self, sensor_values: &Vec<NeatFloat>, _state: &mut Vec<NeatFloat>) -> Vec<NeatFloat>{
+        self.activate(sensor_values)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,15 @@
+use bevy::prelude::Color;
+use bevy_egui::egui::Color32;
+use crate::common::color::Rgb;
+
+impl From<Rgb> for Color{
+    fn from(rgb: Rgb) -> Self {
+        Color::rgb(rgb.red, rgb.green, rgb.blue)
+    }
+}
+
+impl From<Rgb> for Color32{
+    fn from(rgb: Rgb) -> Self {
+        Color32::from_rgb((rgb.red * 255.0) as u8, (rgb.green * 255.0) as u8, (rgb.blue * 255.0) as u8)
+    }
+}
@@ -1,2 +1,3 @@
 pub mod renderer;
-pub mod plugins;
\ No newline at end of file
+pub mod plugins;
+pub mod color;
\ No newline at end of file
@@ -103,6 +103,17 @@ fn show_menu(mut egui_ctx: ResMut<EguiContext>, mut state: ResMut<NeatSettingsGu
                 let member = best_so_far.as_ref().unwrap();
                 ui.label(format!("Best: {:.4}", member.genome.objective_fitness.unwrap()));
             }
+            if trainer_state.configuration.fitness_cache_enabled{
+                ui.label(format!("Cache hits: {:.0}%", trainer_state.fitness_cache_hit_rate * 100.0));
+            }
+            let evaluation_times = &trainer_state.evaluation_times;
+            if evaluation_times.count > 0{
+                ui.label(format!("Eval ms median: {:.1} p90: {:.1} max: {:.1} over budget: {}",
+                    evaluation_times.median.as_secs_f64() * 1000.0,
+                    evaluation_times.p90.as_secs_f64() * 1000.0,
+                    evaluation_times.max.as_secs_f64() * 1000.0,
+                    evaluation_times.over_budget));
+            }
         });
     });
 }
@@ -146,14 +157,35 @@ fn neat_settings(
                         ui.selectable_value(&mut trainer_state.configuration.speciation_offspring_mode, OffSpringMode::AdjustedMemberRange, "AdjustedMemberRange");
                         ui.selectable_value(&mut trainer_state.configuration.speciation_offspring_mode, OffSpringMode::AdjustedSpeciesRange, "AdjustedSpeciesRange");
                         ui.selectable_value(&mut trainer_state.configuration.speciation_offspring_mode, OffSpringMode::Average, "AverageFitness");
+                        ui.selectable_value(&mut trainer_state.configuration.speciation_offspring_mode, OffSpringMode::FitnessSharing, "FitnessSharing");
                     }
                 );
                 ui.end_row();
+                add_slider_row(ui, "new_species_min_offspring", &mut trainer_state.configuration.speciation_new_species_min_offspring, 0..=20);
                 add_slider_row(ui, "speciation_offspring_outcome_novelty_weight", &mut trainer_state.configuration.speciation_offspring_outcome_novelty_weight, 0.0..=1.0);
 
+                combo_box_enum_row(ui, &mut trainer_state.configuration.speciation_selection_strategy, "selection_strategy");
+                add_slider_row(ui, "tournament_size", &mut trainer_state.configuration.speciation_tournament_size, 1..=20);
+                add_slider_row(ui, "truncation_ratio", &mut trainer_state.configuration.speciation_truncation_ratio, 0.01..=1.0);
+
                 ui.label("preserve_elite");
                 ui.checkbox(&mut trainer_state.configuration.speciation_preserve_elite, "");
                 ui.end_row();
+
+                ui.label("fitness_cache_enabled");
+                ui.checkbox(&mut trainer_state.configuration.fitness_cache_enabled, "");
+                ui.end_row();
+
+                add_slider_row(ui, "fitness_evaluations_per_genome", &mut trainer_state.configuration.fitness_evaluations_per_genome, 1..=20);
+                combo_box_enum_row(ui, &mut trainer_state.configuration.fitness_aggregation, "fitness_aggregation");
+                combo_box_enum_row(ui, &mut trainer_state.configuration.fitness_shaping, "fitness_shaping");
+                combo_box_enum_row(ui, &mut trainer_state.configuration.fitness_invalid_policy, "fitness_invalid_policy");
+                add_selectable_value(ui, "fitness_invalid_penalty", &mut trainer_state.configuration.fitness_invalid_penalty);
+                add_selectable_value(ui, "fitness_over_budget_penalty", &mut trainer_state.configuration.fitness_over_budget_penalty);
+
+                ui.label("multi_objective_enabled");
+                ui.checkbox(&mut trainer_state.configuration.multi_objective_enabled, "");
+                ui.end_row();
            
             });
         });
@@ -203,6 +235,20 @@ fn neat_settings(
             });
         });
         
+        ui.collapsing("Mutation schedule", |ui| {
+            let schedule = &mut trainer_state.configuration.mutation_schedule;
+            table(ui, |ui| {
+                combo_box_enum_row(ui, &mut schedule.basis, "basis");
+                add_slider_row(ui, "optimistic_after_generations", &mut schedule.optimistic_after_generations, 0..=1000);
+                add_slider_row(ui, "fool_hardy_after_generations", &mut schedule.fool_hardy_after_generations, 0..=1000);
+                add_slider_row(ui, "shoot_for_the_moon_after_generations", &mut schedule.shoot_for_the_moon_after_generations, 0..=1000);
+                add_slider_row(ui, "steady_structural_mutations", &mut schedule.steady_structural_mutations, 1..=50);
+                add_slider_row(ui, "optimistic_structural_mutations", &mut schedule.optimistic_structural_mutations, 1..=50);
+                add_slider_row(ui, "fool_hardy_structural_mutations", &mut schedule.fool_hardy_structural_mutations, 1..=50);
+                add_slider_row(ui, "shoot_for_the_moon_structural_mutations", &mut schedule.shoot_for_the_moon_structural_mutations, 1..=50);
+            });
+        });
+
         ui.collapsing("Regulators", |ui| {
             for (index, regulator ) in &mut trainer_state.config_regulators.iter_mut().enumerate(){
                 ui.separator();
@@ -365,6 +411,8 @@ pub fn add_species_headers(ui: &mut Ui){
     ui.label(RichText::new("Id").font(FontId::proportional(font_size)));
     ui.label(RichText::new("Members").font(FontId::proportional(font_size)));
     ui.label(RichText::new("Av. Fit").font(FontId::proportional(font_size)));
+    ui.label(RichText::new("Av. Shaped").font(FontId::proportional(font_size)));
+    ui.label(RichText::new("Av. Fit Var.").font(FontId::proportional(font_size)));
     ui.label(RichText::new("Av. Nov").font(FontId::proportional(font_size)));
     ui.label(RichText::new("Fit. Allowed Offspring").font(FontId::proportional(font_size)));
     ui.label(RichText::new("Nov. Allowed Offspring").font(FontId::proportional(font_size)));
@@ -376,13 +424,12 @@ pub fn add_species_row(ui: &mut Ui, species: &crate::neat::trainer::neat_trainer
     let mut cell = |text: String, color: Color32| {
         ui.label(RichText::new(text).font(FontId::proportional(font_size)).color(color));
     };
-    let r = (species.color.r() * 258.0) as u8;
-    let g = (species.color.g() * 258.0) as u8;
-    let b = (species.color.b() * 258.0) as u8;
-    let color = Color32::from_rgb(r, g, b);
+    let color = Color32::from(species.color);
     cell(format!("{}", species.id), color);
     cell(format!("{}", species.no_of_members), color);
+    cell(format!("{:.4}", species.raw_objective_fitness.average), color);
     cell(format!("{:.4}", species.objective_fitness.average), color);
+    cell(format!("{:.4}", species.objective_fitness_variance.average), color);
     cell(format!("{:.8}", species.outcome_novelty.average), color);
     cell(format!("{:.4}", species.allowed_number_of_offspring_based_on_objective_fitness), color);
     cell(format!("{:.4}", species.allowed_number_of_offspring_based_on_outcome_novelty), color);
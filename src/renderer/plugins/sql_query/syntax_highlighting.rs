@@ -38,7 +38,7 @@ pub fn highlight(ctx: &bevy_egui::egui::Context, theme: &CodeTheme, code: &str,
 
 // ----------------------------------------------------------------------------
 
-#[cfg(not(feature = "syntect"))]
+#[cfg(not(feature = "syntax_highlighting"))]
 #[derive(Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[derive(enum_map::Enum)]
@@ -51,7 +51,7 @@ enum TokenType {
     Whitespace,
 }
 
-#[cfg(feature = "syntect")]
+#[cfg(feature = "syntax_highlighting")]
 #[derive(Clone, Copy, Hash, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 enum SyntectTheme {
@@ -64,7 +64,7 @@ enum SyntectTheme {
     SolarizedLight,
 }
 
-#[cfg(feature = "syntect")]
+#[cfg(feature = "syntax_highlighting")]
 impl SyntectTheme {
     fn all() -> impl ExactSizeIterator<Item = Self> {
         [
@@ -122,10 +122,10 @@ impl SyntectTheme {
 pub struct CodeTheme {
     dark_mode: bool,
 
-    #[cfg(feature = "syntect")]
+    #[cfg(feature = "syntax_highlighting")]
     syntect_theme: SyntectTheme,
 
-    #[cfg(not(feature = "syntect"))]
+    #[cfg(not(feature = "syntax_highlighting"))]
     formats: enum_map::EnumMap<TokenType, bevy_egui::egui::TextFormat>,
 }
 
@@ -165,7 +165,7 @@ impl CodeTheme {
     }
 }
 
-#[cfg(feature = "syntect")]
+#[cfg(feature = "syntax_highlighting")]
 impl CodeTheme {
     pub fn dark() -> Self {
         Self {
@@ -192,7 +192,7 @@ impl CodeTheme {
     }
 }
 
-#[cfg(not(feature = "syntect"))]
+#[cfg(not(feature = "syntax_highlighting"))]
 impl CodeTheme {
     pub fn dark() -> Self {
         let font_id = bevy_egui::egui::FontId::monospace(10.0);
@@ -215,7 +215,7 @@ impl CodeTheme {
         use bevy_egui::egui::{Color32, TextFormat};
         Self {
             dark_mode: false,
-            #[cfg(not(feature = "syntect"))]
+            #[cfg(not(feature = "syntax_highlighting"))]
             formats: enum_map::enum_map![
                 TokenType::Comment => TextFormat::simple(font_id.clone(), Color32::GRAY),
                 TokenType::Keyword => TextFormat::simple(font_id.clone(), Color32::from_rgb(235, 0, 0)),
@@ -294,13 +294,13 @@ impl CodeTheme {
 
 // ----------------------------------------------------------------------------
 
-#[cfg(feature = "syntect")]
+#[cfg(feature = "syntax_highlighting")]
 struct Highlighter {
     ps: syntect::parsing::SyntaxSet,
     ts: syntect::highlighting::ThemeSet,
 }
 
-#[cfg(feature = "syntect")]
+#[cfg(feature = "syntax_highlighting")]
 impl Default for Highlighter {
     fn default() -> Self {
         Self {
@@ -310,7 +310,7 @@ impl Default for Highlighter {
     }
 }
 
-#[cfg(feature = "syntect")]
+#[cfg(feature = "syntax_highlighting")]
 impl Highlighter {
     #[allow(clippy::unused_self, clippy::unnecessary_wraps)]
     fn highlight(&self, theme: &CodeTheme, code: &str, lang: &str) -> LayoutJob {
@@ -378,7 +378,7 @@ impl Highlighter {
     }
 }
 
-#[cfg(feature = "syntect")]
+#[cfg(feature = "syntax_highlighting")]
 fn as_byte_range(whole: &str, range: &str) -> std::ops::Range<usize> {
     let whole_start = whole.as_ptr() as usize;
     let range_start = range.as_ptr() as usize;
@@ -390,11 +390,11 @@ fn as_byte_range(whole: &str, range: &str) -> std::ops::Range<usize> {
 
 // ----------------------------------------------------------------------------
 
-#[cfg(not(feature = "syntect"))]
+#[cfg(not(feature = "syntax_highlighting"))]
 #[derive(Default)]
 struct Highlighter {}
 
-#[cfg(not(feature = "syntect"))]
+#[cfg(not(feature = "syntax_highlighting"))]
 impl Highlighter {
     #[allow(clippy::unused_self, clippy::unnecessary_wraps)]
     fn highlight(&self, theme: &CodeTheme, mut text: &str, _language: &str) -> LayoutJob {
@@ -458,7 +458,7 @@ impl Highlighter {
     }
 }
 
-#[cfg(not(feature = "syntect"))]
+#[cfg(not(feature = "syntax_highlighting"))]
 fn is_keyword(word: &str) -> bool {
     matches!(
         word,
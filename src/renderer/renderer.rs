@@ -1,10 +1,12 @@
 use std::sync::Arc;
 use bevy::prelude::*;
 use bevy_egui::{EguiPlugin};
+use crate::common::NeatFloat;
 use crate::neat::genome::neat::NeatGenome;
 use crate::neat::population::GenerationMember;
 use crate::neat::trainer::configuration::Configuration;
 use crate::neat::trainer::generation_stats::GenerationStats;
+use crate::neat::trainer::fitness::evaluation_times::EvaluationTimes;
 use crate::neat::trainer::neat_trainer_host::from_host_events::FromHostEvent;
 use crate::neat::trainer::neat_trainer_host::models::generic_operation::GenericOperation;
 use crate::neat::trainer::neat_trainer_host::models::species::SpeciesList;
@@ -22,6 +24,8 @@ pub struct NeatTrainerState{
     pub best_member_so_far: Option<GenerationMember<NeatGenome>>,
     pub species_list: SpeciesList,
     pub last_ten_thousand_generations_stats: Vec<GenerationStats>,
+    pub fitness_cache_hit_rate: NeatFloat,
+    pub evaluation_times: EvaluationTimes,
     pub config_regulators: Vec<ConfigRegulator>,
     pub generic_operations_queue: Vec<GenericOperation>
 }
@@ -47,6 +51,8 @@ pub fn gui_runner<T>(neat_trainer_host: NeatTrainerHost, trainer_host_client: Ne
         configuration: neat_trainer_host.initial_configuration.clone(),
         current_generation: 0,
         last_ten_thousand_generations_stats: Vec::new(),
+        fitness_cache_hit_rate: 0.0,
+        evaluation_times: EvaluationTimes::default(),
         reset_requested: false,
         species_list: SpeciesList { species_models: Vec::new() },
         config_regulators: neat_trainer_host.initial_config_regulators,
@@ -76,6 +82,8 @@ fn client_update_system(trainer_client: NonSend<NeatTrainerHostClient>,mut train
             FromHostEvent::RunStats(new_stats) => {
                 trainer_state.last_ten_thousand_generations_stats = new_stats.last_ten_thousand_generations_stats;
                 trainer_state.species_list = new_stats.species_list;
+                trainer_state.fitness_cache_hit_rate = new_stats.fitness_cache_hit_rate;
+                trainer_state.evaluation_times = new_stats.evaluation_times;
             },
             FromHostEvent::HitSuccessThreshold(gen) => {
                 trainer_state.run_until = Some(gen);
@@ -83,6 +91,15 @@ fn client_update_system(trainer_client: NonSend<NeatTrainerHostClient>,mut train
             FromHostEvent::SetRunUntil(gen) => {
                 trainer_state.run_until = Some(gen);
             },
+            FromHostEvent::FitnessFailure(id, failure) => {
+                println!("{} for member {}", failure, id);
+            },
+            FromHostEvent::CheckpointError(error) => {
+                println!("Checkpoint Error: {}", error);
+            },
+            FromHostEvent::CurriculumStageChange(stage, name) => {
+                println!("curriculum stage {}: {}", stage, name);
+            },
         }
     }
 